@@ -0,0 +1,171 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use stake_client::accounts::{decode_program_account, fungstake, vault, ProgramAccount};
+
+fn header(kind: &str, address: &Pubkey) {
+    println!("{kind} {address}");
+}
+
+fn field(name: &str, value: impl Display) {
+    println!("  {name:<34} {value}");
+}
+
+pub fn vault_stake_config(address: &Pubkey, config: &vault::StakeConfig) {
+    header("StakeConfig", address);
+    field("authority", config.authority);
+    field("stake_currency_mint", config.stake_currency_mint);
+    field("min_lock_period", config.min_lock_period);
+    field("max_lock_period", config.max_lock_period);
+    field("ve_bias", config.ve_bias);
+    field("ve_slope", config.ve_slope);
+    field("ve_checkpoint_at", config.ve_checkpoint_at);
+    field("ve_fixed_power", config.ve_fixed_power);
+    field("receipt_uri", &config.receipt_uri);
+    field("vote_lock_period", config.vote_lock_period);
+}
+
+pub fn vault_vault(address: &Pubkey, vault: &vault::Vault) {
+    header("Vault", address);
+    field("vault_config", vault.vault_config);
+    field("lock_period", vault.lock_period);
+    field("total_staked", vault.total_staked);
+    field("total_unbonding", vault.total_unbonding);
+    field("undistributed_penalty", vault.undistributed_penalty);
+    field("is_active", vault.is_active);
+    field("min_stake_amount", vault.min_stake_amount);
+    field("max_total_staked", vault.max_total_staked);
+    field("max_stake_per_staker", vault.max_stake_per_staker);
+    field("allow_early_unstake", vault.allow_early_unstake);
+    field(
+        "max_early_unstake_penalty_bps",
+        vault.max_early_unstake_penalty_bps,
+    );
+    field("redistribute_penalty", vault.redistribute_penalty);
+    field("treasury", vault.treasury);
+    field("acc_penalty_per_share", vault.acc_penalty_per_share);
+    field("unbonding_period", vault.unbonding_period);
+    field("renew_window", vault.renew_window);
+}
+
+pub fn vault_staker_info(address: &Pubkey, staker_info: &vault::StakerInfo) {
+    header("StakerInfo", address);
+    field("total_stake", staker_info.total_stake);
+    field("current_id", staker_info.current_id);
+    field("checkpoint_count", staker_info.checkpoint_count);
+    field("open_positions", staker_info.open_positions);
+}
+
+pub fn vault_stake_detail(address: &Pubkey, detail: &vault::StakeDetail) {
+    header("StakeDetail", address);
+    field("id", detail.id);
+    field("staker", detail.staker);
+    field("stake_amount", detail.stake_amount);
+    field("unstaked_at_time", detail.unstaked_at_time);
+    field("penalty_debt", detail.penalty_debt);
+    field("receipt_mint", detail.receipt_mint);
+    field("unbonding_started_at", detail.unbonding_started_at);
+    field("auto_renew", detail.auto_renew);
+    field("renew_window", detail.renew_window);
+    field("vote_locked_until", detail.vote_locked_until);
+    field("grantor", detail.grantor);
+    field("revocable", detail.revocable);
+    field("vesting_start", detail.vesting_start);
+    field("ve_slope", detail.ve_slope);
+    field("ve_end", detail.ve_end);
+    field("delegate_to", detail.delegate_to);
+    field(
+        "delegation_commission_bps",
+        detail.delegation_commission_bps,
+    );
+}
+
+pub fn fungstake_stake_config(address: &Pubkey, config: &fungstake::StakeConfig) {
+    header("StakeConfig", address);
+    field("authority", config.authority);
+    field("stake_currency_mint", config.stake_currency_mint);
+    field("lock_period", config.lock_period);
+    field("lock_extend_time", config.lock_extend_time);
+    field("soft_cap", config.soft_cap);
+    if config.has_price_feed() {
+        field("price_feed", config.price_feed);
+        field("soft_cap_usd", config.soft_cap_usd);
+        field("max_price_age", config.max_price_age);
+        field("max_confidence_bps", config.max_confidence_bps);
+    }
+    if config.has_kyc_attestor() {
+        field("kyc_attestor", config.kyc_attestor);
+    }
+}
+
+pub fn fungstake_vault(address: &Pubkey, vault: &fungstake::Vault) {
+    header("Vault", address);
+    field("reward_currency_mint", vault.reward_currency_mint);
+    field("total_staked", vault.total_staked);
+    field("end_time", vault.end_time);
+    field("reach_soft_cap", vault.reach_soft_cap);
+    field("total_reward", vault.total_reward);
+    field("reach_tge", vault.reach_tge);
+    field("claimed_snapshot_amount", vault.claimed_snapshot_amount);
+    field("destaked_after_end", vault.destaked_after_end);
+    if vault.is_sale() {
+        field("sale_rate", vault.sale_rate);
+        field("sale_allocation", vault.sale_allocation);
+        field("sale_proceeds", vault.sale_proceeds);
+        field("withdrawn_proceeds", vault.withdrawn_proceeds);
+    }
+    for (index, phase) in vault.phases[..vault.phase_count as usize]
+        .iter()
+        .enumerate()
+    {
+        println!("  phase {index}");
+        field("  start_time", phase.start_time);
+        field("  end_time", phase.end_time);
+        field("  wallet_cap", phase.wallet_cap);
+        field("  weight", phase.weight);
+        field("  requires_allowlist", phase.requires_allowlist);
+        field("  total_staked", phase.total_staked);
+        field("  claimed_snapshot_amount", phase.claimed_snapshot_amount);
+    }
+}
+
+pub fn fungstake_stake_info(address: &Pubkey, stake_info: &fungstake::StakeInfo) {
+    header("StakeInfo", address);
+    field("stake_amount", stake_info.stake_amount);
+    field("snapshot_amount", stake_info.snapshot_amount);
+    field("unstaked_at_time", stake_info.unstaked_at_time);
+    field("has_claimed", stake_info.has_claimed);
+    for (index, amount) in stake_info.phase_snapshot_amounts.iter().enumerate() {
+        if *amount > 0 {
+            field(&format!("phase {index} snapshot_amount"), amount);
+        }
+    }
+}
+
+/// print any account of both programs, the type is found from its owner and discriminator
+pub fn account(address: &Pubkey, owner: &Pubkey, data: &[u8]) -> Result<()> {
+    match decode_program_account(owner, data)? {
+        Some(ProgramAccount::VaultStakeConfig(config)) => vault_stake_config(address, &config),
+        Some(ProgramAccount::VaultVault(vault)) => vault_vault(address, &vault),
+        Some(ProgramAccount::VaultStakerInfo(staker_info)) => {
+            vault_staker_info(address, &staker_info)
+        }
+        Some(ProgramAccount::VaultStakeDetail(detail)) => vault_stake_detail(address, &detail),
+        Some(ProgramAccount::FungstakeStakeConfig(config)) => {
+            fungstake_stake_config(address, &config)
+        }
+        Some(ProgramAccount::FungstakeVault(vault)) => fungstake_vault(address, &vault),
+        Some(ProgramAccount::FungstakeStakeInfo(stake_info)) => {
+            fungstake_stake_info(address, &stake_info)
+        }
+        None => unknown(address, owner, data),
+    }
+    Ok(())
+}
+
+fn unknown(address: &Pubkey, owner: &Pubkey, data: &[u8]) {
+    header("unknown account", address);
+    field("owner", owner);
+    field("data_len", data.len());
+}
@@ -0,0 +1,324 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::Subcommand;
+use solana_sdk::pubkey::Pubkey;
+use stake_client::{
+    accounts::fungstake, fungstake_program, instructions::fungstake as ix, pda::fungstake as pda,
+};
+
+use crate::{display, source::Source, tx::TxArgs};
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// create the StakeConfig of a mint
+    Init {
+        #[arg(long)]
+        mint: Pubkey,
+        /// seconds after staking before rewards can be claimed
+        #[arg(long)]
+        lock_period: u32,
+        /// seconds the lock is extended by once the soft cap is reached
+        #[arg(long)]
+        lock_extend_time: u32,
+        #[arg(long)]
+        soft_cap: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// create the vault paying rewards in `reward_mint`
+    CreateVault {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        reward_mint: Pubkey,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// evaluate the soft cap in USD from a Pyth price account, or back in tokens without --price-feed
+    SetPriceFeed {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        price_feed: Option<Pubkey>,
+        /// soft cap in USD with 6 decimals
+        #[arg(long, default_value_t = 0)]
+        soft_cap_usd: u64,
+        /// max seconds since the price was published
+        #[arg(long, default_value_t = 60)]
+        max_price_age: u32,
+        /// max confidence interval relative to the price, in basis points
+        #[arg(long, default_value_t = 100)]
+        max_confidence_bps: u16,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// require stakes to carry an attestation signed by the KYC service key, or stop
+    /// requiring them without --attestor
+    SetKycAttestor {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        attestor: Option<Pubkey>,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// add the next launch phase of a vault or update phase `phase_id`
+    SetPhase {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        reward_mint: Pubkey,
+        #[arg(long)]
+        phase_id: u8,
+        /// unix timestamp the phase opens at
+        #[arg(long)]
+        start_time: i64,
+        /// unix timestamp the phase closes at
+        #[arg(long)]
+        end_time: i64,
+        /// max stake of a wallet in the phase, 0 for no cap
+        #[arg(long, default_value_t = 0)]
+        wallet_cap: u64,
+        /// share of the reward relative to the other phases
+        #[arg(long)]
+        weight: u16,
+        #[arg(long)]
+        requires_allowlist: bool,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// allow or disallow wallets to stake in a phase with an allowlist
+    SetAllowlist {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        reward_mint: Pubkey,
+        #[arg(long)]
+        phase_id: u8,
+        #[arg(long = "wallet", required = true)]
+        wallets: Vec<Pubkey>,
+        #[arg(long)]
+        disallow: bool,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// sell the reward at a fixed rate and refund unspent stake when oversubscribed,
+    /// or share the reward pro-rata again with both set to 0
+    SetSaleTerms {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        reward_mint: Pubkey,
+        /// reward tokens per staking token, scaled by 10^9
+        #[arg(long)]
+        sale_rate: u64,
+        /// reward tokens for sale
+        #[arg(long)]
+        sale_allocation: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// withdraw the stake spent on the sale by the stakers who claimed
+    WithdrawSaleProceeds {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        reward_mint: Pubkey,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// grow a StakeConfig created with an older layout
+    MigrateStakeConfig {
+        #[arg(long)]
+        mint: Pubkey,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// grow a vault created with an older layout, after migrate-stake-config
+    MigrateVault {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        reward_mint: Pubkey,
+        /// the vault reached tge, its reward token account is read for the claimed rewards
+        #[arg(long)]
+        reach_tge: bool,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// list the vaults of a mint and the stakes of the given owners.
+    /// StakeInfo does not store its owner, so stakes can only be found for known wallets
+    List {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long = "owner")]
+        owners: Vec<Pubkey>,
+        /// read account dumps instead of the cluster, see `stake_client::dump`
+        #[arg(long)]
+        accounts_dir: Option<PathBuf>,
+    },
+}
+
+pub fn run(url: &str, command: Command) -> Result<()> {
+    match command {
+        Command::Init {
+            mint,
+            lock_period,
+            lock_extend_time,
+            soft_cap,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::initialize(
+                authority,
+                &mint,
+                lock_period,
+                lock_extend_time,
+                soft_cap,
+            )]
+        }),
+        Command::CreateVault {
+            mint,
+            reward_mint,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::create_vault(authority, &mint, &reward_mint)]
+        }),
+        Command::SetPriceFeed {
+            mint,
+            price_feed,
+            soft_cap_usd,
+            max_price_age,
+            max_confidence_bps,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::set_price_feed(
+                authority,
+                &mint,
+                price_feed,
+                soft_cap_usd,
+                max_price_age,
+                max_confidence_bps,
+            )]
+        }),
+        Command::SetKycAttestor { mint, attestor, tx } => tx.run(url, |authority| {
+            vec![ix::set_kyc_attestor(
+                authority,
+                &mint,
+                &attestor.unwrap_or_default(),
+            )]
+        }),
+        Command::SetPhase {
+            mint,
+            reward_mint,
+            phase_id,
+            start_time,
+            end_time,
+            wallet_cap,
+            weight,
+            requires_allowlist,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::set_phase(
+                authority,
+                &mint,
+                &reward_mint,
+                phase_id,
+                start_time,
+                end_time,
+                wallet_cap,
+                weight,
+                requires_allowlist,
+            )]
+        }),
+        Command::SetAllowlist {
+            mint,
+            reward_mint,
+            phase_id,
+            wallets,
+            disallow,
+            tx,
+        } => tx.run(url, |authority| {
+            wallets
+                .iter()
+                .map(|wallet| {
+                    ix::set_allowlist_entry(
+                        authority,
+                        &mint,
+                        &reward_mint,
+                        phase_id,
+                        wallet,
+                        !disallow,
+                    )
+                })
+                .collect()
+        }),
+        Command::SetSaleTerms {
+            mint,
+            reward_mint,
+            sale_rate,
+            sale_allocation,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::set_sale_terms(
+                authority,
+                &mint,
+                &reward_mint,
+                sale_rate,
+                sale_allocation,
+            )]
+        }),
+        Command::WithdrawSaleProceeds {
+            mint,
+            reward_mint,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::withdraw_sale_proceeds(authority, &mint, &reward_mint)]
+        }),
+        Command::MigrateStakeConfig { mint, tx } => tx.run(url, |authority| {
+            vec![ix::migrate_stake_config(authority, &mint)]
+        }),
+        Command::MigrateVault {
+            mint,
+            reward_mint,
+            reach_tge,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::migrate_vault(authority, &mint, &reward_mint, reach_tge)]
+        }),
+        Command::List {
+            mint,
+            owners,
+            accounts_dir,
+        } => list(&Source::new(url, accounts_dir.as_ref())?, &mint, &owners),
+    }
+}
+
+fn list(source: &Source, mint: &Pubkey, owners: &[Pubkey]) -> Result<()> {
+    let stake_config_key = pda::stake_config(mint);
+    let Some(stake_config) = source.account::<fungstake::StakeConfig>(&stake_config_key)? else {
+        bail!("no StakeConfig {stake_config_key} for mint {mint}");
+    };
+    display::fungstake_stake_config(&stake_config_key, &stake_config);
+
+    // vaults do not store their config, they are matched by deriving their address
+    let vaults = source
+        .program_accounts::<fungstake::Vault>(&fungstake_program::ID)?
+        .into_iter()
+        .filter(|(key, vault)| pda::vault(&stake_config_key, &vault.reward_currency_mint) == *key);
+
+    for (vault_key, vault) in vaults {
+        println!();
+        display::fungstake_vault(&vault_key, &vault);
+
+        for owner in owners {
+            let stake_info_key = pda::stake_info(&vault_key, owner);
+            if let Some(stake_info) = source.account::<fungstake::StakeInfo>(&stake_info_key)? {
+                println!();
+                println!("owner {owner}");
+                display::fungstake_stake_info(&stake_info_key, &stake_info);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! `stakectl`, admin and inspection tool for the vault and fungstake programs.
+//!
+//! Admin commands send the transaction with `--keypair`, print it signed with
+//! `--keypair --sign-only`, or print it unsigned with `--authority`. Add `--blockhash`
+//! to build it without RPC access. Read commands use the cluster at `--url`, or a
+//! directory of account dumps (`solana account --output json` or saved
+//! `getProgramAccounts` responses) with `--accounts-dir`.
+
+mod display;
+mod fungstake;
+mod source;
+mod tx;
+mod vault;
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use solana_sdk::pubkey::Pubkey;
+use stake_client::dump::load_account_file;
+
+use crate::source::Source;
+
+#[derive(Parser)]
+#[command(name = "stakectl", version, about)]
+struct Cli {
+    /// RPC endpoint, a local validator by default
+    #[arg(long, short, global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// vault program, lock period vaults
+    #[command(subcommand)]
+    Vault(vault::Command),
+    /// fungstake program, token launch staking
+    #[command(subcommand)]
+    Fungstake(fungstake::Command),
+    /// pretty-print an account of either program
+    Show {
+        address: Option<Pubkey>,
+        /// `solana account --output json` file to read instead of the cluster
+        #[arg(long, conflicts_with = "address")]
+        file: Option<PathBuf>,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Vault(command) => vault::run(&cli.url, command),
+        Command::Fungstake(command) => fungstake::run(&cli.url, command),
+        Command::Show { address, file } => {
+            let (address, owner, data) = match (address, file) {
+                (_, Some(file)) => {
+                    let account = load_account_file(&file)?;
+                    (account.pubkey, account.owner, account.data)
+                }
+                (Some(address), None) => {
+                    match Source::new(&cli.url, None)?.raw_account(&address)? {
+                        Some((owner, data)) => (address, owner, data),
+                        None => bail!("account {address} not found"),
+                    }
+                }
+                (None, None) => bail!("either an address or --file is required"),
+            };
+            display::account(&address, &owner, &data)
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{Context, Result};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::pubkey::Pubkey;
+use stake_client::dump::{load_account_dir, DumpedAccount};
+
+/// where accounts are read from: a live cluster or a directory of dumped account files
+pub enum Source {
+    Rpc(RpcClient),
+    Dump(Vec<DumpedAccount>),
+}
+
+impl Source {
+    pub fn new(url: &str, accounts_dir: Option<&PathBuf>) -> Result<Self> {
+        match accounts_dir {
+            Some(dir) => Ok(Source::Dump(
+                load_account_dir(dir).with_context(|| format!("reading {}", dir.display()))?,
+            )),
+            None => Ok(Source::Rpc(RpcClient::new(url.to_string()))),
+        }
+    }
+
+    /// owner program and data of an account, None if it does not exist
+    pub fn raw_account(&self, address: &Pubkey) -> Result<Option<(Pubkey, Vec<u8>)>> {
+        match self {
+            Source::Rpc(rpc) => {
+                let account = rpc.get_account_with_commitment(address, rpc.commitment())?;
+                Ok(account.value.map(|account| (account.owner, account.data)))
+            }
+            Source::Dump(accounts) => Ok(accounts
+                .iter()
+                .find(|account| account.pubkey == *address)
+                .map(|account| (account.owner, account.data.clone()))),
+        }
+    }
+
+    pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        self.raw_account(address)?
+            .map(|(_, data)| {
+                stake_client::accounts::decode(&data)
+                    .with_context(|| format!("decoding account {address}"))
+            })
+            .transpose()
+    }
+
+    /// every account of type `T` owned by `program_id`
+    pub fn program_accounts<T: AccountDeserialize + Discriminator>(
+        &self,
+        program_id: &Pubkey,
+    ) -> Result<Vec<(Pubkey, T)>> {
+        let raw = match self {
+            Source::Rpc(rpc) => rpc
+                .get_program_accounts_with_config(
+                    program_id,
+                    RpcProgramAccountsConfig {
+                        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                            0,
+                            T::DISCRIMINATOR.to_vec(),
+                        ))]),
+                        account_config: RpcAccountInfoConfig {
+                            encoding: Some(UiAccountEncoding::Base64),
+                            ..RpcAccountInfoConfig::default()
+                        },
+                        ..RpcProgramAccountsConfig::default()
+                    },
+                )?
+                .into_iter()
+                .map(|(address, account)| (address, account.data))
+                .collect::<Vec<_>>(),
+            Source::Dump(accounts) => accounts
+                .iter()
+                .filter(|account| {
+                    account.owner == *program_id && account.data.starts_with(&T::DISCRIMINATOR)
+                })
+                .map(|account| (account.pubkey, account.data.clone()))
+                .collect(),
+        };
+
+        raw.into_iter()
+            .map(|(address, data)| {
+                let decoded = stake_client::accounts::decode(&data)
+                    .with_context(|| format!("decoding account {address}"))?;
+                Ok((address, decoded))
+            })
+            .collect()
+    }
+}
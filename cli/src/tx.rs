@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::Args;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    hash::Hash, instruction::Instruction, pubkey::Pubkey, signature::read_keypair_file,
+    signer::Signer, transaction::Transaction,
+};
+
+/// how an admin transaction is signed and where it goes
+#[derive(Args)]
+pub struct TxArgs {
+    /// keypair of the authority, it also pays the fee
+    #[arg(long)]
+    keypair: Option<PathBuf>,
+    /// build an unsigned transaction for this authority instead of signing it
+    #[arg(long, conflicts_with = "keypair")]
+    authority: Option<Pubkey>,
+    /// recent blockhash, lets the transaction be built without RPC access
+    #[arg(long)]
+    blockhash: Option<Hash>,
+    /// print the signed transaction instead of sending it
+    #[arg(long, requires = "keypair", conflicts_with = "authority")]
+    sign_only: bool,
+}
+
+impl TxArgs {
+    /// send the instructions built for the authority, or print the transaction as base64.
+    /// Printed transactions can be sent later with `solana` tooling or another signer
+    pub fn run(&self, url: &str, build: impl FnOnce(&Pubkey) -> Vec<Instruction>) -> Result<()> {
+        let keypair = match &self.keypair {
+            Some(path) => Some(
+                read_keypair_file(path)
+                    .map_err(|err| anyhow::anyhow!("reading keypair {}: {err}", path.display()))?,
+            ),
+            None => None,
+        };
+        let authority = match (&keypair, self.authority) {
+            (Some(keypair), _) => keypair.pubkey(),
+            (None, Some(authority)) => authority,
+            (None, None) => bail!("either --keypair or --authority is required"),
+        };
+
+        let instructions = build(&authority);
+        let rpc = RpcClient::new(url.to_string());
+        let blockhash = match self.blockhash {
+            Some(blockhash) => blockhash,
+            None => rpc.get_latest_blockhash()?,
+        };
+
+        let mut tx = Transaction::new_with_payer(&instructions, Some(&authority));
+        match keypair {
+            Some(keypair) => {
+                tx.sign(&[&keypair], blockhash);
+                if self.sign_only {
+                    print_tx(&tx)
+                } else {
+                    let signature = rpc.send_and_confirm_transaction(&tx)?;
+                    println!("{signature}");
+                    Ok(())
+                }
+            }
+            None => {
+                tx.message.recent_blockhash = blockhash;
+                print_tx(&tx)
+            }
+        }
+    }
+}
+
+fn print_tx(tx: &Transaction) -> Result<()> {
+    println!("{}", STANDARD.encode(bincode::serialize(tx)?));
+    Ok(())
+}
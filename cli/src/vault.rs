@@ -0,0 +1,317 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::Subcommand;
+use solana_sdk::pubkey::Pubkey;
+use stake_client::{accounts::vault, instructions::vault as ix, pda::vault as pda, vault_program};
+
+use crate::{display, source::Source, tx::TxArgs};
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// create the StakeConfig of a mint
+    Init {
+        #[arg(long)]
+        mint: Pubkey,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// create the vault of a lock period
+    CreateVault {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        lock_period: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// open or close a vault to new stakes
+    SetVaultStatus {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        lock_period: u64,
+        #[arg(long, action = clap::ArgAction::Set)]
+        is_active: bool,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// set the caps of a vault, 0 means unlimited
+    SetVaultLimits {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        lock_period: u64,
+        #[arg(long)]
+        max_total_staked: u64,
+        #[arg(long)]
+        max_stake_per_staker: u64,
+        #[arg(long)]
+        min_stake_amount: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// set the lock periods accepted by create-vault
+    SetLockPeriodBounds {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        min_lock_period: u64,
+        #[arg(long)]
+        max_lock_period: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// set the base uri of position receipt metadata
+    SetReceiptUri {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        receipt_uri: String,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// set how long positions used in a vote stay locked, in seconds
+    SetVoteLockPeriod {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        vote_lock_period: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// set the early unstake penalty of a vault
+    SetEarlyUnstakeConfig {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        lock_period: u64,
+        #[arg(long, action = clap::ArgAction::Set)]
+        allow_early_unstake: bool,
+        #[arg(long)]
+        max_early_unstake_penalty_bps: u16,
+        #[arg(long, action = clap::ArgAction::Set)]
+        redistribute_penalty: bool,
+        #[arg(long)]
+        treasury: Pubkey,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// set the waiting time between request_unstake and withdraw
+    SetUnbondingPeriod {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        lock_period: u64,
+        #[arg(long)]
+        unbonding_period: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// set the withdraw window of auto-renewed positions
+    SetRenewWindow {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        lock_period: u64,
+        #[arg(long)]
+        renew_window: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// grow a StakeConfig created with an older layout
+    MigrateStakeConfig {
+        #[arg(long)]
+        mint: Pubkey,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// grow a vault created with an older layout, after migrate-stake-config
+    MigrateVault {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        lock_period: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+    /// list the vaults of a mint and their positions
+    List {
+        #[arg(long)]
+        mint: Pubkey,
+        /// only list positions of this staker
+        #[arg(long)]
+        owner: Option<Pubkey>,
+        /// read account dumps instead of the cluster, see `stake_client::dump`
+        #[arg(long)]
+        accounts_dir: Option<PathBuf>,
+    },
+}
+
+pub fn run(url: &str, command: Command) -> Result<()> {
+    match command {
+        Command::Init { mint, tx } => {
+            tx.run(url, |authority| vec![ix::initialize(authority, &mint)])
+        }
+        Command::CreateVault {
+            mint,
+            lock_period,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::create_vault(authority, &mint, lock_period)]
+        }),
+        Command::SetVaultStatus {
+            mint,
+            lock_period,
+            is_active,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::set_vault_status(
+                authority,
+                &mint,
+                lock_period,
+                is_active,
+            )]
+        }),
+        Command::SetVaultLimits {
+            mint,
+            lock_period,
+            max_total_staked,
+            max_stake_per_staker,
+            min_stake_amount,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::set_vault_limits(
+                authority,
+                &mint,
+                lock_period,
+                max_total_staked,
+                max_stake_per_staker,
+                min_stake_amount,
+            )]
+        }),
+        Command::SetLockPeriodBounds {
+            mint,
+            min_lock_period,
+            max_lock_period,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::set_lock_period_bounds(
+                authority,
+                &mint,
+                min_lock_period,
+                max_lock_period,
+            )]
+        }),
+        Command::SetReceiptUri {
+            mint,
+            receipt_uri,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::set_receipt_uri(authority, &mint, receipt_uri)]
+        }),
+        Command::SetVoteLockPeriod {
+            mint,
+            vote_lock_period,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::set_vote_lock_period(authority, &mint, vote_lock_period)]
+        }),
+        Command::SetEarlyUnstakeConfig {
+            mint,
+            lock_period,
+            allow_early_unstake,
+            max_early_unstake_penalty_bps,
+            redistribute_penalty,
+            treasury,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::set_early_unstake_config(
+                authority,
+                &mint,
+                lock_period,
+                allow_early_unstake,
+                max_early_unstake_penalty_bps,
+                redistribute_penalty,
+                treasury,
+            )]
+        }),
+        Command::SetUnbondingPeriod {
+            mint,
+            lock_period,
+            unbonding_period,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::set_unbonding_period(
+                authority,
+                &mint,
+                lock_period,
+                unbonding_period,
+            )]
+        }),
+        Command::SetRenewWindow {
+            mint,
+            lock_period,
+            renew_window,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::set_renew_window(
+                authority,
+                &mint,
+                lock_period,
+                renew_window,
+            )]
+        }),
+        Command::MigrateStakeConfig { mint, tx } => tx.run(url, |authority| {
+            vec![ix::migrate_stake_config(authority, &mint)]
+        }),
+        Command::MigrateVault {
+            mint,
+            lock_period,
+            tx,
+        } => tx.run(url, |authority| {
+            vec![ix::migrate_vault(authority, &mint, lock_period)]
+        }),
+        Command::List {
+            mint,
+            owner,
+            accounts_dir,
+        } => list(&Source::new(url, accounts_dir.as_ref())?, &mint, owner),
+    }
+}
+
+fn list(source: &Source, mint: &Pubkey, owner: Option<Pubkey>) -> Result<()> {
+    let stake_config_key = pda::stake_config(mint);
+    let Some(stake_config) = source.account::<vault::StakeConfig>(&stake_config_key)? else {
+        bail!("no StakeConfig {stake_config_key} for mint {mint}");
+    };
+    display::vault_stake_config(&stake_config_key, &stake_config);
+
+    let mut vaults: Vec<_> = source
+        .program_accounts::<vault::Vault>(&vault_program::ID)?
+        .into_iter()
+        .filter(|(_, vault)| vault.vault_config == stake_config_key)
+        .collect();
+    vaults.sort_by_key(|(_, vault)| vault.lock_period);
+
+    let details: Vec<_> = source
+        .program_accounts::<vault::StakeDetail>(&vault_program::ID)?
+        .into_iter()
+        .filter(|(_, detail)| owner.is_none_or(|owner| detail.staker == owner))
+        .collect();
+
+    for (vault_key, vault) in &vaults {
+        println!();
+        display::vault_vault(vault_key, vault);
+
+        // stake details only store their staker, the vault is found by deriving their address
+        for (detail_key, detail) in &details {
+            let staker_info = pda::staker_info(vault_key, &detail.staker);
+            if pda::stake_detail(&staker_info, detail.id) == *detail_key {
+                println!();
+                display::vault_stake_detail(detail_key, detail);
+            }
+        }
+    }
+    Ok(())
+}
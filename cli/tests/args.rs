@@ -0,0 +1,295 @@
+mod common;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use common::{failure, success, temp_dir};
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{write_keypair_file, Keypair},
+    signer::Signer,
+    transaction::Transaction,
+};
+use stake_client::{
+    instructions::{fungstake, vault},
+    test_utils::key,
+};
+
+fn blockhash() -> Hash {
+    Hash::new_from_array([9; 32])
+}
+
+fn printed_tx(stdout: &str) -> Transaction {
+    bincode::deserialize(&STANDARD.decode(stdout.trim()).unwrap()).unwrap()
+}
+
+/// the unsigned transaction printed for `--authority`, without RPC access
+fn unsigned(args: &[&str], authority: &Pubkey) -> Transaction {
+    let blockhash = blockhash().to_string();
+    let authority = authority.to_string();
+    let mut args = args.to_vec();
+    args.extend(["--authority", &authority, "--blockhash", &blockhash]);
+    printed_tx(&success(&args))
+}
+
+fn assert_builds(args: &[&str], expected: Vec<Instruction>) {
+    let authority = key(1);
+    let tx = unsigned(args, &authority);
+    let mut expected = Transaction::new_with_payer(&expected, Some(&authority));
+    expected.message.recent_blockhash = blockhash();
+    assert_eq!(tx, expected, "{args:?}");
+}
+
+#[test]
+fn vault_commands_build_their_instruction() {
+    let (authority, mint, treasury) = (key(1), key(2), key(3));
+    let mint_arg = mint.to_string();
+    let treasury_arg = treasury.to_string();
+
+    assert_builds(
+        &["vault", "init", "--mint", &mint_arg],
+        vec![vault::initialize(&authority, &mint)],
+    );
+    // booleans take an explicit value so a vault can be closed
+    assert_builds(
+        &[
+            "vault",
+            "set-vault-status",
+            "--mint",
+            &mint_arg,
+            "--lock-period",
+            "86400",
+            "--is-active",
+            "false",
+        ],
+        vec![vault::set_vault_status(&authority, &mint, 86_400, false)],
+    );
+    assert_builds(
+        &[
+            "vault",
+            "set-early-unstake-config",
+            "--mint",
+            &mint_arg,
+            "--lock-period",
+            "60",
+            "--allow-early-unstake",
+            "true",
+            "--max-early-unstake-penalty-bps",
+            "2500",
+            "--redistribute-penalty",
+            "false",
+            "--treasury",
+            &treasury_arg,
+        ],
+        vec![vault::set_early_unstake_config(
+            &authority, &mint, 60, true, 2_500, false, treasury,
+        )],
+    );
+    assert_builds(
+        &[
+            "vault",
+            "set-receipt-uri",
+            "--mint",
+            &mint_arg,
+            "--receipt-uri",
+            "https://example.com/receipts/",
+        ],
+        vec![vault::set_receipt_uri(
+            &authority,
+            &mint,
+            "https://example.com/receipts/".to_string(),
+        )],
+    );
+    assert_builds(
+        &[
+            "vault",
+            "migrate-vault",
+            "--mint",
+            &mint_arg,
+            "--lock-period",
+            "60",
+        ],
+        vec![vault::migrate_vault(&authority, &mint, 60)],
+    );
+}
+
+#[test]
+fn fungstake_commands_build_their_instructions() {
+    let (authority, mint, reward_mint) = (key(1), key(2), key(3));
+    let (mint_arg, reward_mint_arg) = (mint.to_string(), reward_mint.to_string());
+
+    // without --price-feed the soft cap is in tokens again, the other values are defaults
+    assert_builds(
+        &["fungstake", "set-price-feed", "--mint", &mint_arg],
+        vec![fungstake::set_price_feed(
+            &authority, &mint, None, 0, 60, 100,
+        )],
+    );
+    assert_builds(
+        &["fungstake", "set-kyc-attestor", "--mint", &mint_arg],
+        vec![fungstake::set_kyc_attestor(
+            &authority,
+            &mint,
+            &Pubkey::default(),
+        )],
+    );
+    assert_builds(
+        &[
+            "fungstake",
+            "set-phase",
+            "--mint",
+            &mint_arg,
+            "--reward-mint",
+            &reward_mint_arg,
+            "--phase-id",
+            "1",
+            "--start-time",
+            "100",
+            "--end-time",
+            "200",
+            "--weight",
+            "3",
+            "--requires-allowlist",
+        ],
+        vec![fungstake::set_phase(
+            &authority,
+            &mint,
+            &reward_mint,
+            1,
+            100,
+            200,
+            0,
+            3,
+            true,
+        )],
+    );
+
+    // one instruction per wallet
+    let wallets = [key(4), key(5)];
+    assert_builds(
+        &[
+            "fungstake",
+            "set-allowlist",
+            "--mint",
+            &mint_arg,
+            "--reward-mint",
+            &reward_mint_arg,
+            "--phase-id",
+            "0",
+            "--wallet",
+            &wallets[0].to_string(),
+            "--wallet",
+            &wallets[1].to_string(),
+            "--disallow",
+        ],
+        wallets
+            .iter()
+            .map(|wallet| {
+                fungstake::set_allowlist_entry(&authority, &mint, &reward_mint, 0, wallet, false)
+            })
+            .collect(),
+    );
+    assert_builds(
+        &[
+            "fungstake",
+            "migrate-vault",
+            "--mint",
+            &mint_arg,
+            "--reward-mint",
+            &reward_mint_arg,
+            "--reach-tge",
+        ],
+        vec![fungstake::migrate_vault(
+            &authority,
+            &mint,
+            &reward_mint,
+            true,
+        )],
+    );
+}
+
+#[test]
+fn sign_only_prints_a_signed_transaction() {
+    let keypair = Keypair::new();
+    let path = temp_dir("sign_only").join("authority.json");
+    write_keypair_file(&keypair, &path).unwrap();
+    let mint = key(2);
+
+    let stdout = success(&[
+        "vault",
+        "set-vote-lock-period",
+        "--mint",
+        &mint.to_string(),
+        "--vote-lock-period",
+        "3600",
+        "--keypair",
+        path.to_str().unwrap(),
+        "--sign-only",
+        "--blockhash",
+        &blockhash().to_string(),
+    ]);
+    let tx = printed_tx(&stdout);
+    tx.verify().unwrap();
+    assert_eq!(tx.message.account_keys[0], keypair.pubkey());
+    assert_eq!(
+        tx.message.instructions[0].data,
+        vault::set_vote_lock_period(&keypair.pubkey(), &mint, 3_600).data
+    );
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    let mint = key(2).to_string();
+    let authority = key(1).to_string();
+    let init = ["vault", "init", "--mint", &mint];
+
+    // clap errors exit with 2
+    let usage_errors: Vec<Vec<&str>> = vec![
+        [
+            &init[..],
+            &["--authority", &authority, "--keypair", "id.json"],
+        ]
+        .concat(),
+        [&init[..], &["--authority", &authority, "--sign-only"]].concat(),
+        vec![
+            "vault",
+            "init",
+            "--mint",
+            "not-a-pubkey",
+            "--authority",
+            &authority,
+        ],
+        vec![
+            "vault",
+            "create-vault",
+            "--mint",
+            &mint,
+            "--authority",
+            &authority,
+        ],
+        vec![
+            "vault",
+            "set-vault-status",
+            "--mint",
+            &mint,
+            "--lock-period",
+            "60",
+            "--is-active",
+            "maybe",
+        ],
+        vec!["fungstake", "init", "--mint", &mint, "--lock-period", "-1"],
+        vec!["show", &mint, "--file", "account.json"],
+    ];
+    for args in usage_errors {
+        let (code, stderr) = failure(&args);
+        assert_eq!(code, 2, "{args:?}: {stderr}");
+    }
+
+    let (code, stderr) = failure(&init);
+    assert_eq!(code, 1);
+    assert!(stderr.contains("either --keypair or --authority is required"));
+
+    let (code, stderr) = failure(&["show"]);
+    assert_eq!(code, 1);
+    assert!(stderr.contains("either an address or --file is required"));
+}
@@ -0,0 +1,66 @@
+//! Runs the `stakectl` binary and writes the account dumps it reads.
+
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use solana_sdk::pubkey::Pubkey;
+use stake_client::{dump::DumpedAccount, test_utils::account_json};
+
+pub fn stakectl(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_stakectl"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// stdout of a successful run
+pub fn success(args: &[&str]) -> String {
+    let output = stakectl(args);
+    assert!(
+        output.status.success(),
+        "stakectl {args:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// stderr of a failed run, with its exit code
+pub fn failure(args: &[&str]) -> (i32, String) {
+    let output = stakectl(args);
+    assert!(!output.status.success(), "stakectl {args:?} succeeded");
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+/// line printed by `display` for a field of an account
+pub fn field(name: &str, value: impl ToString) -> String {
+    format!("  {name:<34} {}", value.to_string())
+}
+
+/// empty directory under the cargo target dir, cleared from previous runs
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// write the account as `solana account --output json` does, returns the file path
+pub fn dump(dir: &Path, address: &Pubkey, owner: &Pubkey, data: &[u8]) -> PathBuf {
+    let account = DumpedAccount {
+        pubkey: *address,
+        owner: *owner,
+        lamports: 1_000_000,
+        data: data.to_vec(),
+    };
+    let path = dir.join(format!("{address}.json"));
+    fs::write(&path, account_json(&account).to_string()).unwrap();
+    path
+}
@@ -0,0 +1,314 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use anchor_lang::Discriminator;
+use common::{dump, field, success, temp_dir};
+use solana_sdk::pubkey::Pubkey;
+use stake_client::{
+    accounts::{fungstake, vault},
+    fungstake_program, pda,
+    test_utils::{data, key, zeroed},
+    vault_program,
+};
+
+fn vault_stake_config(mint: &Pubkey) -> vault::StakeConfig {
+    let mut config: vault::StakeConfig = zeroed(vault_program::STAKE_CONFIG_SIZE);
+    config.authority = key(1);
+    config.stake_currency_mint = *mint;
+    config.max_lock_period = 365 * 86_400;
+    config.receipt_uri = "https://example.com/".to_string();
+    config
+}
+
+fn vault_vault(stake_config: &Pubkey, lock_period: u64, total_staked: u64) -> vault::Vault {
+    let mut vault: vault::Vault = zeroed(vault_program::VAULT_SIZE);
+    vault.vault_config = *stake_config;
+    vault.lock_period = lock_period;
+    vault.total_staked = total_staked;
+    vault.is_active = true;
+    vault
+}
+
+fn stake_detail(staker: &Pubkey, id: u64, amount: u64) -> vault::StakeDetail {
+    let mut detail: vault::StakeDetail = zeroed(vault_program::STAKE_DETAIL_SIZE);
+    detail.id = id;
+    detail.staker = *staker;
+    detail.stake_amount = amount;
+    detail
+}
+
+/// lines printed for the account of `file`
+fn show(file: &std::path::Path) -> Vec<String> {
+    success(&["show", "--file", file.to_str().unwrap()])
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn show_prints_vault_accounts() {
+    let dir = temp_dir("show_vault");
+    let address = key(10);
+    let mut detail = stake_detail(&key(2), 3, 1_500);
+    detail.auto_renew = true;
+    detail.delegation_commission_bps = 250;
+    let lines = show(&dump(&dir, &address, &vault_program::ID, &data(&detail)));
+
+    assert_eq!(lines[0], format!("StakeDetail {address}"));
+    assert_eq!(lines.len(), 18);
+    for expected in [
+        field("id", 3),
+        field("staker", key(2)),
+        field("stake_amount", 1_500),
+        field("auto_renew", true),
+        field("delegate_to", Pubkey::default()),
+        field("delegation_commission_bps", 250),
+    ] {
+        assert!(lines.contains(&expected), "missing {expected:?}");
+    }
+
+    let config = vault_stake_config(&key(3));
+    let lines = show(&dump(&dir, &key(11), &vault_program::ID, &data(&config)));
+    assert_eq!(lines[0], format!("StakeConfig {}", key(11)));
+    assert!(lines.contains(&field("receipt_uri", "https://example.com/")));
+    assert!(lines.contains(&field("ve_bias", 0)));
+}
+
+#[test]
+fn show_prints_optional_fungstake_fields_once_set() {
+    let dir = temp_dir("show_fungstake");
+    let mut config: fungstake::StakeConfig = zeroed(fungstake_program::STAKE_CONFIG_SIZE);
+    config.soft_cap = 1_000;
+
+    let lines = show(&dump(
+        &dir,
+        &key(10),
+        &fungstake_program::ID,
+        &data(&config),
+    ));
+    assert!(lines.contains(&field("soft_cap", 1_000)));
+    assert!(!lines.iter().any(|line| line.contains("price_feed")));
+    assert!(!lines.iter().any(|line| line.contains("kyc_attestor")));
+
+    config.price_feed = key(4);
+    config.soft_cap_usd = 5_000_000;
+    config.kyc_attestor = key(5);
+    let lines = show(&dump(
+        &dir,
+        &key(10),
+        &fungstake_program::ID,
+        &data(&config),
+    ));
+    assert!(lines.contains(&field("price_feed", key(4))));
+    assert!(lines.contains(&field("soft_cap_usd", 5_000_000)));
+    assert!(lines.contains(&field("kyc_attestor", key(5))));
+
+    // only configured phases and the sale terms of a sale are printed
+    let mut vault: fungstake::Vault = zeroed(fungstake_program::VAULT_SIZE);
+    vault.phase_count = 1;
+    vault.phases[0].weight = 3;
+    vault.phases[1].weight = 7;
+    let lines = show(&dump(&dir, &key(11), &fungstake_program::ID, &data(&vault)));
+    assert!(lines.contains(&"  phase 0".to_string()));
+    assert!(lines.contains(&field("  weight", 3)));
+    assert!(!lines.contains(&"  phase 1".to_string()));
+    assert!(!lines.iter().any(|line| line.contains("sale_rate")));
+
+    vault.sale_rate = 2_000_000_000;
+    let lines = show(&dump(&dir, &key(11), &fungstake_program::ID, &data(&vault)));
+    assert!(lines.contains(&field("sale_rate", 2_000_000_000u64)));
+
+    let mut stake_info: fungstake::StakeInfo = zeroed(fungstake_program::STAKE_INFO_SIZE);
+    stake_info.phase_snapshot_amounts = [0, 40, 0, 0];
+    let lines = show(&dump(
+        &dir,
+        &key(12),
+        &fungstake_program::ID,
+        &data(&stake_info),
+    ));
+    assert!(lines.contains(&field("phase 1 snapshot_amount", 40)));
+    assert!(!lines.iter().any(|line| line.contains("phase 0")));
+}
+
+#[test]
+fn show_prints_the_owner_of_unknown_accounts() {
+    let dir = temp_dir("show_unknown");
+    let detail = stake_detail(&key(2), 1, 10);
+    // a vault account owned by another program is not decoded
+    let lines = show(&dump(
+        &dir,
+        &key(10),
+        &fungstake_program::ID,
+        &data(&detail),
+    ));
+    assert_eq!(
+        lines,
+        [
+            format!("unknown account {}", key(10)),
+            field("owner", fungstake_program::ID),
+            field("data_len", vault_program::STAKE_DETAIL_SIZE),
+        ]
+    );
+
+    let operator = vault::Operator::DISCRIMINATOR.to_vec();
+    let lines = show(&dump(&dir, &key(11), &vault_program::ID, &operator));
+    assert_eq!(lines[0], format!("unknown account {}", key(11)));
+}
+
+#[test]
+fn vault_list_prints_vaults_by_lock_period_with_their_positions() {
+    let dir = temp_dir("vault_list");
+    let mint = key(3);
+    let (alice, bob) = (key(20), key(21));
+    let stake_config = pda::vault::stake_config(&mint);
+    dump(
+        &dir,
+        &stake_config,
+        &vault_program::ID,
+        &data(&vault_stake_config(&mint)),
+    );
+
+    let mut positions = vec![];
+    for (lock_period, total_staked) in [(90 * 86_400, 700), (30 * 86_400, 300)] {
+        let vault_key = pda::vault::vault(&stake_config, lock_period);
+        let vault = vault_vault(&stake_config, lock_period, total_staked);
+        dump(&dir, &vault_key, &vault_program::ID, &data(&vault));
+        for (staker, id) in [(alice, 1), (bob, 1), (alice, 2)] {
+            let staker_info = pda::vault::staker_info(&vault_key, &staker);
+            let address = pda::vault::stake_detail(&staker_info, id);
+            let detail = stake_detail(&staker, id, lock_period / 86_400);
+            dump(&dir, &address, &vault_program::ID, &data(&detail));
+            positions.push((lock_period, staker, address));
+        }
+    }
+    // vaults of other mints are skipped
+    let other_config = pda::vault::stake_config(&key(4));
+    let other_vault = pda::vault::vault(&other_config, 60);
+    dump(
+        &dir,
+        &other_vault,
+        &vault_program::ID,
+        &data(&vault_vault(&other_config, 60, 1)),
+    );
+
+    let list = |owner: Option<Pubkey>| {
+        let mut args = vec![
+            "vault".to_string(),
+            "list".to_string(),
+            "--mint".to_string(),
+            mint.to_string(),
+            "--accounts-dir".to_string(),
+            dir.to_str().unwrap().to_string(),
+        ];
+        if let Some(owner) = owner {
+            args.extend(["--owner".to_string(), owner.to_string()]);
+        }
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        success(&args)
+    };
+
+    // vaults sorted by lock period, each followed by its positions
+    let stdout = list(None);
+    let mut headers = stdout
+        .lines()
+        .filter(|line| !line.starts_with(' ') && !line.is_empty());
+    assert_eq!(
+        headers.next().unwrap(),
+        format!("StakeConfig {stake_config}")
+    );
+    let mut vaults: Vec<(String, BTreeSet<String>)> = vec![];
+    for header in headers {
+        if header.starts_with("Vault") {
+            vaults.push((header.to_string(), BTreeSet::new()));
+        } else {
+            vaults.last_mut().unwrap().1.insert(header.to_string());
+        }
+    }
+    let expected: Vec<(String, BTreeSet<String>)> = [30 * 86_400, 90 * 86_400]
+        .into_iter()
+        .map(|lock_period| {
+            let details = positions
+                .iter()
+                .filter(|(period, _, _)| *period == lock_period)
+                .map(|(_, _, address)| format!("StakeDetail {address}"))
+                .collect();
+            let vault_key = pda::vault::vault(&stake_config, lock_period);
+            (format!("Vault {vault_key}"), details)
+        })
+        .collect();
+    assert_eq!(vaults, expected);
+
+    let stdout = list(Some(bob));
+    let details: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.starts_with("StakeDetail"))
+        .collect();
+    assert_eq!(details.len(), 2);
+    assert_eq!(stdout.matches(&field("staker", bob)).count(), 2, "{stdout}");
+    assert!(!stdout.contains(&alice.to_string()));
+}
+
+#[test]
+fn fungstake_list_prints_stakes_of_the_given_owners() {
+    let dir = temp_dir("fungstake_list");
+    let (mint, reward_mint, owner) = (key(3), key(5), key(20));
+    let stake_config_key = pda::fungstake::stake_config(&mint);
+    let mut config: fungstake::StakeConfig = zeroed(fungstake_program::STAKE_CONFIG_SIZE);
+    config.stake_currency_mint = mint;
+    dump(
+        &dir,
+        &stake_config_key,
+        &fungstake_program::ID,
+        &data(&config),
+    );
+
+    let vault_key = pda::fungstake::vault(&stake_config_key, &reward_mint);
+    let mut vault: fungstake::Vault = zeroed(fungstake_program::VAULT_SIZE);
+    vault.reward_currency_mint = reward_mint;
+    vault.total_staked = 900;
+    dump(&dir, &vault_key, &fungstake_program::ID, &data(&vault));
+
+    let stake_info_key = pda::fungstake::stake_info(&vault_key, &owner);
+    let mut stake_info: fungstake::StakeInfo = zeroed(fungstake_program::STAKE_INFO_SIZE);
+    stake_info.stake_amount = 900;
+    dump(
+        &dir,
+        &stake_info_key,
+        &fungstake_program::ID,
+        &data(&stake_info),
+    );
+
+    let stdout = success(&[
+        "fungstake",
+        "list",
+        "--mint",
+        &mint.to_string(),
+        "--owner",
+        &owner.to_string(),
+        "--owner",
+        &key(21).to_string(),
+        "--accounts-dir",
+        dir.to_str().unwrap(),
+    ]);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], format!("StakeConfig {stake_config_key}"));
+    assert!(lines.contains(&format!("Vault {vault_key}").as_str()));
+    assert!(lines.contains(&field("total_staked", 900).as_str()));
+    // owners without a stake info are skipped
+    assert!(lines.contains(&format!("owner {owner}").as_str()));
+    assert!(!stdout.contains(&format!("owner {}", key(21))));
+    assert!(lines.contains(&format!("StakeInfo {stake_info_key}").as_str()));
+    assert!(lines.contains(&field("stake_amount", 900).as_str()));
+
+    let (code, stderr) = common::failure(&[
+        "fungstake",
+        "list",
+        "--mint",
+        &key(4).to_string(),
+        "--accounts-dir",
+        dir.to_str().unwrap(),
+    ]);
+    assert_eq!(code, 1);
+    assert!(stderr.contains("no StakeConfig"));
+}
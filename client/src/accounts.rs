@@ -0,0 +1,69 @@
+use anchor_lang::{AccountDeserialize, Discriminator};
+use solana_program::pubkey::Pubkey;
+
+/// accounts of the vault program
+pub mod vault {
+    pub use ::vault::{
+        BalanceCheckpoint, MaxVoterWeightRecord, Operator, PositionReceipt, StakeConfig,
+        StakeDetail, StakerInfo, Vault, VoterWeightAction, VoterWeightRecord,
+    };
+}
+
+/// accounts of the fungstake program
+pub mod fungstake {
+    pub use ::fungstake::{AllowlistEntry, Phase, StakeConfig, StakeInfo, Vault};
+}
+
+/// decode raw account data, the anchor discriminator is checked
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> anchor_lang::Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+/// staking accounts of either program
+pub enum ProgramAccount {
+    VaultStakeConfig(Box<vault::StakeConfig>),
+    VaultVault(vault::Vault),
+    VaultStakerInfo(Box<vault::StakerInfo>),
+    VaultStakeDetail(vault::StakeDetail),
+    FungstakeStakeConfig(fungstake::StakeConfig),
+    FungstakeVault(fungstake::Vault),
+    FungstakeStakeInfo(fungstake::StakeInfo),
+}
+
+fn is<T: Discriminator>(data: &[u8]) -> bool {
+    data.starts_with(&T::DISCRIMINATOR)
+}
+
+/// find the account type from its owner and discriminator, None if it is not a staking account.
+/// Both programs name their accounts alike, so the owner is needed to tell them apart
+pub fn decode_program_account(
+    owner: &Pubkey,
+    data: &[u8],
+) -> anchor_lang::Result<Option<ProgramAccount>> {
+    let account = if *owner == ::vault::ID {
+        if is::<vault::StakeConfig>(data) {
+            ProgramAccount::VaultStakeConfig(Box::new(decode(data)?))
+        } else if is::<vault::Vault>(data) {
+            ProgramAccount::VaultVault(decode(data)?)
+        } else if is::<vault::StakerInfo>(data) {
+            ProgramAccount::VaultStakerInfo(Box::new(decode(data)?))
+        } else if is::<vault::StakeDetail>(data) {
+            ProgramAccount::VaultStakeDetail(decode(data)?)
+        } else {
+            return Ok(None);
+        }
+    } else if *owner == ::fungstake::ID {
+        if is::<fungstake::StakeConfig>(data) {
+            ProgramAccount::FungstakeStakeConfig(decode(data)?)
+        } else if is::<fungstake::Vault>(data) {
+            ProgramAccount::FungstakeVault(decode(data)?)
+        } else if is::<fungstake::StakeInfo>(data) {
+            ProgramAccount::FungstakeStakeInfo(decode(data)?)
+        } else {
+            return Ok(None);
+        }
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(account))
+}
@@ -0,0 +1,121 @@
+use std::{fs, io, path::Path, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use solana_program::pubkey::Pubkey;
+
+/// account loaded from a `solana account <ADDRESS> --output json` file
+/// or from a saved `getProgramAccounts` response
+pub struct DumpedAccount {
+    pub pubkey: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct KeyedAccountJson {
+    pubkey: String,
+    account: AccountJson,
+}
+
+/// `getProgramAccounts` result, with or without `withContext`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ProgramAccountsJson {
+    Plain(Vec<KeyedAccountJson>),
+    WithContext { value: Vec<KeyedAccountJson> },
+}
+
+/// shapes accepted in a dump file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DumpJson {
+    Account(KeyedAccountJson),
+    Accounts(Vec<KeyedAccountJson>),
+    /// full JSON-RPC response
+    Response {
+        result: ProgramAccountsJson,
+    },
+}
+
+#[derive(Deserialize)]
+struct AccountJson {
+    lamports: u64,
+    /// `[data, encoding]`, only base64 is supported
+    data: (String, String),
+    owner: String,
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn parse_pubkey(value: &str) -> io::Result<Pubkey> {
+    Pubkey::from_str(value).map_err(|_| invalid_data(format!("invalid pubkey {value}")))
+}
+
+impl TryFrom<KeyedAccountJson> for DumpedAccount {
+    type Error = io::Error;
+
+    fn try_from(keyed: KeyedAccountJson) -> io::Result<Self> {
+        let (data, encoding) = keyed.account.data;
+        if encoding != "base64" {
+            return Err(invalid_data(format!("unsupported encoding {encoding}")));
+        }
+
+        Ok(DumpedAccount {
+            pubkey: parse_pubkey(&keyed.pubkey)?,
+            owner: parse_pubkey(&keyed.account.owner)?,
+            lamports: keyed.account.lamports,
+            data: STANDARD
+                .decode(data)
+                .map_err(|err| invalid_data(err.to_string()))?,
+        })
+    }
+}
+
+pub fn load_account_file(path: &Path) -> io::Result<DumpedAccount> {
+    let keyed: KeyedAccountJson = serde_json::from_slice(&fs::read(path)?)?;
+    keyed.try_into()
+}
+
+/// every account of a file holding one account, an array of accounts or a
+/// `getProgramAccounts` response
+pub fn load_accounts_file(path: &Path) -> io::Result<Vec<DumpedAccount>> {
+    let keyed = match serde_json::from_slice(&fs::read(path)?)? {
+        DumpJson::Account(account) => vec![account],
+        DumpJson::Accounts(accounts) => accounts,
+        DumpJson::Response {
+            result: ProgramAccountsJson::Plain(accounts),
+        }
+        | DumpJson::Response {
+            result: ProgramAccountsJson::WithContext { value: accounts },
+        } => accounts,
+    };
+    keyed.into_iter().map(TryInto::try_into).collect()
+}
+
+/// every account of the `*.json` files of `dir`
+pub fn load_account_dir(dir: &Path) -> io::Result<Vec<DumpedAccount>> {
+    let mut accounts = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            accounts.extend(load_accounts_file(&path)?);
+        }
+    }
+    Ok(accounts)
+}
+
+/// accounts of a dump file or of every dump file of a directory
+pub fn load_accounts(path: &Path) -> io::Result<Vec<DumpedAccount>> {
+    if path.is_dir() {
+        load_account_dir(path)
+    } else {
+        load_accounts_file(path)
+    }
+}
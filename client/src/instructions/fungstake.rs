@@ -0,0 +1,438 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::{associated_token, associated_token::get_associated_token_address, token};
+use fungstake::{accounts, instruction, kyc::KycAttestation};
+use solana_program::{ed25519_program, instruction::Instruction, pubkey::Pubkey, sysvar};
+
+use crate::pda::fungstake as pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: fungstake::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn initialize(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u32,
+    lock_extend_time: u32,
+    soft_cap: u64,
+) -> Instruction {
+    build(
+        accounts::Initialize {
+            signer: *signer,
+            stake_config: pda::stake_config(stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::Initialize {
+            lock_period,
+            lock_extend_time,
+            soft_cap,
+        },
+    )
+}
+
+pub fn create_vault(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    reward_currency_mint: &Pubkey,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, reward_currency_mint);
+    build(
+        accounts::CreateVault {
+            signer: *signer,
+            stake_config,
+            reward_currency_mint: *reward_currency_mint,
+            stake_currency_mint: *stake_currency_mint,
+            vault,
+            vault_token_account: get_associated_token_address(&vault, stake_currency_mint),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::CreateVault {},
+    )
+}
+
+/// `price_feed` is only required while a USD soft cap is configured and not reached.
+/// `allowlist_phase` adds the AllowlistEntry of the signer for a phase with an allowlist.
+/// `kyc` adds the instructions sysvar, the stake must follow [`kyc_attestation`]
+pub fn stake(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    reward_currency_mint: &Pubkey,
+    amount: u64,
+    price_feed: Option<Pubkey>,
+    allowlist_phase: Option<u8>,
+    kyc: bool,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, reward_currency_mint);
+    build(
+        accounts::Stake {
+            signer: *signer,
+            stake_config,
+            vault,
+            vault_staking_token_account: get_associated_token_address(&vault, stake_currency_mint),
+            user_stake_info_pda: pda::stake_info(&vault, signer),
+            user_token_account: get_associated_token_address(signer, stake_currency_mint),
+            reward_currency_mint: *reward_currency_mint,
+            stake_currency_mint: *stake_currency_mint,
+            price_feed,
+            allowlist_entry: allowlist_phase
+                .map(|phase_id| pda::allowlist_entry(&vault, phase_id, signer)),
+            instructions_sysvar: kyc.then_some(sysvar::instructions::ID),
+            token_program: token::ID,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::Stake { amount },
+    )
+}
+
+/// `auto_close` closes the stake info once drained, its rent goes back to signer
+pub fn destake(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    reward_currency_mint: &Pubkey,
+    amount: u64,
+    auto_close: bool,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, reward_currency_mint);
+    build(
+        accounts::DeStake {
+            signer: *signer,
+            stake_config,
+            vault,
+            vault_token_account: get_associated_token_address(&vault, stake_currency_mint),
+            staker_info: pda::stake_info(&vault, signer),
+            staker_token_account: get_associated_token_address(signer, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            reward_currency_mint: *reward_currency_mint,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Destake { amount, auto_close },
+    )
+}
+
+pub fn claim_reward(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    reward_currency_mint: &Pubkey,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, reward_currency_mint);
+    build(
+        accounts::ClaimReward {
+            signer: *signer,
+            stake_config,
+            reward_currency_mint: *reward_currency_mint,
+            stake_currency_mint: *stake_currency_mint,
+            vault,
+            vault_reward_token_account: get_associated_token_address(&vault, reward_currency_mint),
+            vault_token_account: get_associated_token_address(&vault, stake_currency_mint),
+            user_stake_token_account: get_associated_token_address(signer, stake_currency_mint),
+            user_stake_info_pda: pda::stake_info(&vault, signer),
+            user_reward_token_account: get_associated_token_address(signer, reward_currency_mint),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::ClaimReward {},
+    )
+}
+
+pub fn close_stake_info(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    reward_currency_mint: &Pubkey,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, reward_currency_mint);
+    build(
+        accounts::CloseStakeInfo {
+            signer: *signer,
+            stake_config,
+            vault,
+            stake_info: pda::stake_info(&vault, signer),
+            reward_currency_mint: *reward_currency_mint,
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::CloseStakeInfo {},
+    )
+}
+
+/// solvency report of the vault in return data, fails with SolvencyViolated
+pub fn audit_vault(stake_currency_mint: &Pubkey, reward_currency_mint: &Pubkey) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, reward_currency_mint);
+    build(
+        accounts::AuditVault {
+            stake_config,
+            reward_currency_mint: *reward_currency_mint,
+            stake_currency_mint: *stake_currency_mint,
+            vault,
+            vault_token_account: get_associated_token_address(&vault, stake_currency_mint),
+            vault_reward_token_account: get_associated_token_address(&vault, reward_currency_mint),
+        },
+        instruction::AuditVault {},
+    )
+}
+
+/// `price_feed` None switches the stake config back to its token soft cap
+pub fn set_price_feed(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    price_feed: Option<Pubkey>,
+    soft_cap_usd: u64,
+    max_price_age: u32,
+    max_confidence_bps: u16,
+) -> Instruction {
+    build(
+        accounts::SetPriceFeed {
+            authority: *authority,
+            stake_config: pda::stake_config(stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            price_feed,
+        },
+        instruction::SetPriceFeed {
+            soft_cap_usd,
+            max_price_age,
+            max_confidence_bps,
+        },
+    )
+}
+
+/// add the next phase of the vault or update phase `phase_id`
+#[allow(clippy::too_many_arguments)]
+pub fn set_phase(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    reward_currency_mint: &Pubkey,
+    phase_id: u8,
+    start_time: i64,
+    end_time: i64,
+    wallet_cap: u64,
+    weight: u16,
+    requires_allowlist: bool,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    build(
+        accounts::SetPhase {
+            authority: *authority,
+            stake_config,
+            vault: pda::vault(&stake_config, reward_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            reward_currency_mint: *reward_currency_mint,
+        },
+        instruction::SetPhase {
+            phase_id,
+            start_time,
+            end_time,
+            wallet_cap,
+            weight,
+            requires_allowlist,
+        },
+    )
+}
+
+pub fn set_allowlist_entry(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    reward_currency_mint: &Pubkey,
+    phase_id: u8,
+    wallet: &Pubkey,
+    allowed: bool,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, reward_currency_mint);
+    build(
+        accounts::SetAllowlistEntry {
+            authority: *authority,
+            stake_config,
+            vault,
+            allowlist_entry: pda::allowlist_entry(&vault, phase_id, wallet),
+            stake_currency_mint: *stake_currency_mint,
+            reward_currency_mint: *reward_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::SetAllowlistEntry {
+            phase_id,
+            wallet: *wallet,
+            allowed,
+        },
+    )
+}
+
+/// Pubkey::default() stops requiring KYC attestations
+pub fn set_kyc_attestor(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    kyc_attestor: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::SetKycAttestor {
+            authority: *authority,
+            stake_config: pda::stake_config(stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::SetKycAttestor {
+            kyc_attestor: *kyc_attestor,
+        },
+    )
+}
+
+/// `sale_rate` 0 and `sale_allocation` 0 share the reward pro-rata again
+pub fn set_sale_terms(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    reward_currency_mint: &Pubkey,
+    sale_rate: u64,
+    sale_allocation: u64,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    build(
+        accounts::SetSaleTerms {
+            authority: *authority,
+            stake_config,
+            vault: pda::vault(&stake_config, reward_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            reward_currency_mint: *reward_currency_mint,
+        },
+        instruction::SetSaleTerms {
+            sale_rate,
+            sale_allocation,
+        },
+    )
+}
+
+pub fn withdraw_sale_proceeds(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    reward_currency_mint: &Pubkey,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, reward_currency_mint);
+    build(
+        accounts::WithdrawSaleProceeds {
+            authority: *authority,
+            stake_config,
+            vault,
+            vault_token_account: get_associated_token_address(&vault, stake_currency_mint),
+            authority_token_account: get_associated_token_address(authority, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            reward_currency_mint: *reward_currency_mint,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::WithdrawSaleProceeds {},
+    )
+}
+
+/// grow a stake config created with an older layout, the authority pays the rent
+pub fn migrate_stake_config(authority: &Pubkey, stake_currency_mint: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateStakeConfig {
+            authority: *authority,
+            stake_config: pda::stake_config(stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateStakeConfig {},
+    )
+}
+
+/// grow a vault created with an older layout, its stake config must be migrated first.
+/// `reach_tge` adds the reward token account, required once the vault reached tge
+pub fn migrate_vault(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    reward_currency_mint: &Pubkey,
+    reach_tge: bool,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, reward_currency_mint);
+    build(
+        accounts::MigrateVault {
+            authority: *authority,
+            stake_config,
+            vault,
+            vault_token_account: get_associated_token_address(&vault, stake_currency_mint),
+            vault_reward_token_account: reach_tge
+                .then(|| get_associated_token_address(&vault, reward_currency_mint)),
+            reward_currency_mint: *reward_currency_mint,
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateVault {},
+    )
+}
+
+/// grow the stake info of `staker`, paid by `signer`. Its vault must be migrated first
+pub fn migrate_stake_info(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    reward_currency_mint: &Pubkey,
+    staker: &Pubkey,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, reward_currency_mint);
+    build(
+        accounts::MigrateStakeInfo {
+            signer: *signer,
+            stake_config,
+            vault,
+            stake_info: pda::stake_info(&vault, staker),
+            reward_currency_mint: *reward_currency_mint,
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateStakeInfo { staker: *staker },
+    )
+}
+
+/// ed25519 program instruction verifying `signature` of `attestation` by `attestor`,
+/// to be placed right before the stake instruction
+pub fn kyc_attestation(
+    attestor: &Pubkey,
+    signature: &[u8; 64],
+    attestation: &KycAttestation,
+) -> Instruction {
+    const PUBKEY_OFFSET: u16 = 16;
+    const SIGNATURE_OFFSET: u16 = PUBKEY_OFFSET + 32;
+    const MESSAGE_OFFSET: u16 = SIGNATURE_OFFSET + 64;
+    let message = attestation.message();
+
+    let mut data = vec![1, 0];
+    for offset in [
+        SIGNATURE_OFFSET,
+        u16::MAX,
+        PUBKEY_OFFSET,
+        u16::MAX,
+        MESSAGE_OFFSET,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(attestor.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(&message);
+
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
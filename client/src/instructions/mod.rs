@@ -0,0 +1,2 @@
+pub mod fungstake;
+pub mod vault;
@@ -0,0 +1,1125 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::{
+    associated_token, associated_token::get_associated_token_address, metadata, token,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+use vault::{accounts, instruction, VoterWeightAction};
+
+use crate::pda::vault as pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: vault::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// keys shared by most instructions of a staker in one vault
+struct Position {
+    stake_config: Pubkey,
+    vault: Pubkey,
+    vault_token_account: Pubkey,
+    staker_info: Pubkey,
+}
+
+impl Position {
+    fn new(stake_currency_mint: &Pubkey, lock_period: u64, owner: &Pubkey) -> Self {
+        let stake_config = pda::stake_config(stake_currency_mint);
+        let vault = pda::vault(&stake_config, lock_period);
+        Position {
+            stake_config,
+            vault,
+            vault_token_account: pda::vault_token_account(&vault, stake_currency_mint),
+            staker_info: pda::staker_info(&vault, owner),
+        }
+    }
+}
+
+/// (vault, stake detail) pairs expected by get_voting_power and update_voter_weight_record,
+/// from (lock_period, staker, id) of each position. The staker is the wallet which staked it,
+/// not the operator it is delegated to. Stake details are writable when they get vote locked
+fn voting_power_accounts(
+    stake_currency_mint: &Pubkey,
+    positions: &[(u64, Pubkey, u64)],
+    writable: bool,
+) -> Vec<AccountMeta> {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    positions
+        .iter()
+        .flat_map(|(lock_period, staker, id)| {
+            let vault = pda::vault(&stake_config, *lock_period);
+            let stake_detail = pda::stake_detail(&pda::staker_info(&vault, staker), *id);
+            [
+                AccountMeta::new_readonly(vault, false),
+                if writable {
+                    AccountMeta::new(stake_detail, false)
+                } else {
+                    AccountMeta::new_readonly(stake_detail, false)
+                },
+            ]
+        })
+        .collect()
+}
+
+pub fn initialize(signer: &Pubkey, stake_currency_mint: &Pubkey) -> Instruction {
+    build(
+        accounts::Initialize {
+            signer: *signer,
+            stake_config: pda::stake_config(stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::Initialize {},
+    )
+}
+
+pub fn create_vault(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, lock_period);
+    build(
+        accounts::CreateVault {
+            authority: *authority,
+            stake_config,
+            stake_currency_mint: *stake_currency_mint,
+            vault,
+            vault_token_account: pda::vault_token_account(&vault, stake_currency_mint),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::CreateVault { lock_period },
+    )
+}
+
+/// `current_id` is `StakerInfo.current_id` of signer in this vault, 0 if not created yet
+/// `operator` is the wallet of a registered operator the new position is delegated to
+pub fn stake(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    current_id: u64,
+    amount: u64,
+    operator: Option<&Pubkey>,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    build(
+        accounts::Stake {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            vault_token_account: position.vault_token_account,
+            staker_info_pda: position.staker_info,
+            stake_detail_pda: pda::next_stake_detail(&position.staker_info, current_id),
+            user_token_account: get_associated_token_address(signer, stake_currency_mint),
+            operator: operator.map(|operator| pda::operator(&position.stake_config, operator)),
+            stake_currency_mint: *stake_currency_mint,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::Stake {
+            lock_period,
+            amount,
+        },
+    )
+}
+
+/// `auto_close` closes the stake detail once drained, its rent goes back to signer
+pub fn destake(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+    amount: u64,
+    auto_close: bool,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    build(
+        accounts::DeStake {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            vault_token_account: position.vault_token_account,
+            staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, id),
+            staker_token_account: get_associated_token_address(signer, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Destake {
+            id,
+            lock_period,
+            amount,
+            auto_close,
+        },
+    )
+}
+
+/// `treasury_token_account` is only required when the penalty is sent to the treasury
+pub fn early_destake(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+    amount: u64,
+    treasury_token_account: Option<Pubkey>,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    build(
+        accounts::EarlyDeStake {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            vault_token_account: position.vault_token_account,
+            staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, id),
+            staker_token_account: get_associated_token_address(signer, stake_currency_mint),
+            treasury_token_account,
+            stake_currency_mint: *stake_currency_mint,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::EarlyDestake {
+            id,
+            lock_period,
+            amount,
+        },
+    )
+}
+
+pub fn set_early_unstake_config(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    allow_early_unstake: bool,
+    max_early_unstake_penalty_bps: u16,
+    redistribute_penalty: bool,
+    treasury: Pubkey,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    build(
+        accounts::SetEarlyUnstakeConfig {
+            authority: *authority,
+            stake_config,
+            stake_currency_mint: *stake_currency_mint,
+            vault: pda::vault(&stake_config, lock_period),
+        },
+        instruction::SetEarlyUnstakeConfig {
+            lock_period,
+            allow_early_unstake,
+            max_early_unstake_penalty_bps,
+            redistribute_penalty,
+            treasury,
+        },
+    )
+}
+
+pub fn merge_positions(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    target_id: u64,
+    source_ids: Vec<u64>,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    let mut ix = build(
+        accounts::MergePositions {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, target_id),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::MergePositions {
+            lock_period,
+            target_id,
+            source_ids: source_ids.clone(),
+        },
+    );
+    ix.accounts.extend(
+        source_ids
+            .iter()
+            .map(|id| AccountMeta::new(pda::stake_detail(&position.staker_info, *id), false)),
+    );
+    ix
+}
+
+/// `current_id` is `StakerInfo.current_id` of signer, the new position gets `current_id + 1`
+pub fn split_position(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+    current_id: u64,
+    amount: u64,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    build(
+        accounts::SplitPosition {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, id),
+            new_stake_detail: pda::next_stake_detail(&position.staker_info, current_id),
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::SplitPosition {
+            id,
+            lock_period,
+            amount,
+        },
+    )
+}
+
+/// `recipient_current_id` is `StakerInfo.current_id` of recipient, 0 if not created yet
+pub fn transfer_position(
+    signer: &Pubkey,
+    recipient: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+    recipient_current_id: u64,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    let recipient_staker_info = pda::staker_info(&position.vault, recipient);
+    build(
+        accounts::TransferPosition {
+            signer: *signer,
+            recipient: *recipient,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, id),
+            recipient_staker_info,
+            recipient_stake_detail: pda::next_stake_detail(
+                &recipient_staker_info,
+                recipient_current_id,
+            ),
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::TransferPosition { id, lock_period },
+    )
+}
+
+pub fn mint_position_receipt(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    let stake_detail = pda::stake_detail(&position.staker_info, id);
+    let receipt_mint = pda::position_mint(&stake_detail);
+    build(
+        accounts::MintPositionReceipt {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            staker_info: position.staker_info,
+            stake_detail,
+            receipt_mint,
+            receipt: pda::position_receipt(&receipt_mint),
+            signer_receipt_token_account: get_associated_token_address(signer, &receipt_mint),
+            receipt_metadata: pda::receipt_metadata(&receipt_mint),
+            stake_currency_mint: *stake_currency_mint,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: metadata::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::MintPositionReceipt { id, lock_period },
+    )
+}
+
+/// `staker_info` and `stake_detail` are the ones stored in the `PositionReceipt`,
+/// `staker` is the `StakeDetail.staker` refunded the receipt rent
+pub fn destake_with_receipt(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    receipt_mint: &Pubkey,
+    staker: &Pubkey,
+    staker_info: &Pubkey,
+    stake_detail: &Pubkey,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    build(
+        accounts::DeStakeWithReceipt {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            vault_token_account: position.vault_token_account,
+            receipt_mint: *receipt_mint,
+            receipt: pda::position_receipt(receipt_mint),
+            staker: *staker,
+            staker_info: *staker_info,
+            stake_detail: *stake_detail,
+            signer_receipt_token_account: get_associated_token_address(signer, receipt_mint),
+            staker_token_account: get_associated_token_address(signer, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::DestakeWithReceipt { lock_period },
+    )
+}
+
+pub fn destake_many(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    ids: &[u64],
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    let mut ix = build(
+        accounts::DeStakeMany {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            vault_token_account: position.vault_token_account,
+            staker_info: position.staker_info,
+            staker_token_account: get_associated_token_address(signer, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::DestakeMany { lock_period },
+    );
+    ix.accounts.extend(
+        ids.iter()
+            .map(|id| AccountMeta::new(pda::stake_detail(&position.staker_info, *id), false)),
+    );
+    ix
+}
+
+pub fn close_position(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    build(
+        accounts::ClosePosition {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, id),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::ClosePosition { id, lock_period },
+    )
+}
+
+pub fn close_staker_info(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    build(
+        accounts::CloseStakerInfo {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            staker_info: position.staker_info,
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::CloseStakerInfo { lock_period },
+    )
+}
+
+pub fn set_vault_limits(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    max_total_staked: u64,
+    max_stake_per_staker: u64,
+    min_stake_amount: u64,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    build(
+        accounts::SetVaultLimits {
+            authority: *authority,
+            stake_config,
+            stake_currency_mint: *stake_currency_mint,
+            vault: pda::vault(&stake_config, lock_period),
+        },
+        instruction::SetVaultLimits {
+            lock_period,
+            max_total_staked,
+            max_stake_per_staker,
+            min_stake_amount,
+        },
+    )
+}
+
+pub fn set_lock_period_bounds(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    min_lock_period: u64,
+    max_lock_period: u64,
+) -> Instruction {
+    build(
+        accounts::SetLockPeriodBounds {
+            authority: *authority,
+            stake_config: pda::stake_config(stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::SetLockPeriodBounds {
+            min_lock_period,
+            max_lock_period,
+        },
+    )
+}
+
+pub fn set_receipt_uri(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    receipt_uri: String,
+) -> Instruction {
+    build(
+        accounts::SetReceiptUri {
+            authority: *authority,
+            stake_config: pda::stake_config(stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::SetReceiptUri { receipt_uri },
+    )
+}
+
+pub fn set_vote_lock_period(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    vote_lock_period: u64,
+) -> Instruction {
+    build(
+        accounts::SetVoteLockPeriod {
+            authority: *authority,
+            stake_config: pda::stake_config(stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::SetVoteLockPeriod { vote_lock_period },
+    )
+}
+
+pub fn set_vault_status(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    is_active: bool,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    build(
+        accounts::SetVaultStatus {
+            authority: *authority,
+            stake_config,
+            stake_currency_mint: *stake_currency_mint,
+            vault: pda::vault(&stake_config, lock_period),
+        },
+        instruction::SetVaultStatus {
+            lock_period,
+            is_active,
+        },
+    )
+}
+
+pub fn close_vault(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, lock_period);
+    build(
+        accounts::CloseVault {
+            authority: *authority,
+            stake_config,
+            stake_currency_mint: *stake_currency_mint,
+            vault,
+            vault_token_account: pda::vault_token_account(&vault, stake_currency_mint),
+            authority_token_account: get_associated_token_address(authority, stake_currency_mint),
+            token_program: token::ID,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::CloseVault { lock_period },
+    )
+}
+
+pub fn set_unbonding_period(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    unbonding_period: u64,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    build(
+        accounts::SetUnbondingPeriod {
+            authority: *authority,
+            stake_config,
+            stake_currency_mint: *stake_currency_mint,
+            vault: pda::vault(&stake_config, lock_period),
+        },
+        instruction::SetUnbondingPeriod {
+            lock_period,
+            unbonding_period,
+        },
+    )
+}
+
+pub fn request_unstake(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    build(
+        accounts::RequestUnstake {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            vault_token_account: position.vault_token_account,
+            staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, id),
+            staker_token_account: get_associated_token_address(signer, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::RequestUnstake { id, lock_period },
+    )
+}
+
+/// `auto_close` closes the drained stake detail, its rent goes back to signer
+pub fn withdraw(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+    auto_close: bool,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    build(
+        accounts::Withdraw {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            vault_token_account: position.vault_token_account,
+            staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, id),
+            staker_token_account: get_associated_token_address(signer, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Withdraw {
+            id,
+            lock_period,
+            auto_close,
+        },
+    )
+}
+
+pub fn set_renew_window(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    renew_window: u64,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    build(
+        accounts::SetRenewWindow {
+            authority: *authority,
+            stake_config,
+            stake_currency_mint: *stake_currency_mint,
+            vault: pda::vault(&stake_config, lock_period),
+        },
+        instruction::SetRenewWindow {
+            lock_period,
+            renew_window,
+        },
+    )
+}
+
+fn set_auto_renew_accounts(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+) -> accounts::SetAutoRenew {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    accounts::SetAutoRenew {
+        signer: *signer,
+        stake_config: position.stake_config,
+        vault: position.vault,
+        staker_info: position.staker_info,
+        stake_detail: pda::stake_detail(&position.staker_info, id),
+        stake_currency_mint: *stake_currency_mint,
+    }
+}
+
+pub fn enable_auto_renew(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+) -> Instruction {
+    build(
+        set_auto_renew_accounts(signer, stake_currency_mint, id, lock_period),
+        instruction::EnableAutoRenew { id, lock_period },
+    )
+}
+
+pub fn disable_auto_renew(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+) -> Instruction {
+    build(
+        set_auto_renew_accounts(signer, stake_currency_mint, id, lock_period),
+        instruction::DisableAutoRenew { id, lock_period },
+    )
+}
+
+/// `beneficiary_current_id` is `StakerInfo.current_id` of beneficiary, 0 if not created yet.
+/// Only the grantor signs, it pays for the staker info of beneficiary
+pub fn grant_stake(
+    grantor: &Pubkey,
+    beneficiary: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    beneficiary_current_id: u64,
+    amount: u64,
+    revocable: bool,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, beneficiary);
+    build(
+        accounts::GrantStake {
+            grantor: *grantor,
+            beneficiary: *beneficiary,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            vault_token_account: position.vault_token_account,
+            beneficiary_staker_info: position.staker_info,
+            stake_detail: pda::next_stake_detail(&position.staker_info, beneficiary_current_id),
+            grantor_token_account: get_associated_token_address(grantor, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::GrantStake {
+            lock_period,
+            amount,
+            revocable,
+        },
+    )
+}
+
+pub fn revoke_grant(
+    grantor: &Pubkey,
+    beneficiary: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, beneficiary);
+    build(
+        accounts::RevokeGrant {
+            grantor: *grantor,
+            beneficiary: *beneficiary,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            vault_token_account: position.vault_token_account,
+            beneficiary_staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, id),
+            grantor_token_account: get_associated_token_address(grantor, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::RevokeGrant { id, lock_period },
+    )
+}
+
+/// `positions` are (lock_period, staker, id) of stake details voted by `owner`, staked by it or
+/// delegated to it as an operator. Result is in return data
+pub fn get_voting_power(
+    stake_currency_mint: &Pubkey,
+    owner: &Pubkey,
+    timestamp: i64,
+    positions: &[(u64, Pubkey, u64)],
+) -> Instruction {
+    let mut ix = build(
+        accounts::GetVotingPower {
+            stake_config: pda::stake_config(stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::GetVotingPower {
+            owner: *owner,
+            timestamp,
+        },
+    );
+    ix.accounts
+        .extend(voting_power_accounts(stake_currency_mint, positions, false));
+    ix
+}
+
+pub fn get_total_voting_power(stake_currency_mint: &Pubkey, timestamp: i64) -> Instruction {
+    build(
+        accounts::GetTotalVotingPower {
+            stake_config: pda::stake_config(stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::GetTotalVotingPower { timestamp },
+    )
+}
+
+pub fn get_twab(
+    stake_currency_mint: &Pubkey,
+    owner: &Pubkey,
+    lock_period: u64,
+    start: i64,
+    end: i64,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, owner);
+    build(
+        accounts::GetTwab {
+            stake_config: position.stake_config,
+            vault: position.vault,
+            staker_info: position.staker_info,
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::GetTwab {
+            owner: *owner,
+            lock_period,
+            start,
+            end,
+        },
+    )
+}
+
+pub fn create_voter_weight_record(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    realm: Pubkey,
+) -> Instruction {
+    build(
+        accounts::CreateVoterWeightRecord {
+            signer: *signer,
+            stake_config: pda::stake_config(stake_currency_mint),
+            voter_weight_record: pda::voter_weight_record(&realm, stake_currency_mint, signer),
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::CreateVoterWeightRecord { realm },
+    )
+}
+
+/// `positions` are (lock_period, staker, id) of stake details voted by signer, staked by it or
+/// delegated to it as an operator. `governance` is the
+/// governance of the `target` proposal, required with it for CastVote
+pub fn update_voter_weight_record(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    realm: Pubkey,
+    action: VoterWeightAction,
+    target: Option<Pubkey>,
+    governance: Option<Pubkey>,
+    positions: &[(u64, Pubkey, u64)],
+) -> Instruction {
+    let mut ix = build(
+        accounts::UpdateVoterWeightRecord {
+            signer: *signer,
+            stake_config: pda::stake_config(stake_currency_mint),
+            voter_weight_record: pda::voter_weight_record(&realm, stake_currency_mint, signer),
+            governance_realm: governance.map(|_| realm),
+            governance,
+            proposal: governance.and(target),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::UpdateVoterWeightRecord {
+            realm,
+            action,
+            target,
+        },
+    );
+    ix.accounts.extend(voting_power_accounts(
+        stake_currency_mint,
+        positions,
+        action == VoterWeightAction::CastVote,
+    ));
+    ix
+}
+
+pub fn create_max_voter_weight_record(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    realm: Pubkey,
+) -> Instruction {
+    build(
+        accounts::CreateMaxVoterWeightRecord {
+            signer: *signer,
+            stake_config: pda::stake_config(stake_currency_mint),
+            max_voter_weight_record: pda::max_voter_weight_record(&realm, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::CreateMaxVoterWeightRecord { realm },
+    )
+}
+
+pub fn update_max_voter_weight_record(stake_currency_mint: &Pubkey, realm: Pubkey) -> Instruction {
+    build(
+        accounts::UpdateMaxVoterWeightRecord {
+            stake_config: pda::stake_config(stake_currency_mint),
+            max_voter_weight_record: pda::max_voter_weight_record(&realm, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::UpdateMaxVoterWeightRecord { realm },
+    )
+}
+
+/// signed by the operator wallet and the authority of the stake config
+pub fn register_operator(
+    signer: &Pubkey,
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    commission_bps: u16,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    build(
+        accounts::RegisterOperator {
+            signer: *signer,
+            authority: *authority,
+            stake_config,
+            operator: pda::operator(&stake_config, signer),
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterOperator { commission_bps },
+    )
+}
+
+pub fn set_operator_config(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    commission_bps: u16,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    build(
+        accounts::SetOperatorConfig {
+            signer: *signer,
+            stake_config,
+            operator: pda::operator(&stake_config, signer),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::SetOperatorConfig { commission_bps },
+    )
+}
+
+pub fn set_operator_status(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    operator_wallet: &Pubkey,
+    is_active: bool,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    build(
+        accounts::SetOperatorStatus {
+            authority: *authority,
+            stake_config,
+            stake_currency_mint: *stake_currency_mint,
+            operator: pda::operator(&stake_config, operator_wallet),
+        },
+        instruction::SetOperatorStatus {
+            operator_wallet: *operator_wallet,
+            is_active,
+        },
+    )
+}
+
+/// `previous_operator` is `StakeDetail.delegate_to` when redelegating, its commission goes to its ATA
+pub fn delegate(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+    operator: &Pubkey,
+    previous_operator: Option<Pubkey>,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    build(
+        accounts::Delegate {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            vault_token_account: position.vault_token_account,
+            staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, id),
+            operator: pda::operator(&position.stake_config, operator),
+            previous_operator: previous_operator
+                .map(|previous| pda::operator(&position.stake_config, &previous)),
+            previous_operator_token_account: previous_operator
+                .map(|previous| get_associated_token_address(&previous, stake_currency_mint)),
+            stake_currency_mint: *stake_currency_mint,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Delegate { id, lock_period },
+    )
+}
+
+/// `operator` is `StakeDetail.delegate_to`, its commission goes to its ATA
+pub fn undelegate(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    id: u64,
+    lock_period: u64,
+    operator: &Pubkey,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, signer);
+    build(
+        accounts::Undelegate {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            vault_token_account: position.vault_token_account,
+            staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, id),
+            operator: pda::operator(&position.stake_config, operator),
+            operator_token_account: get_associated_token_address(operator, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Undelegate { id, lock_period },
+    )
+}
+
+/// solvency report of the vault in return data, fails with SolvencyViolated
+pub fn audit_vault(stake_currency_mint: &Pubkey, lock_period: u64) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    let vault = pda::vault(&stake_config, lock_period);
+    build(
+        accounts::AuditVault {
+            stake_config,
+            vault,
+            vault_token_account: pda::vault_token_account(&vault, stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+        },
+        instruction::AuditVault { lock_period },
+    )
+}
+
+/// grow a stake config created with an older layout, the authority pays the rent
+pub fn migrate_stake_config(authority: &Pubkey, stake_currency_mint: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateStakeConfig {
+            authority: *authority,
+            stake_config: pda::stake_config(stake_currency_mint),
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateStakeConfig {},
+    )
+}
+
+/// grow a vault created with an older layout, its stake config must be migrated first
+pub fn migrate_vault(
+    authority: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+) -> Instruction {
+    let stake_config = pda::stake_config(stake_currency_mint);
+    build(
+        accounts::MigrateVault {
+            authority: *authority,
+            stake_config,
+            vault: pda::vault(&stake_config, lock_period),
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateVault { lock_period },
+    )
+}
+
+/// grow the staker info of `staker`, paid by `signer`. Its vault must be migrated first
+pub fn migrate_staker_info(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    staker: &Pubkey,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, staker);
+    build(
+        accounts::MigrateStakerInfo {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            staker_info: position.staker_info,
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateStakerInfo {
+            lock_period,
+            staker: *staker,
+        },
+    )
+}
+
+/// grow a stake detail of `staker`, paid by `signer`. Its staker info must be migrated first
+pub fn migrate_stake_detail(
+    signer: &Pubkey,
+    stake_currency_mint: &Pubkey,
+    lock_period: u64,
+    staker: &Pubkey,
+    id: u64,
+) -> Instruction {
+    let position = Position::new(stake_currency_mint, lock_period, staker);
+    build(
+        accounts::MigrateStakeDetail {
+            signer: *signer,
+            stake_config: position.stake_config,
+            vault: position.vault,
+            staker_info: position.staker_info,
+            stake_detail: pda::stake_detail(&position.staker_info, id),
+            stake_currency_mint: *stake_currency_mint,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateStakeDetail {
+            lock_period,
+            staker: *staker,
+            id,
+        },
+    )
+}
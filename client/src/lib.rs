@@ -0,0 +1,20 @@
+//! Client helpers for the `vault` and `fungstake` programs.
+//!
+//! - [`pda`] derives every program address, including the `lock_period.to_le_bytes()`
+//!   and `current_id + 1` seeds
+//! - [`instructions`] builds an [`Instruction`](solana_program::instruction::Instruction)
+//!   for every program instruction
+//! - [`accounts`] decodes raw account data into the on-chain structs
+//! - [`dump`] loads accounts saved with `solana account --output json` or `getProgramAccounts`
+//! - `test_utils`, with the `test-utils` feature, holds fixtures shared by the tests of the
+//!   crates built on this one
+
+pub mod accounts;
+pub mod dump;
+pub mod instructions;
+pub mod pda;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+
+pub use fungstake as fungstake_program;
+pub use vault as vault_program;
@@ -0,0 +1,28 @@
+use fungstake::constant::constants::{
+    ALLOWLIST_SEED, STAKE_CONFIG_SEED, STAKE_INFO_SEED, VAULT_SEED,
+};
+use solana_program::pubkey::Pubkey;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &fungstake::ID).0
+}
+
+pub fn stake_config(stake_currency_mint: &Pubkey) -> Pubkey {
+    find(&[STAKE_CONFIG_SEED, stake_currency_mint.as_ref()])
+}
+
+pub fn vault(stake_config: &Pubkey, reward_currency_mint: &Pubkey) -> Pubkey {
+    find(&[
+        VAULT_SEED,
+        stake_config.as_ref(),
+        reward_currency_mint.as_ref(),
+    ])
+}
+
+pub fn stake_info(vault: &Pubkey, owner: &Pubkey) -> Pubkey {
+    find(&[STAKE_INFO_SEED, vault.as_ref(), owner.as_ref()])
+}
+
+pub fn allowlist_entry(vault: &Pubkey, phase_id: u8, wallet: &Pubkey) -> Pubkey {
+    find(&[ALLOWLIST_SEED, vault.as_ref(), &[phase_id], wallet.as_ref()])
+}
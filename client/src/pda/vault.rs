@@ -0,0 +1,80 @@
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::metadata::mpl_token_metadata;
+use solana_program::pubkey::Pubkey;
+use vault::constant::constants::{
+    MAX_VOTER_WEIGHT_RECORD_SEED, OPERATOR_SEED, POSITION_MINT_SEED, POSITION_RECEIPT_SEED,
+    STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED, VOTER_WEIGHT_RECORD_SEED,
+};
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &vault::ID).0
+}
+
+pub fn stake_config(stake_currency_mint: &Pubkey) -> Pubkey {
+    find(&[STAKE_CONFIG_SEED, stake_currency_mint.as_ref()])
+}
+
+pub fn vault(stake_config: &Pubkey, lock_period: u64) -> Pubkey {
+    find(&[
+        VAULT_SEED,
+        stake_config.as_ref(),
+        &lock_period.to_le_bytes(),
+    ])
+}
+
+/// staking ATA of the vault
+pub fn vault_token_account(vault: &Pubkey, stake_currency_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(vault, stake_currency_mint)
+}
+
+pub fn staker_info(vault: &Pubkey, owner: &Pubkey) -> Pubkey {
+    find(&[STAKER_INFO_SEED, vault.as_ref(), owner.as_ref()])
+}
+
+pub fn stake_detail(staker_info: &Pubkey, id: u64) -> Pubkey {
+    find(&[STAKE_DETAIL_SEED, staker_info.as_ref(), &id.to_le_bytes()])
+}
+
+/// stake detail created by the next stake, `current_id` is `StakerInfo.current_id` (0 if not created yet)
+pub fn next_stake_detail(staker_info: &Pubkey, current_id: u64) -> Pubkey {
+    stake_detail(staker_info, current_id + 1)
+}
+
+pub fn position_mint(stake_detail: &Pubkey) -> Pubkey {
+    find(&[POSITION_MINT_SEED, stake_detail.as_ref()])
+}
+
+pub fn position_receipt(receipt_mint: &Pubkey) -> Pubkey {
+    find(&[POSITION_RECEIPT_SEED, receipt_mint.as_ref()])
+}
+
+/// metaplex metadata of a receipt mint
+pub fn receipt_metadata(receipt_mint: &Pubkey) -> Pubkey {
+    let program_id = mpl_token_metadata::ID;
+    Pubkey::find_program_address(
+        &[b"metadata", program_id.as_ref(), receipt_mint.as_ref()],
+        &program_id,
+    )
+    .0
+}
+
+pub fn operator(stake_config: &Pubkey, operator: &Pubkey) -> Pubkey {
+    find(&[OPERATOR_SEED, stake_config.as_ref(), operator.as_ref()])
+}
+
+pub fn voter_weight_record(realm: &Pubkey, stake_currency_mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    find(&[
+        VOTER_WEIGHT_RECORD_SEED,
+        realm.as_ref(),
+        stake_currency_mint.as_ref(),
+        owner.as_ref(),
+    ])
+}
+
+pub fn max_voter_weight_record(realm: &Pubkey, stake_currency_mint: &Pubkey) -> Pubkey {
+    find(&[
+        MAX_VOTER_WEIGHT_RECORD_SEED,
+        realm.as_ref(),
+        stake_currency_mint.as_ref(),
+    ])
+}
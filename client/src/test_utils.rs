@@ -0,0 +1,53 @@
+//! Fixtures shared by the tests of this crate and of the CLI, snapshot and indexer.
+
+use anchor_lang::{AccountDeserialize, AccountSerialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_program::pubkey::Pubkey;
+
+use crate::dump::DumpedAccount;
+
+/// pubkey made of `seed` bytes, distinct for every seed
+pub fn key(seed: u8) -> Pubkey {
+    Pubkey::new_from_array([seed; 32])
+}
+
+/// account of `size` bytes with every field zeroed
+pub fn zeroed<T: AccountDeserialize + Discriminator>(size: usize) -> T {
+    let mut data = T::DISCRIMINATOR.to_vec();
+    data.resize(size, 0);
+    T::try_deserialize(&mut &data[..]).unwrap()
+}
+
+/// account data with its discriminator
+pub fn data<T: AccountSerialize>(account: &T) -> Vec<u8> {
+    let mut data = vec![];
+    account.try_serialize(&mut data).unwrap();
+    data
+}
+
+/// account at `pubkey` owned by `owner`, holding 1_000_000 lamports
+pub fn dumped<T: AccountSerialize>(pubkey: Pubkey, owner: Pubkey, account: &T) -> DumpedAccount {
+    DumpedAccount {
+        pubkey,
+        owner,
+        lamports: 1_000_000,
+        data: data(account),
+    }
+}
+
+/// keyed account as printed by `solana account --output json` and listed in a
+/// `getProgramAccounts` response
+pub fn account_json(account: &DumpedAccount) -> Value {
+    json!({
+        "pubkey": account.pubkey.to_string(),
+        "account": {
+            "lamports": account.lamports,
+            "data": [STANDARD.encode(&account.data), "base64"],
+            "owner": account.owner.to_string(),
+            "executable": false,
+            "rentEpoch": u64::MAX,
+            "space": account.data.len(),
+        },
+    })
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use anchor_lang::{AccountDeserialize, AccountSerialize};
+use common::Idl;
+use stake_client::accounts::{decode_program_account, ProgramAccount};
+
+/// unused bytes the first release allocated in the vault stake config, its size constant
+/// still counts them
+const VAULT_STAKE_CONFIG_PADDING: usize = 4;
+
+/// IDL bytes decode into `T` and serialize back unchanged, `size` is the allocated size
+/// and holds `padding` unused bytes
+fn round_trip<T: AccountSerialize + AccountDeserialize>(
+    idl: &Idl,
+    name: &str,
+    size: usize,
+    padding: usize,
+) {
+    let sample = idl.sample_account(name);
+    let data = idl.encode_account(name, &sample);
+    assert_eq!(data.len() + padding, size, "{name} size");
+    assert_eq!(8 + idl.account_size(name), data.len(), "{name} IDL size");
+
+    let account = T::try_deserialize(&mut &data[..]).unwrap();
+    let mut serialized = Vec::new();
+    account.try_serialize(&mut serialized).unwrap();
+    assert_eq!(serialized, data, "{name} layout");
+    assert_eq!(idl.decode_account(name, &serialized), sample);
+
+    // the discriminator of another account is rejected
+    let mut wrong = data.clone();
+    wrong[0] ^= 1;
+    assert!(
+        T::try_deserialize(&mut &wrong[..]).is_err(),
+        "{name} discriminator"
+    );
+}
+
+#[test]
+fn vault_accounts_match_the_idl() {
+    use ::vault::*;
+
+    let idl = Idl::load("vault");
+    round_trip::<StakeConfig>(
+        &idl,
+        "StakeConfig",
+        STAKE_CONFIG_SIZE,
+        VAULT_STAKE_CONFIG_PADDING,
+    );
+    round_trip::<Vault>(&idl, "Vault", VAULT_SIZE, 0);
+    round_trip::<StakerInfo>(&idl, "StakerInfo", STAKER_INFO_SIZE, 0);
+    round_trip::<StakeDetail>(&idl, "StakeDetail", STAKE_DETAIL_SIZE, 0);
+    round_trip::<PositionReceipt>(&idl, "PositionReceipt", POSITION_RECEIPT_SIZE, 0);
+    round_trip::<Operator>(&idl, "Operator", OPERATOR_SIZE, 0);
+    round_trip::<VoterWeightRecord>(&idl, "VoterWeightRecord", VOTER_WEIGHT_RECORD_SIZE, 0);
+    round_trip::<MaxVoterWeightRecord>(
+        &idl,
+        "MaxVoterWeightRecord",
+        MAX_VOTER_WEIGHT_RECORD_SIZE,
+        0,
+    );
+}
+
+#[test]
+fn fungstake_accounts_match_the_idl() {
+    use ::fungstake::*;
+
+    let idl = Idl::load("fungstake");
+    round_trip::<StakeConfig>(&idl, "StakeConfig", STAKE_CONFIG_SIZE, 0);
+    round_trip::<Vault>(&idl, "Vault", VAULT_SIZE, 0);
+    round_trip::<StakeInfo>(&idl, "StakeInfo", STAKE_INFO_SIZE, 0);
+    round_trip::<AllowlistEntry>(&idl, "AllowlistEntry", ALLOWLIST_ENTRY_SIZE, 0);
+}
+
+#[test]
+fn program_accounts_are_told_apart_by_owner() {
+    let vault_idl = Idl::load("vault");
+    let fungstake_idl = Idl::load("fungstake");
+    let account = |idl: &Idl, name: &str| idl.encode_account(name, &idl.sample_account(name));
+
+    let decoded = |owner, data: Vec<u8>| decode_program_account(owner, &data).unwrap();
+    assert!(matches!(
+        decoded(&vault::ID, account(&vault_idl, "StakeConfig")),
+        Some(ProgramAccount::VaultStakeConfig(_))
+    ));
+    assert!(matches!(
+        decoded(&vault::ID, account(&vault_idl, "Vault")),
+        Some(ProgramAccount::VaultVault(_))
+    ));
+    assert!(matches!(
+        decoded(&vault::ID, account(&vault_idl, "StakerInfo")),
+        Some(ProgramAccount::VaultStakerInfo(_))
+    ));
+    assert!(matches!(
+        decoded(&vault::ID, account(&vault_idl, "StakeDetail")),
+        Some(ProgramAccount::VaultStakeDetail(_))
+    ));
+    assert!(matches!(
+        decoded(&fungstake::ID, account(&fungstake_idl, "StakeConfig")),
+        Some(ProgramAccount::FungstakeStakeConfig(_))
+    ));
+    assert!(matches!(
+        decoded(&fungstake::ID, account(&fungstake_idl, "Vault")),
+        Some(ProgramAccount::FungstakeVault(_))
+    ));
+    assert!(matches!(
+        decoded(&fungstake::ID, account(&fungstake_idl, "StakeInfo")),
+        Some(ProgramAccount::FungstakeStakeInfo(_))
+    ));
+
+    // other accounts of the programs and accounts of other owners are skipped
+    assert!(decoded(&vault::ID, account(&vault_idl, "Operator")).is_none());
+    assert!(decoded(&fungstake::ID, account(&fungstake_idl, "AllowlistEntry")).is_none());
+    assert!(decoded(
+        &solana_program::system_program::ID,
+        account(&vault_idl, "StakeDetail")
+    )
+    .is_none());
+
+    // both programs name their vault `Vault`, the owner picks the layout
+    let fungstake_vault = account(&fungstake_idl, "Vault");
+    assert!(decode_program_account(&vault::ID, &fungstake_vault).is_err());
+}
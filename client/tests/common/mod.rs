@@ -0,0 +1,459 @@
+//! Checks of the client against the IDL generated by `anchor build`, which is read from
+//! `target/idl`. The IDL is decoded independently of the program types, so a builder or
+//! a size constant drifting from the program layout shows up here.
+
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use serde_json::{json, Map, Value};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+
+/// max length of the strings stored in accounts, the IDL does not carry it
+pub const MAX_STRING_LEN: usize = vault::constant::constants::MAX_RECEIPT_URI_LEN;
+
+pub struct Idl {
+    pub program_id: Pubkey,
+    idl: Value,
+}
+
+impl Idl {
+    pub fn load(program: &str) -> Self {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../target/idl")
+            .join(format!("{program}.json"));
+        let file = std::fs::read(&path).unwrap_or_else(|err| {
+            panic!(
+                "reading {}, run `anchor build` first: {err}",
+                path.display()
+            )
+        });
+        let idl: Value = serde_json::from_slice(&file).unwrap();
+        Idl {
+            program_id: idl["address"].as_str().unwrap().parse().unwrap(),
+            idl,
+        }
+    }
+
+    pub fn instruction_names(&self) -> Vec<String> {
+        self.idl["instructions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|instruction| instruction["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    fn find<'a>(&'a self, list: &str, name: &str) -> &'a Value {
+        self.idl[list]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|item| item["name"] == name)
+            .unwrap_or_else(|| panic!("{name} is not in the {list} of the IDL"))
+    }
+
+    fn defined(&self, name: &str) -> &Value {
+        &self.find("types", name)["type"]
+    }
+
+    pub fn account_discriminator(&self, name: &str) -> Vec<u8> {
+        bytes(&self.find("accounts", name)["discriminator"])
+    }
+
+    /// largest serialized size of account `name` without its discriminator
+    pub fn account_size(&self, name: &str) -> usize {
+        self.max_size(&json!({ "defined": { "name": name } }))
+    }
+
+    fn max_size(&self, ty: &Value) -> usize {
+        if let Some(primitive) = ty.as_str() {
+            return match primitive {
+                "bool" | "u8" | "i8" => 1,
+                "u16" | "i16" => 2,
+                "u32" | "i32" => 4,
+                "u64" | "i64" => 8,
+                "u128" | "i128" => 16,
+                "pubkey" => 32,
+                "string" => 4 + MAX_STRING_LEN,
+                other => panic!("unsupported IDL type {other}"),
+            };
+        }
+        if let Some(inner) = ty.get("option") {
+            return 1 + self.max_size(inner);
+        }
+        if let Some(array) = ty.get("array") {
+            return self.max_size(&array[0]) * array[1].as_u64().unwrap() as usize;
+        }
+        let defined = self.defined(ty["defined"]["name"].as_str().unwrap());
+        match defined["kind"].as_str().unwrap() {
+            "struct" => defined["fields"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|field| self.max_size(&field["type"]))
+                .sum(),
+            // only fieldless enums are stored in accounts
+            "enum" => 1,
+            kind => panic!("unsupported IDL type kind {kind}"),
+        }
+    }
+
+    /// decode account data with the IDL layout, after checking its discriminator
+    pub fn decode_account(&self, name: &str, data: &[u8]) -> Value {
+        assert_eq!(
+            data[..8],
+            self.account_discriminator(name)[..],
+            "{name} discriminator"
+        );
+        let mut rest = &data[8..];
+        self.decode(&json!({ "defined": { "name": name } }), &mut rest)
+    }
+
+    /// borsh value of `ty` as json: integers wider than u64 and pubkeys are strings,
+    /// enums are their variant name
+    pub fn decode(&self, ty: &Value, data: &mut &[u8]) -> Value {
+        if let Some(primitive) = ty.as_str() {
+            return match primitive {
+                "bool" => match take(data, 1)[0] {
+                    0 => json!(false),
+                    1 => json!(true),
+                    other => panic!("invalid bool {other}"),
+                },
+                "u8" => json!(take(data, 1)[0]),
+                "u16" => json!(u16::from_le_bytes(take(data, 2).try_into().unwrap())),
+                "u32" => json!(u32::from_le_bytes(take(data, 4).try_into().unwrap())),
+                "u64" => json!(u64::from_le_bytes(take(data, 8).try_into().unwrap())),
+                "i64" => json!(i64::from_le_bytes(take(data, 8).try_into().unwrap())),
+                "u128" => {
+                    json!(u128::from_le_bytes(take(data, 16).try_into().unwrap()).to_string())
+                }
+                "i128" => {
+                    json!(i128::from_le_bytes(take(data, 16).try_into().unwrap()).to_string())
+                }
+                "pubkey" => json!(Pubkey::try_from(take(data, 32)).unwrap().to_string()),
+                "string" => {
+                    let len = u32::from_le_bytes(take(data, 4).try_into().unwrap()) as usize;
+                    json!(String::from_utf8(take(data, len).to_vec()).unwrap())
+                }
+                other => panic!("unsupported IDL type {other}"),
+            };
+        }
+        if let Some(inner) = ty.get("option") {
+            return match take(data, 1)[0] {
+                0 => Value::Null,
+                1 => self.decode(inner, data),
+                other => panic!("invalid option tag {other}"),
+            };
+        }
+        if let Some(inner) = ty.get("vec") {
+            let len = u32::from_le_bytes(take(data, 4).try_into().unwrap());
+            return Value::Array((0..len).map(|_| self.decode(inner, data)).collect());
+        }
+        if let Some(array) = ty.get("array") {
+            let len = array[1].as_u64().unwrap();
+            return Value::Array((0..len).map(|_| self.decode(&array[0], data)).collect());
+        }
+        let defined = self.defined(ty["defined"]["name"].as_str().unwrap());
+        match defined["kind"].as_str().unwrap() {
+            "struct" => Value::Object(
+                defined["fields"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|field| {
+                        let name = field["name"].as_str().unwrap().to_string();
+                        (name, self.decode(&field["type"], data))
+                    })
+                    .collect(),
+            ),
+            "enum" => {
+                let variant = take(data, 1)[0] as usize;
+                defined["variants"][variant]["name"].clone()
+            }
+            kind => panic!("unsupported IDL type kind {kind}"),
+        }
+    }
+
+    /// account `name` with a distinct value in every field, strings at their max length
+    pub fn sample_account(&self, name: &str) -> Value {
+        let mut next = 0u64;
+        self.sample(&json!({ "defined": { "name": name } }), &mut next)
+    }
+
+    fn sample(&self, ty: &Value, next: &mut u64) -> Value {
+        *next += 1;
+        let n = *next;
+        if let Some(primitive) = ty.as_str() {
+            return match primitive {
+                "bool" => json!(n.is_multiple_of(2)),
+                "u8" => json!(n % 200),
+                "u16" | "u32" | "u64" => json!(n * 1_000 + 7),
+                "i64" => json!(-(n as i64) * 1_000),
+                "u128" | "i128" => json!((n as u128 * 1_000_000_000_000_000_000).to_string()),
+                "pubkey" => json!(Pubkey::new_from_array([n as u8; 32]).to_string()),
+                "string" => json!("u".repeat(MAX_STRING_LEN)),
+                other => panic!("unsupported IDL type {other}"),
+            };
+        }
+        if let Some(inner) = ty.get("option") {
+            return self.sample(inner, next);
+        }
+        if let Some(array) = ty.get("array") {
+            let len = array[1].as_u64().unwrap();
+            return Value::Array((0..len).map(|_| self.sample(&array[0], next)).collect());
+        }
+        let defined = self.defined(ty["defined"]["name"].as_str().unwrap());
+        match defined["kind"].as_str().unwrap() {
+            "struct" => Value::Object(
+                defined["fields"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|field| {
+                        let name = field["name"].as_str().unwrap().to_string();
+                        (name, self.sample(&field["type"], next))
+                    })
+                    .collect(),
+            ),
+            "enum" => {
+                let variants = defined["variants"].as_array().unwrap();
+                variants[n as usize % variants.len()]["name"].clone()
+            }
+            kind => panic!("unsupported IDL type kind {kind}"),
+        }
+    }
+
+    /// account data of `value` with the IDL layout, discriminator included
+    pub fn encode_account(&self, name: &str, value: &Value) -> Vec<u8> {
+        let mut data = self.account_discriminator(name);
+        self.encode(&json!({ "defined": { "name": name } }), value, &mut data);
+        data
+    }
+
+    /// borsh encoding of a json value in the format of `decode`
+    pub fn encode(&self, ty: &Value, value: &Value, data: &mut Vec<u8>) {
+        if let Some(primitive) = ty.as_str() {
+            match primitive {
+                "bool" => data.push(value.as_bool().unwrap() as u8),
+                "u8" => data.push(value.as_u64().unwrap() as u8),
+                "u16" => data.extend((value.as_u64().unwrap() as u16).to_le_bytes()),
+                "u32" => data.extend((value.as_u64().unwrap() as u32).to_le_bytes()),
+                "u64" => data.extend(value.as_u64().unwrap().to_le_bytes()),
+                "i64" => data.extend(value.as_i64().unwrap().to_le_bytes()),
+                "u128" => data.extend(
+                    value
+                        .as_str()
+                        .unwrap()
+                        .parse::<u128>()
+                        .unwrap()
+                        .to_le_bytes(),
+                ),
+                "i128" => data.extend(
+                    value
+                        .as_str()
+                        .unwrap()
+                        .parse::<i128>()
+                        .unwrap()
+                        .to_le_bytes(),
+                ),
+                "pubkey" => data.extend(
+                    value
+                        .as_str()
+                        .unwrap()
+                        .parse::<Pubkey>()
+                        .unwrap()
+                        .to_bytes(),
+                ),
+                "string" => {
+                    let string = value.as_str().unwrap();
+                    data.extend((string.len() as u32).to_le_bytes());
+                    data.extend(string.as_bytes());
+                }
+                other => panic!("unsupported IDL type {other}"),
+            }
+            return;
+        }
+        if let Some(inner) = ty.get("option") {
+            if value.is_null() {
+                data.push(0);
+            } else {
+                data.push(1);
+                self.encode(inner, value, data);
+            }
+            return;
+        }
+        if let Some(inner) = ty.get("vec") {
+            let items = value.as_array().unwrap();
+            data.extend((items.len() as u32).to_le_bytes());
+            items.iter().for_each(|item| self.encode(inner, item, data));
+            return;
+        }
+        if let Some(array) = ty.get("array") {
+            let items = value.as_array().unwrap();
+            assert_eq!(items.len() as u64, array[1].as_u64().unwrap());
+            items
+                .iter()
+                .for_each(|item| self.encode(&array[0], item, data));
+            return;
+        }
+        let defined = self.defined(ty["defined"]["name"].as_str().unwrap());
+        match defined["kind"].as_str().unwrap() {
+            "struct" => {
+                for field in defined["fields"].as_array().unwrap() {
+                    self.encode(
+                        &field["type"],
+                        &value[field["name"].as_str().unwrap()],
+                        data,
+                    );
+                }
+            }
+            "enum" => {
+                let variant = defined["variants"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .position(|variant| variant["name"] == *value)
+                    .unwrap_or_else(|| panic!("unknown variant {value}"));
+                data.push(variant as u8);
+            }
+            kind => panic!("unsupported IDL type kind {kind}"),
+        }
+    }
+
+    /// check `instruction` against the IDL instruction `name`: discriminator, signer and
+    /// writable flags, fixed addresses and PDA seeds of its accounts. Returns its decoded
+    /// args, the data must hold nothing else. Accounts past the IDL ones are remaining accounts
+    pub fn check_instruction(&self, name: &str, instruction: &Instruction) -> Value {
+        let idl_instruction = self.find("instructions", name);
+        assert_eq!(instruction.program_id, self.program_id, "{name} program");
+        assert_eq!(
+            instruction.data[..8],
+            bytes(&idl_instruction["discriminator"])[..],
+            "{name} discriminator"
+        );
+
+        let mut data = &instruction.data[8..];
+        let mut args = Map::new();
+        let mut raw_args = Map::new();
+        for arg in idl_instruction["args"].as_array().unwrap() {
+            let before = data;
+            let value = self.decode(&arg["type"], &mut data);
+            let raw = &before[..before.len() - data.len()];
+            let name = arg["name"].as_str().unwrap().to_string();
+            raw_args.insert(name.clone(), json!(raw));
+            args.insert(name, value);
+        }
+        assert!(
+            data.is_empty(),
+            "{name} data has {} trailing bytes",
+            data.len()
+        );
+
+        let idl_accounts = idl_instruction["accounts"].as_array().unwrap();
+        assert!(
+            instruction.accounts.len() >= idl_accounts.len(),
+            "{name} has {} accounts, the IDL {}",
+            instruction.accounts.len(),
+            idl_accounts.len()
+        );
+        let keys: Map<String, Value> = idl_accounts
+            .iter()
+            .zip(&instruction.accounts)
+            .map(|(account, meta)| {
+                (
+                    account["name"].as_str().unwrap().to_string(),
+                    json!(meta.pubkey.to_string()),
+                )
+            })
+            .collect();
+
+        for (account, meta) in idl_accounts.iter().zip(&instruction.accounts) {
+            let account_name = account["name"].as_str().unwrap();
+            // anchor passes the program id in place of a missing optional account
+            if account["optional"] == true && meta.pubkey == self.program_id {
+                assert!(
+                    !meta.is_writable && !meta.is_signer,
+                    "{name}.{account_name}"
+                );
+                continue;
+            }
+            assert_eq!(
+                meta.is_signer,
+                account["signer"] == true,
+                "{name}.{account_name} signer"
+            );
+            assert_eq!(
+                meta.is_writable,
+                account["writable"] == true,
+                "{name}.{account_name} writable"
+            );
+            if let Some(address) = account["address"].as_str() {
+                assert_eq!(
+                    meta.pubkey.to_string(),
+                    address,
+                    "{name}.{account_name} address"
+                );
+            }
+            if let Some(pda) = account.get("pda") {
+                if let Some(expected) = self.derive(pda, &keys, &raw_args) {
+                    assert_eq!(meta.pubkey, expected, "{name}.{account_name} seeds");
+                }
+            }
+        }
+        Value::Object(args)
+    }
+
+    /// PDA described by the IDL, None if a seed reads account data
+    fn derive(
+        &self,
+        pda: &Value,
+        keys: &Map<String, Value>,
+        raw_args: &Map<String, Value>,
+    ) -> Option<Pubkey> {
+        let seed = |seed: &Value| -> Option<Vec<u8>> {
+            match seed["kind"].as_str().unwrap() {
+                "const" => Some(bytes(&seed["value"])),
+                "arg" => Some(bytes(&raw_args[seed["path"].as_str().unwrap()])),
+                "account" => {
+                    let path = seed["path"].as_str().unwrap();
+                    let key = keys.get(path)?.as_str().unwrap();
+                    Some(key.parse::<Pubkey>().unwrap().to_bytes().to_vec())
+                }
+                kind => panic!("unsupported seed kind {kind}"),
+            }
+        };
+        let seeds = pda["seeds"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(seed)
+            .collect::<Option<Vec<_>>>()?;
+        let program_id = match pda.get("program") {
+            Some(program) => Pubkey::try_from(seed(program)?.as_slice()).unwrap(),
+            None => self.program_id,
+        };
+        let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        Some(Pubkey::find_program_address(&seeds, &program_id).0)
+    }
+}
+
+fn bytes(value: &Value) -> Vec<u8> {
+    value
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|byte| byte.as_u64().unwrap() as u8)
+        .collect()
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> &'a [u8] {
+    assert!(
+        data.len() >= len,
+        "data ends {} bytes early",
+        len - data.len()
+    );
+    let (taken, rest) = data.split_at(len);
+    *data = rest;
+    taken
+}
@@ -0,0 +1,448 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::Idl;
+use serde_json::{json, Value};
+use solana_program::instruction::Instruction;
+use stake_client::accounts::vault::VoterWeightAction;
+use stake_client::instructions::{fungstake, vault};
+use stake_client::pda::vault as vault_pda;
+use stake_client::test_utils::key;
+
+/// every builder must match the IDL, and every IDL instruction must have a builder
+fn check_all(idl: &Idl, cases: Vec<(&str, Instruction, Value)>) {
+    for (name, instruction, expected_args) in &cases {
+        let args = idl.check_instruction(name, instruction);
+        assert_eq!(&args, expected_args, "{name} args");
+    }
+
+    let built: BTreeSet<String> = cases.iter().map(|(name, _, _)| name.to_string()).collect();
+    let idl_names: BTreeSet<String> = idl.instruction_names().into_iter().collect();
+    assert_eq!(built, idl_names);
+}
+
+#[test]
+fn vault_instructions_match_the_idl() {
+    let (signer, other, mint) = (key(1), key(2), key(3));
+    let (realm, target, treasury) = (key(4), key(5), key(6));
+    let lock = 86_400u64;
+
+    let cases = vec![
+        ("initialize", vault::initialize(&signer, &mint), json!({})),
+        (
+            "create_vault",
+            vault::create_vault(&signer, &mint, lock),
+            json!({ "lock_period": lock }),
+        ),
+        (
+            "stake",
+            vault::stake(&signer, &mint, lock, 4, 500, None),
+            json!({ "lock_period": lock, "amount": 500 }),
+        ),
+        (
+            "destake",
+            vault::destake(&signer, &mint, 2, lock, 100, true),
+            json!({ "id": 2, "lock_period": lock, "amount": 100, "auto_close": true }),
+        ),
+        (
+            "early_destake",
+            vault::early_destake(&signer, &mint, 2, lock, 100, Some(treasury)),
+            json!({ "id": 2, "lock_period": lock, "amount": 100 }),
+        ),
+        (
+            "set_early_unstake_config",
+            vault::set_early_unstake_config(&signer, &mint, lock, true, 2_500, false, treasury),
+            json!({
+                "lock_period": lock,
+                "allow_early_unstake": true,
+                "max_early_unstake_penalty_bps": 2_500,
+                "redistribute_penalty": false,
+                "treasury": treasury.to_string(),
+            }),
+        ),
+        (
+            "merge_positions",
+            vault::merge_positions(&signer, &mint, lock, 1, vec![2, 3]),
+            json!({ "lock_period": lock, "target_id": 1, "source_ids": [2, 3] }),
+        ),
+        (
+            "split_position",
+            vault::split_position(&signer, &mint, 1, lock, 3, 40),
+            json!({ "id": 1, "lock_period": lock, "amount": 40 }),
+        ),
+        (
+            "transfer_position",
+            vault::transfer_position(&signer, &other, &mint, 1, lock, 0),
+            json!({ "id": 1, "lock_period": lock }),
+        ),
+        (
+            "mint_position_receipt",
+            vault::mint_position_receipt(&signer, &mint, 1, lock),
+            json!({ "id": 1, "lock_period": lock }),
+        ),
+        (
+            "destake_with_receipt",
+            vault::destake_with_receipt(&signer, &mint, lock, &key(7), &other, &key(8), &key(9)),
+            json!({ "lock_period": lock }),
+        ),
+        (
+            "destake_many",
+            vault::destake_many(&signer, &mint, lock, &[1, 2]),
+            json!({ "lock_period": lock }),
+        ),
+        (
+            "close_position",
+            vault::close_position(&signer, &mint, 1, lock),
+            json!({ "id": 1, "lock_period": lock }),
+        ),
+        (
+            "close_staker_info",
+            vault::close_staker_info(&signer, &mint, lock),
+            json!({ "lock_period": lock }),
+        ),
+        (
+            "set_vault_limits",
+            vault::set_vault_limits(&signer, &mint, lock, 1_000, 100, 10),
+            json!({
+                "lock_period": lock,
+                "max_total_staked": 1_000,
+                "max_stake_per_staker": 100,
+                "min_stake_amount": 10,
+            }),
+        ),
+        (
+            "set_lock_period_bounds",
+            vault::set_lock_period_bounds(&signer, &mint, 60, lock),
+            json!({ "min_lock_period": 60, "max_lock_period": lock }),
+        ),
+        (
+            "set_receipt_uri",
+            vault::set_receipt_uri(&signer, &mint, "https://example.com/".to_string()),
+            json!({ "receipt_uri": "https://example.com/" }),
+        ),
+        (
+            "set_vote_lock_period",
+            vault::set_vote_lock_period(&signer, &mint, 3_600),
+            json!({ "vote_lock_period": 3_600 }),
+        ),
+        (
+            "set_vault_status",
+            vault::set_vault_status(&signer, &mint, lock, false),
+            json!({ "lock_period": lock, "is_active": false }),
+        ),
+        (
+            "close_vault",
+            vault::close_vault(&signer, &mint, lock),
+            json!({ "lock_period": lock }),
+        ),
+        (
+            "set_unbonding_period",
+            vault::set_unbonding_period(&signer, &mint, lock, 600),
+            json!({ "lock_period": lock, "unbonding_period": 600 }),
+        ),
+        (
+            "request_unstake",
+            vault::request_unstake(&signer, &mint, 1, lock),
+            json!({ "id": 1, "lock_period": lock }),
+        ),
+        (
+            "withdraw",
+            vault::withdraw(&signer, &mint, 1, lock, false),
+            json!({ "id": 1, "lock_period": lock, "auto_close": false }),
+        ),
+        (
+            "set_renew_window",
+            vault::set_renew_window(&signer, &mint, lock, 300),
+            json!({ "lock_period": lock, "renew_window": 300 }),
+        ),
+        (
+            "enable_auto_renew",
+            vault::enable_auto_renew(&signer, &mint, 1, lock),
+            json!({ "id": 1, "lock_period": lock }),
+        ),
+        (
+            "disable_auto_renew",
+            vault::disable_auto_renew(&signer, &mint, 1, lock),
+            json!({ "id": 1, "lock_period": lock }),
+        ),
+        (
+            "grant_stake",
+            vault::grant_stake(&signer, &other, &mint, lock, 0, 700, true),
+            json!({ "lock_period": lock, "amount": 700, "revocable": true }),
+        ),
+        (
+            "revoke_grant",
+            vault::revoke_grant(&signer, &other, &mint, 1, lock),
+            json!({ "id": 1, "lock_period": lock }),
+        ),
+        (
+            "get_voting_power",
+            vault::get_voting_power(&mint, &other, 1_700_000_000, &[(lock, other, 1)]),
+            json!({ "owner": other.to_string(), "timestamp": 1_700_000_000 }),
+        ),
+        (
+            "get_total_voting_power",
+            vault::get_total_voting_power(&mint, -1),
+            json!({ "timestamp": -1 }),
+        ),
+        (
+            "get_twab",
+            vault::get_twab(&mint, &other, lock, 10, 20),
+            json!({ "owner": other.to_string(), "lock_period": lock, "start": 10, "end": 20 }),
+        ),
+        (
+            "create_voter_weight_record",
+            vault::create_voter_weight_record(&signer, &mint, realm),
+            json!({ "realm": realm.to_string() }),
+        ),
+        (
+            "update_voter_weight_record",
+            vault::update_voter_weight_record(
+                &signer,
+                &mint,
+                realm,
+                VoterWeightAction::CastVote,
+                Some(target),
+                Some(other),
+                &[(lock, signer, 1)],
+            ),
+            json!({ "realm": realm.to_string(), "action": "CastVote", "target": target.to_string() }),
+        ),
+        (
+            "create_max_voter_weight_record",
+            vault::create_max_voter_weight_record(&signer, &mint, realm),
+            json!({ "realm": realm.to_string() }),
+        ),
+        (
+            "update_max_voter_weight_record",
+            vault::update_max_voter_weight_record(&mint, realm),
+            json!({ "realm": realm.to_string() }),
+        ),
+        (
+            "register_operator",
+            vault::register_operator(&signer, &other, &mint, 1_000),
+            json!({ "commission_bps": 1_000 }),
+        ),
+        (
+            "set_operator_config",
+            vault::set_operator_config(&signer, &mint, 500),
+            json!({ "commission_bps": 500 }),
+        ),
+        (
+            "set_operator_status",
+            vault::set_operator_status(&signer, &mint, &other, false),
+            json!({ "operator_wallet": other.to_string(), "is_active": false }),
+        ),
+        (
+            "delegate",
+            vault::delegate(&signer, &mint, 1, lock, &other, Some(target)),
+            json!({ "id": 1, "lock_period": lock }),
+        ),
+        (
+            "undelegate",
+            vault::undelegate(&signer, &mint, 1, lock, &other),
+            json!({ "id": 1, "lock_period": lock }),
+        ),
+        (
+            "audit_vault",
+            vault::audit_vault(&mint, lock),
+            json!({ "lock_period": lock }),
+        ),
+        (
+            "migrate_stake_config",
+            vault::migrate_stake_config(&signer, &mint),
+            json!({}),
+        ),
+        (
+            "migrate_vault",
+            vault::migrate_vault(&signer, &mint, lock),
+            json!({ "lock_period": lock }),
+        ),
+        (
+            "migrate_staker_info",
+            vault::migrate_staker_info(&signer, &mint, lock, &other),
+            json!({ "lock_period": lock, "staker": other.to_string() }),
+        ),
+        (
+            "migrate_stake_detail",
+            vault::migrate_stake_detail(&signer, &mint, lock, &other, 3),
+            json!({ "lock_period": lock, "staker": other.to_string(), "id": 3 }),
+        ),
+    ];
+    check_all(&Idl::load("vault"), cases);
+}
+
+#[test]
+fn fungstake_instructions_match_the_idl() {
+    let (signer, mint, reward_mint) = (key(1), key(2), key(3));
+    let (price_feed, wallet) = (key(4), key(5));
+
+    let cases = vec![
+        (
+            "initialize",
+            fungstake::initialize(&signer, &mint, 30, 10, 1_000),
+            json!({ "lock_period": 30, "lock_extend_time": 10, "soft_cap": 1_000 }),
+        ),
+        (
+            "create_vault",
+            fungstake::create_vault(&signer, &mint, &reward_mint),
+            json!({}),
+        ),
+        (
+            "stake",
+            fungstake::stake(
+                &signer,
+                &mint,
+                &reward_mint,
+                50,
+                Some(price_feed),
+                Some(0),
+                true,
+            ),
+            json!({ "amount": 50 }),
+        ),
+        (
+            "destake",
+            fungstake::destake(&signer, &mint, &reward_mint, 20, true),
+            json!({ "amount": 20, "auto_close": true }),
+        ),
+        (
+            "claim_reward",
+            fungstake::claim_reward(&signer, &mint, &reward_mint),
+            json!({}),
+        ),
+        (
+            "close_stake_info",
+            fungstake::close_stake_info(&signer, &mint, &reward_mint),
+            json!({}),
+        ),
+        (
+            "audit_vault",
+            fungstake::audit_vault(&mint, &reward_mint),
+            json!({}),
+        ),
+        (
+            "set_price_feed",
+            fungstake::set_price_feed(&signer, &mint, Some(price_feed), 5_000, 60, 100),
+            json!({ "soft_cap_usd": 5_000, "max_price_age": 60, "max_confidence_bps": 100 }),
+        ),
+        (
+            "set_phase",
+            fungstake::set_phase(&signer, &mint, &reward_mint, 1, 100, 200, 50, 2, true),
+            json!({
+                "phase_id": 1,
+                "start_time": 100,
+                "end_time": 200,
+                "wallet_cap": 50,
+                "weight": 2,
+                "requires_allowlist": true,
+            }),
+        ),
+        (
+            "set_allowlist_entry",
+            fungstake::set_allowlist_entry(&signer, &mint, &reward_mint, 1, &wallet, true),
+            json!({ "phase_id": 1, "wallet": wallet.to_string(), "allowed": true }),
+        ),
+        (
+            "set_kyc_attestor",
+            fungstake::set_kyc_attestor(&signer, &mint, &wallet),
+            json!({ "kyc_attestor": wallet.to_string() }),
+        ),
+        (
+            "set_sale_terms",
+            fungstake::set_sale_terms(&signer, &mint, &reward_mint, 3, 900),
+            json!({ "sale_rate": 3, "sale_allocation": 900 }),
+        ),
+        (
+            "withdraw_sale_proceeds",
+            fungstake::withdraw_sale_proceeds(&signer, &mint, &reward_mint),
+            json!({}),
+        ),
+        (
+            "migrate_stake_config",
+            fungstake::migrate_stake_config(&signer, &mint),
+            json!({}),
+        ),
+        (
+            "migrate_vault",
+            fungstake::migrate_vault(&signer, &mint, &reward_mint, true),
+            json!({}),
+        ),
+        (
+            "migrate_stake_info",
+            fungstake::migrate_stake_info(&signer, &mint, &reward_mint, &wallet),
+            json!({ "staker": wallet.to_string() }),
+        ),
+    ];
+    check_all(&Idl::load("fungstake"), cases);
+}
+
+#[test]
+fn voting_power_positions_are_remaining_accounts() {
+    let (owner, mint) = (key(1), key(2));
+    let lock = 86_400u64;
+    let stake_config = vault_pda::stake_config(&mint);
+    let vault_key = vault_pda::vault(&stake_config, lock);
+    let stake_detail = vault_pda::stake_detail(&vault_pda::staker_info(&vault_key, &owner), 3);
+
+    let read = vault::get_voting_power(&mint, &owner, 0, &[(lock, owner, 3)]);
+    let remaining = &read.accounts[read.accounts.len() - 2..];
+    assert_eq!(remaining[0].pubkey, vault_key);
+    assert_eq!(remaining[1].pubkey, stake_detail);
+    assert!(!remaining[1].is_writable);
+
+    // stake details counted in a vote get locked, so they are writable
+    for (action, writable) in [
+        (VoterWeightAction::CastVote, true),
+        (VoterWeightAction::CreateProposal, false),
+    ] {
+        let update = vault::update_voter_weight_record(
+            &owner,
+            &mint,
+            key(4),
+            action,
+            None,
+            None,
+            &[(lock, owner, 3)],
+        );
+        let remaining = &update.accounts[update.accounts.len() - 2..];
+        assert_eq!(remaining[1].pubkey, stake_detail);
+        assert_eq!(remaining[1].is_writable, writable);
+    }
+}
+
+#[test]
+fn operator_voting_power_uses_the_staker_positions() {
+    let (operator, staker, mint) = (key(1), key(2), key(3));
+    let lock = 86_400u64;
+    let vault_key = vault_pda::vault(&vault_pda::stake_config(&mint), lock);
+    let own = vault_pda::stake_detail(&vault_pda::staker_info(&vault_key, &operator), 1);
+    let delegated = vault_pda::stake_detail(&vault_pda::staker_info(&vault_key, &staker), 2);
+
+    // the operator votes its own position and one delegated to it by another staker
+    let read = vault::get_voting_power(
+        &mint,
+        &operator,
+        0,
+        &[(lock, operator, 1), (lock, staker, 2)],
+    );
+    let remaining = &read.accounts[read.accounts.len() - 4..];
+    assert_eq!(remaining[0].pubkey, vault_key);
+    assert_eq!(remaining[1].pubkey, own);
+    assert_eq!(remaining[2].pubkey, vault_key);
+    assert_eq!(remaining[3].pubkey, delegated);
+}
+
+#[test]
+fn grant_stake_is_signed_by_the_grantor_alone() {
+    let (grantor, beneficiary, mint) = (key(1), key(2), key(3));
+    let grant = vault::grant_stake(&grantor, &beneficiary, &mint, 86_400, 0, 100, true);
+    let signers: Vec<_> = grant
+        .accounts
+        .iter()
+        .filter(|meta| meta.is_signer)
+        .map(|meta| meta.pubkey)
+        .collect();
+    assert_eq!(signers, [grantor]);
+}
@@ -0,0 +1,143 @@
+//! Addresses derived by the client, checked against seeds spelled out by hand so a renamed
+//! seed constant or a changed seed order is caught.
+
+use anchor_spl::associated_token::get_associated_token_address;
+use solana_program::pubkey::Pubkey;
+use stake_client::{
+    pda::{fungstake, vault},
+    test_utils::key,
+};
+
+fn vault_pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &::vault::ID).0
+}
+
+fn fungstake_pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &::fungstake::ID).0
+}
+
+#[test]
+fn vault_addresses() {
+    let (mint, owner, realm) = (key(1), key(2), key(3));
+    let lock = 86_400u64;
+
+    let stake_config = vault::stake_config(&mint);
+    assert_eq!(stake_config, vault_pda(&[b"staking_config", mint.as_ref()]));
+
+    // the lock period is a little endian u64 seed
+    let vault_key = vault::vault(&stake_config, lock);
+    assert_eq!(
+        vault_key,
+        vault_pda(&[b"staking_vault", stake_config.as_ref(), &lock.to_le_bytes()])
+    );
+    assert_ne!(vault_key, vault::vault(&stake_config, lock + 1));
+    assert_eq!(
+        vault::vault_token_account(&vault_key, &mint),
+        get_associated_token_address(&vault_key, &mint)
+    );
+
+    let staker_info = vault::staker_info(&vault_key, &owner);
+    assert_eq!(
+        staker_info,
+        vault_pda(&[b"staker_info", vault_key.as_ref(), owner.as_ref()])
+    );
+    let stake_detail = vault::stake_detail(&staker_info, 3);
+    assert_eq!(
+        stake_detail,
+        vault_pda(&[b"stake_detail", staker_info.as_ref(), &3u64.to_le_bytes()])
+    );
+
+    let position_mint = vault::position_mint(&stake_detail);
+    assert_eq!(
+        position_mint,
+        vault_pda(&[b"position_mint", stake_detail.as_ref()])
+    );
+    assert_eq!(
+        vault::position_receipt(&position_mint),
+        vault_pda(&[b"position_receipt", position_mint.as_ref()])
+    );
+
+    assert_eq!(
+        vault::operator(&stake_config, &owner),
+        vault_pda(&[b"operator", stake_config.as_ref(), owner.as_ref()])
+    );
+    assert_eq!(
+        vault::voter_weight_record(&realm, &mint, &owner),
+        vault_pda(&[
+            b"voter_weight_record",
+            realm.as_ref(),
+            mint.as_ref(),
+            owner.as_ref()
+        ])
+    );
+    assert_eq!(
+        vault::max_voter_weight_record(&realm, &mint),
+        vault_pda(&[b"max_voter_weight_record", realm.as_ref(), mint.as_ref()])
+    );
+}
+
+#[test]
+fn next_stake_detail_follows_the_current_id() {
+    let staker_info = key(4);
+    // a staker without a staker info yet opens position 1
+    assert_eq!(
+        vault::next_stake_detail(&staker_info, 0),
+        vault::stake_detail(&staker_info, 1)
+    );
+    assert_eq!(
+        vault::next_stake_detail(&staker_info, 7),
+        vault::stake_detail(&staker_info, 8)
+    );
+}
+
+#[test]
+fn receipt_metadata_is_owned_by_token_metadata() {
+    let receipt_mint = key(5);
+    let metadata_program: Pubkey = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+        .parse()
+        .unwrap();
+    let (expected, _) = Pubkey::find_program_address(
+        &[
+            b"metadata",
+            metadata_program.as_ref(),
+            receipt_mint.as_ref(),
+        ],
+        &metadata_program,
+    );
+    assert_eq!(vault::receipt_metadata(&receipt_mint), expected);
+}
+
+#[test]
+fn fungstake_addresses() {
+    let (mint, reward_mint, owner) = (key(1), key(2), key(3));
+
+    let stake_config = fungstake::stake_config(&mint);
+    assert_eq!(
+        stake_config,
+        fungstake_pda(&[b"staking_config", mint.as_ref()])
+    );
+    // same seeds as the vault program, but a different program
+    assert_ne!(stake_config, vault::stake_config(&mint));
+
+    let vault_key = fungstake::vault(&stake_config, &reward_mint);
+    assert_eq!(
+        vault_key,
+        fungstake_pda(&[
+            b"staking_vault",
+            stake_config.as_ref(),
+            reward_mint.as_ref()
+        ])
+    );
+    assert_eq!(
+        fungstake::stake_info(&vault_key, &owner),
+        fungstake_pda(&[b"stake_info", vault_key.as_ref(), owner.as_ref()])
+    );
+    assert_eq!(
+        fungstake::allowlist_entry(&vault_key, 2, &owner),
+        fungstake_pda(&[b"allowlist", vault_key.as_ref(), &[2], owner.as_ref()])
+    );
+    assert_ne!(
+        fungstake::allowlist_entry(&vault_key, 2, &owner),
+        fungstake::allowlist_entry(&vault_key, 3, &owner)
+    );
+}
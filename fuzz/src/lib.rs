@@ -0,0 +1,587 @@
+//! Randomized invariant harness for the fungstake program.
+//!
+//! [`FungstakeHarness`] runs the program natively inside `solana-program-test` with a
+//! few funded users, on vaults with or without launch phases and sale terms, applies random
+//! [`Op`] sequences (stake, destake, claim_reward, reward funding, proceeds withdrawal and
+//! clock warps, some past the refund deadline of unfunded sales) and checks
+//! [`FungstakeHarness::check_invariants`] after every step. Rejected instructions are
+//! expected, only invariant breaks fail.
+
+use anchor_lang::AccountDeserialize;
+use anchor_spl::{
+    associated_token::{get_associated_token_address, spl_associated_token_account},
+    token::spl_token::{
+        self,
+        state::{Account as TokenAccount, AccountState, Mint},
+    },
+};
+use fungstake::{
+    constant::constants::{SALE_RATE_PRECISION, SALE_REFUND_DELAY},
+    StakeInfo, Vault, MAX_PHASES,
+};
+use rand::Rng;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account, account_info::AccountInfo, clock::Clock, commitment_config::CommitmentLevel,
+    compute_budget::ComputeBudgetInstruction, entrypoint::ProgramResult, instruction::Instruction,
+    program_option::COption, program_pack::Pack, pubkey::Pubkey, rent::Rent, signature::Keypair,
+    signer::Signer, transaction::Transaction,
+};
+use stake_client::{instructions::fungstake as ix, pda::fungstake as pda};
+
+/// staking tokens each user holds at genesis
+pub const INITIAL_BALANCE: u64 = 10_000;
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // anchor ties the account infos to the lifetime of the slice, which the test processor
+    // does not provide. Leaking is fine for a test process
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    fungstake::entry(program_id, accounts, data)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Op {
+    Stake {
+        user: usize,
+        amount: u64,
+    },
+    /// `auto_close` closes the drained stake info
+    Destake {
+        user: usize,
+        amount: u64,
+        auto_close: bool,
+    },
+    ClaimReward {
+        user: usize,
+    },
+    /// mint reward tokens to the vault, as the launchpad does at tge
+    FundReward {
+        amount: u64,
+    },
+    /// authority withdraws the stake spent on the sale
+    WithdrawProceeds,
+    Warp {
+        seconds: i64,
+    },
+}
+
+impl Op {
+    /// amounts include 0 and values above balances so rejections are exercised too
+    pub fn random(rng: &mut impl Rng, users: usize) -> Self {
+        let user = rng.gen_range(0..users);
+        match rng.gen_range(0..11) {
+            0..=3 => Op::Stake {
+                user,
+                amount: rng.gen_range(0..=INITIAL_BALANCE / 3),
+            },
+            4..=5 => Op::Destake {
+                user,
+                amount: rng.gen_range(0..=INITIAL_BALANCE / 3),
+                auto_close: rng.gen_bool(0.5),
+            },
+            6 => Op::ClaimReward { user },
+            7 => Op::FundReward {
+                amount: rng.gen_range(1..=1_000_000),
+            },
+            8 => Op::WithdrawProceeds,
+            // rarely past the refund deadline, voids a sale whose reward was not funded in time
+            _ if rng.gen_ratio(1, 15) => Op::Warp {
+                seconds: SALE_REFUND_DELAY + rng.gen_range(1..=40),
+            },
+            _ => Op::Warp {
+                seconds: rng.gen_range(0..=40),
+            },
+        }
+    }
+}
+
+pub struct FungstakeHarness {
+    ctx: ProgramTestContext,
+    authority: Keypair,
+    users: Vec<Keypair>,
+    stake_mint: Pubkey,
+    reward_mint: Pubkey,
+    /// makes every transaction unique, identical ones would be deduplicated
+    nonce: u64,
+}
+
+fn pack<T: Pack>(state: T, owner: Pubkey) -> Account {
+    let mut data = vec![0; T::LEN];
+    state.pack_into_slice(&mut data);
+    Account {
+        lamports: Rent::default().minimum_balance(T::LEN),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// initialized mint account with 0 decimals
+pub fn mint(authority: &Pubkey, supply: u64) -> Account {
+    pack(
+        Mint {
+            mint_authority: COption::Some(*authority),
+            supply,
+            decimals: 0,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        },
+        spl_token::ID,
+    )
+}
+
+/// initialized token account holding `amount`
+pub fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    pack(
+        TokenAccount {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        },
+        spl_token::ID,
+    )
+}
+
+/// system account funded for fees and rent
+pub fn wallet() -> Account {
+    Account::new(1_000_000_000_000, 0, &solana_sdk::system_program::ID)
+}
+
+impl FungstakeHarness {
+    /// random stake config, users funded with INITIAL_BALANCE staking tokens
+    pub async fn new(rng: &mut impl Rng, users: usize) -> Self {
+        let (phases, sale) = (rng.gen_bool(0.5), rng.gen_bool(0.3));
+        Self::with_launch(rng, users, phases, sale).await
+    }
+
+    /// same as `new`, with or without random launch phases and sale terms
+    pub async fn with_launch(rng: &mut impl Rng, users: usize, phases: bool, sale: bool) -> Self {
+        let mut program_test =
+            ProgramTest::new("fungstake", fungstake::ID, processor!(process_instruction));
+
+        let authority = Keypair::new();
+        let users: Vec<Keypair> = (0..users).map(|_| Keypair::new()).collect();
+        let stake_mint = Pubkey::new_unique();
+        let reward_mint = Pubkey::new_unique();
+
+        program_test.add_account(authority.pubkey(), wallet());
+        program_test.add_account(
+            stake_mint,
+            mint(&authority.pubkey(), INITIAL_BALANCE * users.len() as u64),
+        );
+        program_test.add_account(reward_mint, mint(&authority.pubkey(), 0));
+        program_test.add_account(
+            get_associated_token_address(&authority.pubkey(), &stake_mint),
+            token_account(&stake_mint, &authority.pubkey(), 0),
+        );
+        for user in &users {
+            program_test.add_account(user.pubkey(), wallet());
+            program_test.add_account(
+                get_associated_token_address(&user.pubkey(), &stake_mint),
+                token_account(&stake_mint, &user.pubkey(), INITIAL_BALANCE),
+            );
+        }
+
+        let mut harness = FungstakeHarness {
+            ctx: program_test.start_with_context().await,
+            authority,
+            users,
+            stake_mint,
+            reward_mint,
+            nonce: 0,
+        };
+
+        let stake_config_ix = ix::initialize(
+            &harness.authority.pubkey(),
+            &stake_mint,
+            rng.gen_range(1..=30),
+            rng.gen_range(1..=60),
+            rng.gen_range(1..=INITIAL_BALANCE * harness.users.len() as u64),
+        );
+        let vault_ix = ix::create_vault(&harness.authority.pubkey(), &stake_mint, &reward_mint);
+        let vault = harness.vault();
+        let reward_account_ix =
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &harness.authority.pubkey(),
+                &vault,
+                &reward_mint,
+                &spl_token::ID,
+            );
+        for instruction in [stake_config_ix, vault_ix, reward_account_ix] {
+            harness.send_as_authority(instruction).await.unwrap();
+        }
+
+        // half of the runs use consecutive launch phases, only the first two users are
+        // on the allowlists
+        if phases {
+            let clock: Clock = harness.ctx.banks_client.get_sysvar().await.unwrap();
+            let mut start_time = clock.unix_timestamp;
+            for phase_id in 0..rng.gen_range(1..=MAX_PHASES as u8) {
+                let end_time = start_time + rng.gen_range(5..=60);
+                let requires_allowlist = rng.gen_bool(0.3);
+                let wallet_cap = match rng.gen_bool(0.5) {
+                    true => rng.gen_range(1..=INITIAL_BALANCE / 2),
+                    false => 0,
+                };
+                let mut instructions = vec![ix::set_phase(
+                    &harness.authority.pubkey(),
+                    &stake_mint,
+                    &reward_mint,
+                    phase_id,
+                    start_time,
+                    end_time,
+                    wallet_cap,
+                    rng.gen_range(1..=5),
+                    requires_allowlist,
+                )];
+                if requires_allowlist {
+                    for user in harness.users.iter().take(2) {
+                        instructions.push(ix::set_allowlist_entry(
+                            &harness.authority.pubkey(),
+                            &stake_mint,
+                            &reward_mint,
+                            phase_id,
+                            &user.pubkey(),
+                            true,
+                        ));
+                    }
+                }
+                for instruction in instructions {
+                    harness.send_as_authority(instruction).await.unwrap();
+                }
+                start_time = end_time;
+            }
+        }
+
+        // a third of the runs sell the reward at a fixed rate, possibly oversubscribed
+        if sale {
+            let instruction = ix::set_sale_terms(
+                &harness.authority.pubkey(),
+                &stake_mint,
+                &reward_mint,
+                rng.gen_range(SALE_RATE_PRECISION / 10..=SALE_RATE_PRECISION * 100),
+                rng.gen_range(1..=1_000_000),
+            );
+            harness.send_as_authority(instruction).await.unwrap();
+        }
+        harness
+    }
+
+    pub fn users(&self) -> usize {
+        self.users.len()
+    }
+
+    fn vault(&self) -> Pubkey {
+        pda::vault(&pda::stake_config(&self.stake_mint), &self.reward_mint)
+    }
+
+    async fn send(
+        &mut self,
+        instruction: Instruction,
+        signer: Option<usize>,
+    ) -> Result<(), BanksClientError> {
+        self.nonce += 1;
+        let signer = match signer {
+            Some(user) => &self.users[user],
+            None => &self.authority,
+        };
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_price(self.nonce),
+                instruction,
+            ],
+            Some(&signer.pubkey()),
+            &[signer],
+            self.ctx.last_blockhash,
+        );
+        // waiting for finalized confirmation can outlive the client deadline once the clock moved
+        self.ctx
+            .banks_client
+            .process_transaction_with_commitment(tx, CommitmentLevel::Processed)
+            .await
+    }
+
+    async fn send_as_authority(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<(), BanksClientError> {
+        self.send(instruction, None).await
+    }
+
+    /// returns false if the program rejected the operation
+    /// each op lands in a fresh slot, otherwise the per-account block cost limit drops transactions
+    async fn next_slot(&mut self) {
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        self.ctx.warp_to_slot(clock.slot + 1).unwrap();
+        // the new bank recomputes the clock, keep the time warped so far
+        let mut next: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        next.unix_timestamp = next.unix_timestamp.max(clock.unix_timestamp);
+        self.ctx.set_sysvar(&next);
+        self.ctx.last_blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+    }
+
+    pub async fn apply(&mut self, op: &Op) -> bool {
+        self.next_slot().await;
+        let (stake_mint, reward_mint) = (self.stake_mint, self.reward_mint);
+        let result = match *op {
+            Op::Stake { user, amount } => {
+                let signer = self.users[user].pubkey();
+                let vault: Vault = self.decode(self.vault()).await.unwrap();
+                let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+                // the other users stake without an entry and are rejected in allowlisted phases
+                let phase = vault
+                    .active_phase(clock.unix_timestamp)
+                    .filter(|index| vault.phases[*index].requires_allowlist && user < 2)
+                    .map(|index| index as u8);
+                self.send(
+                    ix::stake(
+                        &signer,
+                        &stake_mint,
+                        &reward_mint,
+                        amount,
+                        None,
+                        phase,
+                        false,
+                    ),
+                    Some(user),
+                )
+                .await
+            }
+            Op::Destake {
+                user,
+                amount,
+                auto_close,
+            } => {
+                let signer = self.users[user].pubkey();
+                self.send(
+                    ix::destake(&signer, &stake_mint, &reward_mint, amount, auto_close),
+                    Some(user),
+                )
+                .await
+            }
+            Op::ClaimReward { user } => {
+                let signer = self.users[user].pubkey();
+                self.send(
+                    ix::claim_reward(&signer, &stake_mint, &reward_mint),
+                    Some(user),
+                )
+                .await
+            }
+            Op::FundReward { amount } => {
+                let instruction = spl_token::instruction::mint_to(
+                    &spl_token::ID,
+                    &reward_mint,
+                    &get_associated_token_address(&self.vault(), &reward_mint),
+                    &self.authority.pubkey(),
+                    &[],
+                    amount,
+                )
+                .unwrap();
+                self.send_as_authority(instruction).await
+            }
+            Op::WithdrawProceeds => {
+                let authority = self.authority.pubkey();
+                self.send_as_authority(ix::withdraw_sale_proceeds(
+                    &authority,
+                    &stake_mint,
+                    &reward_mint,
+                ))
+                .await
+            }
+            Op::Warp { seconds } => {
+                let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+                clock.unix_timestamp += seconds;
+                self.ctx.set_sysvar(&clock);
+                Ok(())
+            }
+        };
+        result.is_ok()
+    }
+
+    async fn account(&mut self, address: Pubkey) -> Option<Account> {
+        self.ctx.banks_client.get_account(address).await.unwrap()
+    }
+
+    async fn decode<T: AccountDeserialize>(&mut self, address: Pubkey) -> Option<T> {
+        let account = self.account(address).await?;
+        Some(stake_client::accounts::decode(&account.data).unwrap())
+    }
+
+    async fn token_balance(&mut self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        match self
+            .account(get_associated_token_address(owner, mint))
+            .await
+        {
+            Some(account) => TokenAccount::unpack(&account.data).unwrap().amount,
+            None => 0,
+        }
+    }
+
+    /// - phase snapshot amounts add up to snapshot_amount and Vault.total_staked, respect
+    ///   the wallet caps, and users off the allowlists have none in allowlisted phases
+    /// - sum of StakeInfo.stake_amount == Vault.total_staked before the soft cap
+    /// - sum of StakeInfo.stake_amount and unwithdrawn sale proceeds == vault staking token
+    ///   balance, and the stake spent by claimed users adds up to Vault.sale_proceeds
+    /// - no user withdraws more than deposited, what is missing from a wallet is staked or
+    ///   spent on the sale
+    /// - rewards paid <= Vault.total_reward <= sale_allocation of a sale, and nothing is paid
+    ///   before tge
+    /// - a void sale has paid no reward and taken no proceeds
+    /// - audit_vault reports the vault solvent
+    pub async fn check_invariants(&mut self) -> Result<(), String> {
+        let vault_key = self.vault();
+        let vault: Vault = self.decode(vault_key).await.ok_or("vault missing")?;
+        let (stake_mint, reward_mint) = (self.stake_mint, self.reward_mint);
+
+        let mut total_stake_amount = 0;
+        let mut total_spent_amount = 0;
+        let mut total_reward_paid = 0;
+        for user in 0..self.users.len() {
+            let owner = self.users[user].pubkey();
+            let stake_info = self
+                .decode::<StakeInfo>(pda::stake_info(&vault_key, &owner))
+                .await;
+            let stake_amount = stake_info
+                .as_ref()
+                .map_or(0, |stake_info| stake_info.stake_amount);
+            let spent_amount = stake_info
+                .as_ref()
+                .map_or(0, |stake_info| sale_spent_amount(&vault, stake_info));
+            let balance = self.token_balance(&owner, &stake_mint).await;
+
+            if balance > INITIAL_BALANCE {
+                return Err(format!(
+                    "user {user} holds {balance}, more than its initial {INITIAL_BALANCE}"
+                ));
+            }
+            if INITIAL_BALANCE - balance != stake_amount + spent_amount {
+                return Err(format!(
+                    "user {user} deposited {} net but stake_amount is {stake_amount} \
+                     and spent {spent_amount}",
+                    INITIAL_BALANCE - balance
+                ));
+            }
+
+            if vault.has_phases() {
+                self.check_phase_amounts(&vault, user, &stake_info)?;
+            }
+
+            total_stake_amount += stake_amount;
+            total_spent_amount += spent_amount;
+            total_reward_paid += self.token_balance(&owner, &reward_mint).await;
+        }
+
+        let phase_total: u64 = vault.phases.iter().map(|phase| phase.total_staked).sum();
+        if vault.has_phases() && phase_total != vault.total_staked {
+            return Err(format!(
+                "sum of phase total_staked {phase_total} != total_staked {}",
+                vault.total_staked
+            ));
+        }
+
+        if !vault.reach_soft_cap && total_stake_amount != vault.total_staked {
+            return Err(format!(
+                "sum of stake_amount {total_stake_amount} != total_staked {} before soft cap",
+                vault.total_staked
+            ));
+        }
+
+        if total_spent_amount != vault.sale_proceeds {
+            return Err(format!(
+                "sum of spent stake {total_spent_amount} != sale_proceeds {}",
+                vault.sale_proceeds
+            ));
+        }
+
+        let vault_balance = self.token_balance(&vault_key, &stake_mint).await;
+        let unwithdrawn_proceeds = vault.sale_proceeds - vault.withdrawn_proceeds;
+        if total_stake_amount + unwithdrawn_proceeds != vault_balance {
+            return Err(format!(
+                "sum of stake_amount {total_stake_amount} and unwithdrawn proceeds \
+                 {unwithdrawn_proceeds} != vault balance {vault_balance}"
+            ));
+        }
+
+        if vault.is_sale() && vault.total_reward > vault.sale_allocation {
+            return Err(format!(
+                "total_reward {} > sale_allocation {}",
+                vault.total_reward, vault.sale_allocation
+            ));
+        }
+
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        if vault.is_void_sale(clock.unix_timestamp)
+            && (total_reward_paid > 0 || vault.sale_proceeds > 0)
+        {
+            return Err(format!(
+                "void sale paid {total_reward_paid} reward and took {} proceeds",
+                vault.sale_proceeds
+            ));
+        }
+
+        if total_reward_paid > vault.total_reward {
+            return Err(format!(
+                "rewards paid {total_reward_paid} > total_reward {}",
+                vault.total_reward
+            ));
+        }
+
+        self.send_as_authority(ix::audit_vault(&stake_mint, &reward_mint))
+            .await
+            .map_err(|err| format!("audit_vault failed: {err}"))
+    }
+
+    fn check_phase_amounts(
+        &self,
+        vault: &Vault,
+        user: usize,
+        stake_info: &Option<StakeInfo>,
+    ) -> Result<(), String> {
+        let Some(stake_info) = stake_info else {
+            return Ok(());
+        };
+
+        let phase_sum: u64 = stake_info.phase_snapshot_amounts.iter().sum();
+        if phase_sum != stake_info.snapshot_amount {
+            return Err(format!(
+                "user {user} phase amounts {phase_sum} != snapshot_amount {}",
+                stake_info.snapshot_amount
+            ));
+        }
+
+        for (index, phase) in vault.phases[..vault.phase_count as usize]
+            .iter()
+            .enumerate()
+        {
+            let amount = stake_info.phase_snapshot_amounts[index];
+            if phase.wallet_cap > 0 && amount > phase.wallet_cap {
+                return Err(format!(
+                    "user {user} holds {amount} in phase {index} above its cap {}",
+                    phase.wallet_cap
+                ));
+            }
+            if phase.requires_allowlist && user >= 2 && amount > 0 {
+                return Err(format!(
+                    "user {user} staked {amount} in phase {index} without being allowlisted"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// staking tokens a claimed user spent on the sale, as claim_reward computes them
+fn sale_spent_amount(vault: &Vault, stake_info: &StakeInfo) -> u64 {
+    if !vault.is_sale() || !stake_info.has_claimed {
+        return 0;
+    }
+    ((stake_info.snapshot_amount as u128) * (vault.sold_stake() as u128)
+        / (vault.total_staked as u128)) as u64
+}
@@ -0,0 +1,80 @@
+//! FUZZ_SEED, FUZZ_RUNS and FUZZ_STEPS widen the search, e.g.
+//! `FUZZ_RUNS=200 FUZZ_STEPS=300 cargo test -p stake-fuzz --release`
+
+use fungstake::constant::constants::SALE_REFUND_DELAY;
+use rand::{rngs::StdRng, SeedableRng};
+use stake_fuzz::{FungstakeHarness, Op, INITIAL_BALANCE};
+
+const USERS: usize = 3;
+
+fn env_or(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+#[tokio::test]
+async fn fungstake_invariants_hold_for_random_sequences() {
+    let base_seed = env_or("FUZZ_SEED", 0);
+    let runs = env_or("FUZZ_RUNS", 4);
+    let steps = env_or("FUZZ_STEPS", 60);
+
+    for seed in base_seed..base_seed + runs {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut harness = FungstakeHarness::new(&mut rng, USERS).await;
+        for step in 0..steps {
+            let op = Op::random(&mut rng, harness.users());
+            let accepted = harness.apply(&op).await;
+            if let Err(violation) = harness.check_invariants().await {
+                panic!("seed {seed} step {step} after {op:?} (accepted: {accepted}): {violation}");
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn unfunded_sales_refund_in_full_after_the_deadline() {
+    let mut rng = StdRng::seed_from_u64(env_or("FUZZ_SEED", 0));
+    let mut harness = FungstakeHarness::with_launch(&mut rng, USERS, false, true).await;
+    // the soft cap is at most the staking token supply, so staking it all reaches it
+    for user in 0..USERS {
+        let stake = Op::Stake {
+            user,
+            amount: INITIAL_BALANCE,
+        };
+        assert!(harness.apply(&stake).await, "user {user} could not stake");
+    }
+    let destake = Op::Destake {
+        user: 0,
+        amount: INITIAL_BALANCE,
+        auto_close: false,
+    };
+
+    // committed to the sale until the deadline
+    harness.apply(&Op::Warp { seconds: 3_600 }).await;
+    assert!(!harness.apply(&destake).await);
+    harness
+        .apply(&Op::Warp {
+            seconds: SALE_REFUND_DELAY,
+        })
+        .await;
+
+    // then every staker gets its whole stake back, and funding comes too late
+    assert!(harness.apply(&destake).await);
+    assert!(!harness.apply(&destake).await);
+    harness.apply(&Op::FundReward { amount: 1_000_000 }).await;
+    assert!(!harness.apply(&Op::ClaimReward { user: 1 }).await);
+    for user in 1..USERS {
+        let destake = Op::Destake {
+            user,
+            amount: INITIAL_BALANCE,
+            auto_close: false,
+        };
+        assert!(
+            harness.apply(&destake).await,
+            "user {user} was not refunded"
+        );
+    }
+    harness.check_invariants().await.unwrap();
+}
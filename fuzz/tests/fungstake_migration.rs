@@ -0,0 +1,332 @@
+//! Accounts written with the layouts of the first fungstake release are migrated by the
+//! authority, stake config then vault, then the stake infos by anyone, and then used by the
+//! regular instructions.
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::spl_token::state::Account as TokenAccount,
+};
+use fungstake::{
+    constant::constants::{ACCOUNT_VERSION, VAULT_SEED},
+    StakeConfig, StakeInfo, Vault, MAX_PHASES, STAKE_CONFIG_SIZE, STAKE_INFO_SIZE, VAULT_SIZE,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account, account_info::AccountInfo, entrypoint::ProgramResult,
+    instruction::Instruction, program_pack::Pack, pubkey::Pubkey, rent::Rent, signature::Keypair,
+    signer::Signer, transaction::Transaction,
+};
+use stake_client::{instructions::fungstake as ix, pda::fungstake as pda};
+use stake_fuzz::{mint, token_account, wallet};
+
+const LOCK_PERIOD: u32 = 60;
+const LOCK_EXTEND_TIME: u32 = 30;
+const SOFT_CAP: u64 = 1_000;
+/// snapshot amounts of two stakers, the first destaked and claimed before the migration
+const STAKED: [u64; 2] = [400, 600];
+const TOTAL_REWARD: u64 = 500;
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // same as the fungstake harness, anchor needs account infos outliving the slice
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    fungstake::entry(program_id, accounts, data)
+}
+
+fn legacy<T: Discriminator>(fields: &[&[u8]]) -> Account {
+    let mut data = T::DISCRIMINATOR.to_vec();
+    for field in fields {
+        data.extend_from_slice(field);
+    }
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: fungstake::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+struct Legacy {
+    ctx: ProgramTestContext,
+    authority: Keypair,
+    /// destaked and claimed before the migration
+    staker: Keypair,
+    /// still holds its stake and reward
+    holder: Keypair,
+    mint: Pubkey,
+    reward_mint: Pubkey,
+}
+
+impl Legacy {
+    /// a stake config and a vault past tge, where the first staker destaked and claimed
+    async fn new() -> Self {
+        let mut program_test =
+            ProgramTest::new("fungstake", fungstake::ID, processor!(process_instruction));
+        let authority = Keypair::new();
+        let staker = Keypair::new();
+        let holder = Keypair::new();
+        let mint_key = Pubkey::new_unique();
+        let reward_mint = Pubkey::new_unique();
+        let stake_config = pda::stake_config(&mint_key);
+        let (vault, vault_bump) = Pubkey::find_program_address(
+            &[VAULT_SEED, stake_config.as_ref(), reward_mint.as_ref()],
+            &fungstake::ID,
+        );
+        let total_staked: u64 = STAKED.iter().sum();
+        let first_reward = STAKED[0] * TOTAL_REWARD / total_staked;
+
+        program_test.add_account(authority.pubkey(), wallet());
+        program_test.add_account(staker.pubkey(), wallet());
+        program_test.add_account(holder.pubkey(), wallet());
+        program_test.add_account(mint_key, mint(&authority.pubkey(), total_staked));
+        program_test.add_account(reward_mint, mint(&authority.pubkey(), TOTAL_REWARD));
+        program_test.add_account(
+            get_associated_token_address(&vault, &mint_key),
+            token_account(&mint_key, &vault, total_staked - STAKED[0]),
+        );
+        program_test.add_account(
+            get_associated_token_address(&vault, &reward_mint),
+            token_account(&reward_mint, &vault, TOTAL_REWARD - first_reward),
+        );
+
+        program_test.add_account(
+            stake_config,
+            legacy::<StakeConfig>(&[
+                &[255, 1],
+                authority.pubkey().as_ref(),
+                mint_key.as_ref(),
+                &LOCK_PERIOD.to_le_bytes(),
+                &LOCK_EXTEND_TIME.to_le_bytes(),
+                &SOFT_CAP.to_le_bytes(),
+            ]),
+        );
+        program_test.add_account(
+            vault,
+            legacy::<Vault>(&[
+                &[vault_bump, 0],
+                reward_mint.as_ref(),
+                &total_staked.to_le_bytes(),
+                &1i64.to_le_bytes(),
+                &[1],
+                &TOTAL_REWARD.to_le_bytes(),
+                &[1],
+            ]),
+        );
+        for (owner, stake_amount, snapshot_amount, has_claimed) in [
+            (staker.pubkey(), 0, STAKED[0], 1),
+            (holder.pubkey(), STAKED[1], STAKED[1], 0),
+        ] {
+            program_test.add_account(
+                pda::stake_info(&vault, &owner),
+                legacy::<StakeInfo>(&[
+                    &[255],
+                    &0i64.to_le_bytes(),
+                    &stake_amount.to_le_bytes(),
+                    &snapshot_amount.to_le_bytes(),
+                    &[has_claimed],
+                ]),
+            );
+        }
+
+        Legacy {
+            ctx: program_test.start_with_context().await,
+            authority,
+            staker,
+            holder,
+            mint: mint_key,
+            reward_mint,
+        }
+    }
+
+    async fn send(
+        &mut self,
+        instruction: Instruction,
+        signer: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        // a fresh blockhash each time, a resent transaction would get the status of the first
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&signer.pubkey()),
+            &[signer],
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    async fn as_authority(&mut self, instruction: Instruction) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        self.send(instruction, &authority).await
+    }
+
+    async fn as_staker(&mut self, instruction: Instruction) -> Result<(), BanksClientError> {
+        let staker = self.staker.insecure_clone();
+        self.send(instruction, &staker).await
+    }
+
+    async fn as_holder(&mut self, instruction: Instruction) -> Result<(), BanksClientError> {
+        let holder = self.holder.insecure_clone();
+        self.send(instruction, &holder).await
+    }
+
+    async fn balance(&mut self, address: Pubkey) -> u64 {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        TokenAccount::unpack(&account.data).unwrap().amount
+    }
+
+    async fn account<T: AccountDeserialize>(&mut self, address: Pubkey) -> (usize, T) {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        let decoded = T::try_deserialize(&mut &account.data[..]).unwrap();
+        (account.data.len(), decoded)
+    }
+}
+
+#[tokio::test]
+async fn legacy_accounts_are_migrated_in_order() {
+    let mut legacy = Legacy::new().await;
+    let (mint, reward_mint) = (legacy.mint, legacy.reward_mint);
+    let (authority, staker) = (legacy.authority.pubkey(), legacy.staker.pubkey());
+    let stake_config = pda::stake_config(&mint);
+    let vault = pda::vault(&stake_config, &reward_mint);
+
+    // a vault cannot be migrated under a legacy stake config
+    assert!(legacy
+        .as_authority(ix::migrate_vault(&authority, &mint, &reward_mint, true))
+        .await
+        .is_err());
+
+    // only the authority migrates the stake config, once
+    assert!(legacy
+        .as_staker(ix::migrate_stake_config(&staker, &mint))
+        .await
+        .is_err());
+    legacy
+        .as_authority(ix::migrate_stake_config(&authority, &mint))
+        .await
+        .unwrap();
+    let (size, config) = legacy.account::<StakeConfig>(stake_config).await;
+    assert_eq!(size, STAKE_CONFIG_SIZE);
+    assert_eq!(config.version, ACCOUNT_VERSION);
+    assert_eq!(config.authority, authority);
+    assert_eq!(config.soft_cap, SOFT_CAP);
+    assert!(!config.has_price_feed());
+    assert!(!config.has_kyc_attestor());
+    assert!(legacy
+        .as_authority(ix::migrate_stake_config(&authority, &mint))
+        .await
+        .is_err());
+
+    // the claimed rewards are read from the reward token account once past tge
+    assert!(legacy
+        .as_authority(ix::migrate_vault(&authority, &mint, &reward_mint, false))
+        .await
+        .is_err());
+    legacy
+        .as_authority(ix::migrate_vault(&authority, &mint, &reward_mint, true))
+        .await
+        .unwrap();
+    let (size, migrated_vault) = legacy.account::<Vault>(vault).await;
+    assert_eq!(size, VAULT_SIZE);
+    assert_eq!(migrated_vault.version, ACCOUNT_VERSION);
+    assert_eq!(migrated_vault.total_staked, STAKED.iter().sum::<u64>());
+    assert_eq!(migrated_vault.destaked_after_end, STAKED[0]);
+    assert_eq!(migrated_vault.claimed_snapshot_amount, STAKED[0]);
+    assert!(!migrated_vault.has_phases() && !migrated_vault.is_sale());
+    assert!(legacy
+        .as_authority(ix::migrate_vault(&authority, &mint, &reward_mint, true))
+        .await
+        .is_err());
+
+    // the migrated vault audits as solvent
+    legacy
+        .as_staker(ix::audit_vault(&mint, &reward_mint))
+        .await
+        .unwrap();
+
+    // stake infos can be migrated by anyone, the holder then claims and destakes
+    let holder = legacy.holder.pubkey();
+    assert!(legacy
+        .as_holder(ix::claim_reward(&holder, &mint, &reward_mint))
+        .await
+        .is_err());
+    legacy
+        .as_staker(ix::migrate_stake_info(
+            &staker,
+            &mint,
+            &reward_mint,
+            &holder,
+        ))
+        .await
+        .unwrap();
+    let (size, info) = legacy
+        .account::<StakeInfo>(pda::stake_info(&vault, &holder))
+        .await;
+    assert_eq!(size, STAKE_INFO_SIZE);
+    assert_eq!(info.version, ACCOUNT_VERSION);
+    assert_eq!(info.stake_amount, STAKED[1]);
+    assert_eq!(info.phase_snapshot_amounts, [0; MAX_PHASES]);
+
+    legacy
+        .as_holder(ix::claim_reward(&holder, &mint, &reward_mint))
+        .await
+        .unwrap();
+    legacy
+        .as_holder(ix::destake(&holder, &mint, &reward_mint, STAKED[1], true))
+        .await
+        .unwrap();
+    assert_eq!(
+        legacy
+            .balance(get_associated_token_address(&holder, &reward_mint))
+            .await,
+        STAKED[1] * TOTAL_REWARD / STAKED.iter().sum::<u64>()
+    );
+    assert_eq!(
+        legacy
+            .balance(get_associated_token_address(&holder, &mint))
+            .await,
+        STAKED[1]
+    );
+    legacy
+        .as_staker(ix::audit_vault(&mint, &reward_mint))
+        .await
+        .unwrap();
+
+    // the drained stake info of the first staker is closed once migrated
+    legacy
+        .as_staker(ix::migrate_stake_info(
+            &staker,
+            &mint,
+            &reward_mint,
+            &staker,
+        ))
+        .await
+        .unwrap();
+    legacy
+        .as_staker(ix::close_stake_info(&staker, &mint, &reward_mint))
+        .await
+        .unwrap();
+    assert!(legacy
+        .ctx
+        .banks_client
+        .get_account(pda::stake_info(&vault, &staker))
+        .await
+        .unwrap()
+        .is_none());
+}
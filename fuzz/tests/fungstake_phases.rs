@@ -0,0 +1,80 @@
+//! Phase weights can be changed until the phase starts or gets stake.
+
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, pubkey::Pubkey,
+    signature::Keypair, signer::Signer, transaction::Transaction,
+};
+use stake_client::instructions::fungstake as ix;
+use stake_fuzz::{mint, wallet};
+
+const START_DELAY: i64 = 100;
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // same as the fungstake harness, anchor needs account infos outliving the slice
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    fungstake::entry(program_id, accounts, data)
+}
+
+#[tokio::test]
+async fn phase_weight_is_locked_once_started() {
+    let mut program_test =
+        ProgramTest::new("fungstake", fungstake::ID, processor!(process_instruction));
+    let authority = Keypair::new();
+    let mint_key = Pubkey::new_unique();
+    let reward_mint = Pubkey::new_unique();
+    program_test.add_account(authority.pubkey(), wallet());
+    program_test.add_account(mint_key, mint(&authority.pubkey(), 0));
+    program_test.add_account(reward_mint, mint(&authority.pubkey(), 0));
+    let mut ctx = program_test.start_with_context().await;
+
+    let owner = authority.pubkey();
+    let clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    let start_time = clock.unix_timestamp + START_DELAY;
+    let set_phase = |weight| {
+        ix::set_phase(
+            &owner,
+            &mint_key,
+            &reward_mint,
+            0,
+            start_time,
+            start_time + START_DELAY,
+            0,
+            weight,
+            false,
+        )
+    };
+
+    // (instruction, succeeds), the clock passes the phase start after the third one
+    for (step, (instruction, succeeds)) in [
+        (ix::initialize(&owner, &mint_key, 10, 10, 1_000), true),
+        (ix::create_vault(&owner, &mint_key, &reward_mint), true),
+        (set_phase(1), true),
+        (set_phase(2), true),
+        (set_phase(3), false),
+        // the rest of the phase can still be updated
+        (set_phase(2), true),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if step == 4 {
+            let mut clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+            clock.unix_timestamp = start_time;
+            ctx.set_sysvar(&clock);
+        }
+        let blockhash = ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&owner),
+            &[&authority],
+            blockhash,
+        );
+        let result = ctx.banks_client.process_transaction(tx).await;
+        assert_eq!(result.is_ok(), succeeds, "step {step}: {result:?}");
+    }
+}
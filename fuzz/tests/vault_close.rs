@@ -0,0 +1,81 @@
+//! Positions and staker infos left behind by close_vault can still be closed by their owner.
+
+use anchor_spl::associated_token::get_associated_token_address;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, pubkey::Pubkey,
+    signature::Keypair, signer::Signer, transaction::Transaction,
+};
+use stake_client::{instructions::vault as ix, pda::vault as pda};
+use stake_fuzz::{mint, token_account, wallet};
+
+const LOCK_PERIOD: u64 = 10;
+const STAKED: u64 = 500;
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // same as the fungstake harness, anchor needs account infos outliving the slice
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    vault::entry(program_id, accounts, data)
+}
+
+#[tokio::test]
+async fn drained_accounts_are_closed_after_close_vault() {
+    let mut program_test = ProgramTest::new("vault", vault::ID, processor!(process_instruction));
+    // the authority stakes in its own vault
+    let authority = Keypair::new();
+    let mint_key = Pubkey::new_unique();
+    program_test.add_account(authority.pubkey(), wallet());
+    program_test.add_account(mint_key, mint(&authority.pubkey(), STAKED));
+    program_test.add_account(
+        get_associated_token_address(&authority.pubkey(), &mint_key),
+        token_account(&mint_key, &authority.pubkey(), STAKED),
+    );
+    let mut ctx = program_test.start_with_context().await;
+
+    let owner = authority.pubkey();
+    let stake_config = pda::stake_config(&mint_key);
+    let vault = pda::vault(&stake_config, LOCK_PERIOD);
+    let staker_info = pda::staker_info(&vault, &owner);
+    let stake_detail = pda::stake_detail(&staker_info, 1);
+
+    for (step, instruction) in [
+        ix::initialize(&owner, &mint_key),
+        ix::create_vault(&owner, &mint_key, LOCK_PERIOD),
+        ix::stake(&owner, &mint_key, LOCK_PERIOD, 0, STAKED, None),
+        // destaked without auto_close, the drained position stays open
+        ix::destake(&owner, &mint_key, 1, LOCK_PERIOD, STAKED, false),
+        ix::close_vault(&owner, &mint_key, LOCK_PERIOD),
+        ix::close_position(&owner, &mint_key, 1, LOCK_PERIOD),
+        ix::close_staker_info(&owner, &mint_key, LOCK_PERIOD),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if step == 3 {
+            let mut clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+            clock.unix_timestamp += LOCK_PERIOD as i64;
+            ctx.set_sysvar(&clock);
+        }
+        let blockhash = ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&owner),
+            &[&authority],
+            blockhash,
+        );
+        ctx.banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    for address in [vault, staker_info, stake_detail] {
+        assert!(ctx
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
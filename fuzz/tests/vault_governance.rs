@@ -0,0 +1,314 @@
+//! Votes cast through update_voter_weight_record against spl-governance accounts: the vote
+//! lock needs a proposal of the realm in voting state, and never blocks revoke_grant.
+
+use anchor_lang::AccountDeserialize;
+use anchor_spl::associated_token::get_associated_token_address;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account, account_info::AccountInfo, entrypoint::ProgramResult,
+    instruction::Instruction, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer,
+    transaction::Transaction,
+};
+use stake_client::{instructions::vault as ix, pda::vault as pda};
+use stake_fuzz::{mint, token_account, wallet};
+use vault::{StakeDetail, VoterWeightAction};
+
+const LOCK_PERIOD: u64 = 90 * 24 * 60 * 60;
+const GRANTED: u64 = 600;
+const VOTE_LOCK_PERIOD: u64 = 1_000;
+
+/// spl-governance account types and proposal states
+const REALM_V2: u8 = 16;
+const GOVERNANCE_V2: u8 = 18;
+const PROPOSAL_V2: u8 = 14;
+const DRAFT: u8 = 0;
+const VOTING: u8 = 2;
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // same as the fungstake harness, anchor needs account infos outliving the slice
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    vault::entry(program_id, accounts, data)
+}
+
+/// account of the governance program starting with `fields`, padded like a real one
+fn governance_account(owner: &Pubkey, fields: &[&[u8]]) -> Account {
+    let mut data = fields.concat();
+    data.resize(200, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+struct Governance {
+    ctx: ProgramTestContext,
+    grantor: Keypair,
+    voter: Keypair,
+    mint: Pubkey,
+    realm: Pubkey,
+    governance: Pubkey,
+    /// proposal of `governance` in voting state
+    proposal: Pubkey,
+    /// proposal of `governance` not yet voting
+    draft: Pubkey,
+    /// voting proposal of a governance of the realm, owned by another program
+    foreign_proposal: Pubkey,
+}
+
+impl Governance {
+    /// a realm with proposals, and a vault where the grantor granted a revocable
+    /// position to the voter
+    async fn new() -> Self {
+        let mut program_test =
+            ProgramTest::new("vault", vault::ID, processor!(process_instruction));
+        let grantor = Keypair::new();
+        let voter = Keypair::new();
+        let mint_key = Pubkey::new_unique();
+        let governance_program = Pubkey::new_unique();
+        let realm = Pubkey::new_unique();
+        let governance = Pubkey::new_unique();
+        let foreign_governance = Pubkey::new_unique();
+        let (proposal, draft, foreign_proposal) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        program_test.add_account(grantor.pubkey(), wallet());
+        program_test.add_account(voter.pubkey(), wallet());
+        program_test.add_account(mint_key, mint(&grantor.pubkey(), GRANTED));
+        program_test.add_account(
+            get_associated_token_address(&grantor.pubkey(), &mint_key),
+            token_account(&mint_key, &grantor.pubkey(), GRANTED),
+        );
+
+        program_test.add_account(
+            realm,
+            governance_account(&governance_program, &[&[REALM_V2], mint_key.as_ref()]),
+        );
+        program_test.add_account(
+            governance,
+            governance_account(&governance_program, &[&[GOVERNANCE_V2], realm.as_ref()]),
+        );
+        for (address, state) in [(proposal, VOTING), (draft, DRAFT)] {
+            program_test.add_account(
+                address,
+                governance_account(
+                    &governance_program,
+                    &[
+                        &[PROPOSAL_V2],
+                        governance.as_ref(),
+                        mint_key.as_ref(),
+                        &[state],
+                    ],
+                ),
+            );
+        }
+        let other_program = Pubkey::new_unique();
+        program_test.add_account(
+            foreign_governance,
+            governance_account(&other_program, &[&[GOVERNANCE_V2], realm.as_ref()]),
+        );
+        program_test.add_account(
+            foreign_proposal,
+            governance_account(
+                &other_program,
+                &[
+                    &[PROPOSAL_V2],
+                    foreign_governance.as_ref(),
+                    mint_key.as_ref(),
+                    &[VOTING],
+                ],
+            ),
+        );
+
+        let mut governance = Governance {
+            ctx: program_test.start_with_context().await,
+            grantor,
+            voter,
+            mint: mint_key,
+            realm,
+            governance,
+            proposal,
+            draft,
+            foreign_proposal,
+        };
+
+        let grantor = governance.grantor.insecure_clone();
+        let voter = governance.voter.insecure_clone();
+        let (authority, mint) = (grantor.pubkey(), governance.mint);
+        for instruction in [
+            ix::initialize(&authority, &mint),
+            ix::create_vault(&authority, &mint, LOCK_PERIOD),
+            ix::set_vote_lock_period(&authority, &mint, VOTE_LOCK_PERIOD),
+        ] {
+            governance.send(instruction, &[&grantor]).await.unwrap();
+        }
+        governance
+            .send(
+                ix::grant_stake(
+                    &authority,
+                    &voter.pubkey(),
+                    &mint,
+                    LOCK_PERIOD,
+                    0,
+                    GRANTED,
+                    true,
+                ),
+                &[&grantor],
+            )
+            .await
+            .unwrap();
+        governance
+            .send(
+                ix::create_voter_weight_record(&voter.pubkey(), &mint, realm),
+                &[&voter],
+            )
+            .await
+            .unwrap();
+        governance
+    }
+
+    async fn send(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        // a fresh blockhash each time, a resent transaction would get the status of the first
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&signers[0].pubkey()),
+            signers,
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    /// CastVote of the granted position on `target`, under `governance`
+    async fn cast_vote(
+        &mut self,
+        target: Pubkey,
+        governance: Option<Pubkey>,
+    ) -> Result<(), BanksClientError> {
+        let voter = self.voter.insecure_clone();
+        let instruction = ix::update_voter_weight_record(
+            &voter.pubkey(),
+            &self.mint,
+            self.realm,
+            VoterWeightAction::CastVote,
+            Some(target),
+            governance,
+            &[(LOCK_PERIOD, voter.pubkey(), 1)],
+        );
+        self.send(instruction, &[&voter]).await
+    }
+
+    async fn stake_detail(&mut self) -> StakeDetail {
+        let staker_info = pda::staker_info(
+            &pda::vault(&pda::stake_config(&self.mint), LOCK_PERIOD),
+            &self.voter.pubkey(),
+        );
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(pda::stake_detail(&staker_info, 1))
+            .await
+            .unwrap()
+            .unwrap();
+        StakeDetail::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+}
+
+#[tokio::test]
+async fn votes_lock_positions_only_for_voting_proposals() {
+    let mut governance = Governance::new().await;
+    let (proposal, draft) = (governance.proposal, governance.draft);
+    let governance_key = governance.governance;
+
+    // no governance accounts, a draft, a proposal of another program, or a target
+    // which is not the proposal passed
+    assert!(governance.cast_vote(proposal, None).await.is_err());
+    assert!(governance
+        .cast_vote(draft, Some(governance_key))
+        .await
+        .is_err());
+    let foreign_proposal = governance.foreign_proposal;
+    assert!(governance
+        .cast_vote(foreign_proposal, Some(governance_key))
+        .await
+        .is_err());
+    let voter = governance.voter.pubkey();
+    let mut mismatched = ix::update_voter_weight_record(
+        &voter,
+        &governance.mint,
+        governance.realm,
+        VoterWeightAction::CastVote,
+        Some(draft),
+        Some(governance_key),
+        &[(LOCK_PERIOD, voter, 1)],
+    );
+    let proposal_index = mismatched
+        .accounts
+        .iter()
+        .position(|meta| meta.pubkey == draft)
+        .unwrap();
+    mismatched.accounts[proposal_index].pubkey = proposal;
+    let voter_keypair = governance.voter.insecure_clone();
+    assert!(governance
+        .send(mismatched, &[&voter_keypair])
+        .await
+        .is_err());
+    assert_eq!(governance.stake_detail().await.vote_locked_until, 0);
+
+    // other actions need no proposal and lock nothing
+    let comment = ix::update_voter_weight_record(
+        &voter,
+        &governance.mint,
+        governance.realm,
+        VoterWeightAction::CommentProposal,
+        None,
+        None,
+        &[(LOCK_PERIOD, voter, 1)],
+    );
+    governance.send(comment, &[&voter_keypair]).await.unwrap();
+    assert_eq!(governance.stake_detail().await.vote_locked_until, 0);
+
+    governance
+        .cast_vote(proposal, Some(governance_key))
+        .await
+        .unwrap();
+    assert!(governance.stake_detail().await.vote_locked_until > 0);
+}
+
+#[tokio::test]
+async fn vote_locks_do_not_block_revoke_grant() {
+    let mut governance = Governance::new().await;
+    let (proposal, governance_key) = (governance.proposal, governance.governance);
+    governance
+        .cast_vote(proposal, Some(governance_key))
+        .await
+        .unwrap();
+    let locked = governance.stake_detail().await;
+    assert!(locked.vote_locked_until > 0);
+
+    let grantor = governance.grantor.insecure_clone();
+    let revoke = ix::revoke_grant(
+        &grantor.pubkey(),
+        &governance.voter.pubkey(),
+        &governance.mint,
+        1,
+        LOCK_PERIOD,
+    );
+    governance.send(revoke, &[&grantor]).await.unwrap();
+    let revoked = governance.stake_detail().await;
+    assert!(revoked.stake_amount < GRANTED);
+    assert_eq!(revoked.vote_locked_until, locked.vote_locked_until);
+}
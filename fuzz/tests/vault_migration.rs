@@ -0,0 +1,275 @@
+//! Accounts written with the layouts of the first vault release are migrated in order:
+//! stake config, vault, staker info, then its stake details. Migrated positions are then
+//! used by the regular instructions.
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anchor_spl::associated_token::get_associated_token_address;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account, account_info::AccountInfo, entrypoint::ProgramResult,
+    instruction::Instruction, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer,
+    transaction::Transaction,
+};
+use stake_client::{instructions::vault as ix, pda::vault as pda};
+use stake_fuzz::{mint, token_account, wallet};
+use vault::{
+    constant::constants::ACCOUNT_VERSION, StakeConfig, StakeDetail, StakerInfo, Vault,
+    STAKER_INFO_SIZE, STAKE_CONFIG_SIZE, STAKE_DETAIL_SIZE, VAULT_SIZE,
+};
+
+const LOCK_PERIOD: u64 = 30 * 24 * 60 * 60;
+const STAKED: [u64; 2] = [300, 700];
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // same as the fungstake harness, anchor needs account infos outliving the slice
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    vault::entry(program_id, accounts, data)
+}
+
+fn legacy<T: Discriminator>(fields: &[&[u8]]) -> Account {
+    let mut data = T::DISCRIMINATOR.to_vec();
+    for field in fields {
+        data.extend_from_slice(field);
+    }
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: vault::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+struct Legacy {
+    ctx: ProgramTestContext,
+    authority: Keypair,
+    staker: Keypair,
+    mint: Pubkey,
+}
+
+impl Legacy {
+    /// a stake config, a vault and a staker holding a matured and a locked position
+    async fn new() -> Self {
+        let mut program_test =
+            ProgramTest::new("vault", vault::ID, processor!(process_instruction));
+        let authority = Keypair::new();
+        let staker = Keypair::new();
+        let mint_key = Pubkey::new_unique();
+        let stake_config = pda::stake_config(&mint_key);
+        let vault = pda::vault(&stake_config, LOCK_PERIOD);
+        let staker_info = pda::staker_info(&vault, &staker.pubkey());
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let total: u64 = STAKED.iter().sum();
+
+        program_test.add_account(authority.pubkey(), wallet());
+        program_test.add_account(staker.pubkey(), wallet());
+        program_test.add_account(mint_key, mint(&authority.pubkey(), total));
+        program_test.add_account(
+            pda::vault_token_account(&vault, &mint_key),
+            token_account(&mint_key, &vault, total),
+        );
+        program_test.add_account(
+            get_associated_token_address(&staker.pubkey(), &mint_key),
+            token_account(&mint_key, &staker.pubkey(), 0),
+        );
+
+        program_test.add_account(
+            stake_config,
+            legacy::<StakeConfig>(&[
+                &[255, 1],
+                authority.pubkey().as_ref(),
+                mint_key.as_ref(),
+                &[0; 4],
+            ]),
+        );
+        program_test.add_account(
+            vault,
+            legacy::<Vault>(&[
+                &[255, 1],
+                stake_config.as_ref(),
+                &total.to_le_bytes(),
+                &LOCK_PERIOD.to_le_bytes(),
+            ]),
+        );
+        program_test.add_account(
+            staker_info,
+            legacy::<StakerInfo>(&[&[255], &total.to_le_bytes(), &2u64.to_le_bytes()]),
+        );
+        // position 1 matured long ago, position 2 unlocks in 10 days
+        for (id, unstaked_at_time) in [(1u64, now - 100), (2, now + 10 * 24 * 60 * 60)] {
+            program_test.add_account(
+                pda::stake_detail(&staker_info, id),
+                legacy::<StakeDetail>(&[
+                    &[255],
+                    &id.to_le_bytes(),
+                    &STAKED[id as usize - 1].to_le_bytes(),
+                    &unstaked_at_time.to_le_bytes(),
+                    staker.pubkey().as_ref(),
+                ]),
+            );
+        }
+
+        Legacy {
+            ctx: program_test.start_with_context().await,
+            authority,
+            staker,
+            mint: mint_key,
+        }
+    }
+
+    async fn send(
+        &mut self,
+        instruction: Instruction,
+        signer: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        // a fresh blockhash each time, a resent transaction would get the status of the first
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&signer.pubkey()),
+            &[signer],
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    async fn as_authority(&mut self, instruction: Instruction) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        self.send(instruction, &authority).await
+    }
+
+    async fn as_staker(&mut self, instruction: Instruction) -> Result<(), BanksClientError> {
+        let staker = self.staker.insecure_clone();
+        self.send(instruction, &staker).await
+    }
+
+    async fn account<T: AccountDeserialize>(&mut self, address: Pubkey) -> (usize, T) {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        let decoded = T::try_deserialize(&mut &account.data[..]).unwrap();
+        (account.data.len(), decoded)
+    }
+}
+
+#[tokio::test]
+async fn legacy_accounts_are_migrated_in_order() {
+    let mut legacy = Legacy::new().await;
+    let mint = legacy.mint;
+    let staker = legacy.staker.pubkey();
+    let stake_config = pda::stake_config(&mint);
+    let vault = pda::vault(&stake_config, LOCK_PERIOD);
+    let staker_info = pda::staker_info(&vault, &staker);
+
+    // a vault cannot be migrated under a legacy stake config
+    let authority = legacy.authority.pubkey();
+    assert!(legacy
+        .as_authority(ix::migrate_vault(&authority, &mint, LOCK_PERIOD))
+        .await
+        .is_err());
+
+    // only the authority migrates the stake config, once
+    assert!(legacy
+        .as_staker(ix::migrate_stake_config(&staker, &mint))
+        .await
+        .is_err());
+    legacy
+        .as_authority(ix::migrate_stake_config(&authority, &mint))
+        .await
+        .unwrap();
+    let (size, config) = legacy.account::<StakeConfig>(stake_config).await;
+    assert_eq!(size, STAKE_CONFIG_SIZE);
+    assert_eq!(config.version, ACCOUNT_VERSION);
+    assert_eq!(config.authority, authority);
+    assert!(config.max_lock_period >= LOCK_PERIOD);
+    assert!(legacy
+        .as_authority(ix::migrate_stake_config(&authority, &mint))
+        .await
+        .is_err());
+
+    legacy
+        .as_authority(ix::migrate_vault(&authority, &mint, LOCK_PERIOD))
+        .await
+        .unwrap();
+    let (size, migrated_vault) = legacy.account::<Vault>(vault).await;
+    assert_eq!(size, VAULT_SIZE);
+    assert_eq!(migrated_vault.version, ACCOUNT_VERSION);
+    assert_eq!(migrated_vault.total_staked, STAKED.iter().sum::<u64>());
+    assert!(migrated_vault.is_active);
+
+    // staker accounts can be migrated by anyone
+    legacy
+        .as_authority(ix::migrate_staker_info(
+            &authority,
+            &mint,
+            LOCK_PERIOD,
+            &staker,
+        ))
+        .await
+        .unwrap();
+    let (size, info) = legacy.account::<StakerInfo>(staker_info).await;
+    assert_eq!(size, STAKER_INFO_SIZE);
+    assert_eq!(info.version, ACCOUNT_VERSION);
+    assert_eq!(info.open_positions, 2);
+    assert_eq!(info.checkpoint_count, 1);
+    assert_eq!(info.checkpoints[0].total_stake, STAKED.iter().sum::<u64>());
+
+    for id in [1, 2] {
+        legacy
+            .as_staker(ix::migrate_stake_detail(
+                &staker,
+                &mint,
+                LOCK_PERIOD,
+                &staker,
+                id,
+            ))
+            .await
+            .unwrap();
+        let (size, detail) = legacy
+            .account::<StakeDetail>(pda::stake_detail(&staker_info, id))
+            .await;
+        assert_eq!(size, STAKE_DETAIL_SIZE);
+        assert_eq!(detail.version, ACCOUNT_VERSION);
+        assert_eq!(detail.stake_amount, STAKED[id as usize - 1]);
+        assert_eq!(detail.staker, staker);
+    }
+
+    // only the locked position has voting power
+    let (_, matured) = legacy
+        .account::<StakeDetail>(pda::stake_detail(&staker_info, 1))
+        .await;
+    let (_, locked) = legacy
+        .account::<StakeDetail>(pda::stake_detail(&staker_info, 2))
+        .await;
+    assert_eq!(matured.ve_slope, 0);
+    assert!(locked.ve_slope > 0);
+    let (_, config) = legacy.account::<StakeConfig>(stake_config).await;
+    assert_eq!(config.ve_slope, locked.ve_slope);
+
+    // migrated positions go through the regular instructions, the drained one is closed
+    legacy
+        .as_staker(ix::destake(&staker, &mint, 1, LOCK_PERIOD, STAKED[0], true))
+        .await
+        .unwrap();
+    assert!(legacy
+        .ctx
+        .banks_client
+        .get_account(pda::stake_detail(&staker_info, 1))
+        .await
+        .unwrap()
+        .is_none());
+    let (_, info) = legacy.account::<StakerInfo>(staker_info).await;
+    assert_eq!(info.total_stake, STAKED[1]);
+    assert_eq!(info.open_positions, 1);
+}
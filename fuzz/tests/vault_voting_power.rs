@@ -0,0 +1,84 @@
+//! Voting power of positions locked for less than a ve epoch, which ends at the epoch
+//! boundary from their unlock.
+
+use anchor_lang::AccountDeserialize;
+use anchor_spl::associated_token::get_associated_token_address;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, signature::Keypair,
+    signer::Signer, transaction::Transaction,
+};
+use stake_client::{instructions::vault as ix, pda::vault as pda};
+use stake_fuzz::{mint, token_account, wallet};
+use vault::{constant::constants::VE_EPOCH, StakeConfig, StakeDetail};
+
+const LOCK_PERIOD: u64 = 7 * 24 * 60 * 60;
+const STAKED: u64 = 1_000_000;
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // same as the fungstake harness, anchor needs account infos outliving the slice
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    vault::entry(program_id, accounts, data)
+}
+
+#[tokio::test]
+async fn locks_shorter_than_an_epoch_have_voting_power() {
+    let mut program_test = ProgramTest::new("vault", vault::ID, processor!(process_instruction));
+    let staker = Keypair::new();
+    let mint_key = Pubkey::new_unique();
+    program_test.add_account(staker.pubkey(), wallet());
+    program_test.add_account(mint_key, mint(&staker.pubkey(), STAKED));
+    program_test.add_account(
+        get_associated_token_address(&staker.pubkey(), &mint_key),
+        token_account(&mint_key, &staker.pubkey(), STAKED),
+    );
+    let mut ctx = program_test.start_with_context().await;
+
+    let owner = staker.pubkey();
+    for instruction in [
+        ix::initialize(&owner, &mint_key),
+        ix::create_vault(&owner, &mint_key, LOCK_PERIOD),
+        ix::stake(&owner, &mint_key, LOCK_PERIOD, 0, STAKED, None),
+    ] {
+        let blockhash = ctx.get_new_latest_blockhash().await.unwrap();
+        let tx =
+            Transaction::new_signed_with_payer(&[instruction], Some(&owner), &[&staker], blockhash);
+        ctx.banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    let stake_config = pda::stake_config(&mint_key);
+    let staker_info = pda::staker_info(&pda::vault(&stake_config, LOCK_PERIOD), &owner);
+    let detail = ctx
+        .banks_client
+        .get_account(pda::stake_detail(&staker_info, 1))
+        .await
+        .unwrap()
+        .unwrap();
+    let detail = StakeDetail::try_deserialize(&mut &detail.data[..]).unwrap();
+    let config = ctx
+        .banks_client
+        .get_account(stake_config)
+        .await
+        .unwrap()
+        .unwrap();
+    let config = StakeConfig::try_deserialize(&mut &config.data[..]).unwrap();
+
+    let staked_at = detail.unstaked_at_time - LOCK_PERIOD as i64;
+    assert_eq!(detail.ve_end % VE_EPOCH, 0);
+    assert!(detail.ve_end >= detail.unstaked_at_time);
+    assert!(detail.ve_end < detail.unstaked_at_time + VE_EPOCH);
+
+    // full power at stake time, decaying to 0 at ve_end
+    let power = detail.voting_power(LOCK_PERIOD, staked_at);
+    let expected = (STAKED as u128 * (detail.ve_end - staked_at) as u128
+        / config.max_lock_period as u128) as u64;
+    assert!(power > 0);
+    assert!(power.abs_diff(expected) <= 1);
+    assert_eq!(config.total_voting_power(staked_at), power);
+    assert_eq!(detail.voting_power(LOCK_PERIOD, detail.ve_end), 0);
+    assert_eq!(config.total_voting_power(detail.ve_end), 0);
+}
@@ -0,0 +1,10 @@
+//! Materializes `vault` and `fungstake` events into SQLite.
+//!
+//! - [`logs`] decodes the events of a transaction from its log messages
+//! - [`source`] reads transactions from a cluster or from saved `getTransaction` responses
+//! - [`store`] keeps positions, vault totals and claim history. Every event is applied
+//!   once, so transactions can be replayed from any slot
+
+pub mod logs;
+pub mod source;
+pub mod store;
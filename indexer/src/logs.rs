@@ -0,0 +1,75 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_sdk::pubkey::Pubkey;
+use stake_client::{fungstake_program, vault_program};
+
+pub use fungstake_program::event::{ClaimRewardEvent, DeStakeEvent, StakeEvent};
+pub use vault_program::event::{EarlyDeStakeEvent, PositionUpdateEvent};
+
+pub enum ProgramEvent {
+    VaultPositionUpdate(PositionUpdateEvent),
+    VaultEarlyDeStake(EarlyDeStakeEvent),
+    FungstakeStake(StakeEvent),
+    FungstakeDeStake(DeStakeEvent),
+    FungstakeClaimReward(ClaimRewardEvent),
+}
+
+impl ProgramEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProgramEvent::VaultPositionUpdate(_) => "PositionUpdateEvent",
+            ProgramEvent::VaultEarlyDeStake(_) => "EarlyDeStakeEvent",
+            ProgramEvent::FungstakeStake(_) => "StakeEvent",
+            ProgramEvent::FungstakeDeStake(_) => "DeStakeEvent",
+            ProgramEvent::FungstakeClaimReward(_) => "ClaimRewardEvent",
+        }
+    }
+}
+
+fn decode<E: Discriminator + AnchorDeserialize>(data: &[u8]) -> Option<E> {
+    let body = data.strip_prefix(&E::DISCRIMINATOR[..])?;
+    E::deserialize(&mut &body[..]).ok()
+}
+
+fn decode_event(program_id: &Pubkey, data: &[u8]) -> Option<ProgramEvent> {
+    if *program_id == vault_program::ID {
+        decode(data)
+            .map(ProgramEvent::VaultPositionUpdate)
+            .or_else(|| decode(data).map(ProgramEvent::VaultEarlyDeStake))
+    } else if *program_id == fungstake_program::ID {
+        decode(data)
+            .map(ProgramEvent::FungstakeStake)
+            .or_else(|| decode(data).map(ProgramEvent::FungstakeDeStake))
+            .or_else(|| decode(data).map(ProgramEvent::FungstakeClaimReward))
+    } else {
+        None
+    }
+}
+
+/// events of both programs in log order. `Program data:` lines belong to the program on top
+/// of the invocation stack, so data logged by other programs is never decoded as an event
+pub fn parse_logs(logs: &[String]) -> Vec<ProgramEvent> {
+    let mut invocations: Vec<Pubkey> = vec![];
+    let mut events = vec![];
+
+    for log in logs {
+        let mut words = log.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("Program"), Some("data:"), Some(data)) => {
+                let (Some(program_id), Ok(data)) = (invocations.last(), STANDARD.decode(data))
+                else {
+                    continue;
+                };
+                events.extend(decode_event(program_id, &data));
+            }
+            (Some("Program"), Some(program_id), Some("invoke")) => {
+                invocations.push(program_id.parse().unwrap_or_default());
+            }
+            (Some("Program"), Some(_), Some("success" | "failed:")) => {
+                invocations.pop();
+            }
+            _ => {}
+        }
+    }
+    events
+}
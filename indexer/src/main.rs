@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use stake_indexer::{
+    source::{fetch_transactions, load_fixtures},
+    store::Store,
+};
+
+/// Index vault and fungstake events into SQLite
+#[derive(Parser)]
+#[command(name = "stake-indexer", version)]
+struct Cli {
+    /// SQLite database, created if missing
+    #[arg(long, default_value = "stake-index.sqlite")]
+    db: PathBuf,
+    /// RPC endpoint to read transactions from, a local validator by default
+    #[arg(long, short, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// saved `getTransaction` responses (json encoding) to read instead of the cluster
+    #[arg(long, num_args = 1..)]
+    fixtures: Vec<PathBuf>,
+    /// replay from this slot, the last indexed slot by default
+    #[arg(long)]
+    from_slot: Option<u64>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut store = Store::open(&cli.db)?;
+
+    let from_slot = match cli.from_slot {
+        Some(slot) => slot,
+        None => store.cursor()?.unwrap_or_default(),
+    };
+    let transactions = if cli.fixtures.is_empty() {
+        fetch_transactions(&cli.url, from_slot)?
+    } else {
+        load_fixtures(&cli.fixtures, from_slot)?
+    };
+
+    let mut events = 0;
+    for transaction in &transactions {
+        events += store.apply(transaction)?;
+    }
+    println!(
+        "indexed {} transactions from slot {from_slot}, {events} new events, cursor at slot {}",
+        transactions.len(),
+        store.cursor()?.unwrap_or_default(),
+    );
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use std::{collections::HashSet, fs, path::Path, str::FromStr};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_request::RpcRequest,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use stake_client::{fungstake_program, vault_program};
+
+/// successful transaction of either program
+pub struct Transaction {
+    pub slot: u64,
+    pub signature: String,
+    pub logs: Vec<String>,
+}
+
+/// `getTransaction` result with `json` encoding, only the fields used by the indexer
+#[derive(Deserialize)]
+struct TransactionJson {
+    slot: u64,
+    transaction: TransactionBodyJson,
+    meta: Option<MetaJson>,
+}
+
+#[derive(Deserialize)]
+struct TransactionBodyJson {
+    signatures: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MetaJson {
+    err: Option<serde_json::Value>,
+    log_messages: Option<Vec<String>>,
+}
+
+/// shapes accepted in a fixture file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FixtureJson {
+    Transaction(TransactionJson),
+    Transactions(Vec<TransactionJson>),
+    /// full JSON-RPC response
+    Response {
+        result: TransactionJson,
+    },
+}
+
+impl TransactionJson {
+    /// None for failed transactions, their events were rolled back
+    fn into_transaction(self) -> Option<Transaction> {
+        let meta = self.meta?;
+        if meta.err.is_some() {
+            return None;
+        }
+        Some(Transaction {
+            slot: self.slot,
+            signature: self.transaction.signatures.into_iter().next()?,
+            logs: meta.log_messages.unwrap_or_default(),
+        })
+    }
+}
+
+/// transactions of saved `getTransaction` responses from `from_slot`, in slot order.
+/// A file holds one response, an array of them or a full JSON-RPC response
+pub fn load_fixtures(paths: &[impl AsRef<Path>], from_slot: u64) -> Result<Vec<Transaction>> {
+    let mut transactions = vec![];
+    for path in paths {
+        let path = path.as_ref();
+        let fixture = serde_json::from_slice(&fs::read(path)?)
+            .with_context(|| format!("parsing {}", path.display()))?;
+        let parsed = match fixture {
+            FixtureJson::Transaction(transaction)
+            | FixtureJson::Response {
+                result: transaction,
+            } => {
+                vec![transaction]
+            }
+            FixtureJson::Transactions(transactions) => transactions,
+        };
+        transactions.extend(
+            parsed
+                .into_iter()
+                .filter_map(TransactionJson::into_transaction),
+        );
+    }
+
+    transactions.retain(|transaction| transaction.slot >= from_slot);
+    // stable, transactions of the same slot keep their recorded order
+    transactions.sort_by_key(|transaction| transaction.slot);
+    Ok(transactions)
+}
+
+/// transactions of both programs from `from_slot`, in slot order
+pub fn fetch_transactions(url: &str, from_slot: u64) -> Result<Vec<Transaction>> {
+    let rpc = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+
+    let mut signatures = vec![];
+    for program_id in [vault_program::ID, fungstake_program::ID] {
+        // newest first, paginated backward until from_slot
+        let mut program_signatures = vec![];
+        let mut before = None;
+        loop {
+            let page = rpc.get_signatures_for_address_with_config(
+                &program_id,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: None,
+                    limit: None,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            before = Some(Signature::from_str(&last.signature)?);
+            let reached_from_slot = last.slot < from_slot;
+
+            program_signatures.extend(
+                page.into_iter()
+                    .filter(|status| status.slot >= from_slot && status.err.is_none())
+                    .map(|status| (status.slot, status.signature)),
+            );
+            if reached_from_slot {
+                break;
+            }
+        }
+        program_signatures.reverse();
+        signatures.extend(program_signatures);
+    }
+    signatures.sort_by_key(|(slot, _)| *slot);
+    // transactions calling both programs are listed twice
+    let mut seen = HashSet::new();
+    signatures.retain(|(_, signature)| seen.insert(signature.clone()));
+
+    let mut transactions = vec![];
+    for (_, signature) in signatures {
+        let transaction: TransactionJson = rpc.send(
+            RpcRequest::GetTransaction,
+            json!([
+                signature,
+                {
+                    "encoding": "json",
+                    "commitment": "confirmed",
+                    "maxSupportedTransactionVersion": 0,
+                }
+            ]),
+        )?;
+        transactions.extend(transaction.into_transaction());
+    }
+    Ok(transactions)
+}
@@ -0,0 +1,208 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{
+    logs::{parse_logs, ProgramEvent},
+    source::Transaction,
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS positions (
+    program TEXT NOT NULL,
+    vault TEXT NOT NULL,
+    staker TEXT NOT NULL,
+    position_id INTEGER NOT NULL,
+    stake_amount INTEGER NOT NULL,
+    unbonding INTEGER NOT NULL,
+    updated_slot INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (program, vault, staker, position_id)
+);
+CREATE TABLE IF NOT EXISTS vaults (
+    program TEXT NOT NULL,
+    vault TEXT NOT NULL,
+    total_staked INTEGER NOT NULL,
+    updated_slot INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (program, vault)
+);
+CREATE TABLE IF NOT EXISTS claims (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    vault TEXT NOT NULL,
+    staker TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    slot INTEGER NOT NULL
+);
+";
+
+/// position state carried by an event, fungstake has a single position per wallet and vault
+struct PositionState {
+    program: &'static str,
+    vault: String,
+    staker: String,
+    position_id: u64,
+    stake_amount: u64,
+    unbonding: bool,
+    vault_total_staked: u64,
+    timestamp: i64,
+}
+
+impl PositionState {
+    fn from_event(event: &ProgramEvent) -> Option<Self> {
+        match event {
+            ProgramEvent::VaultPositionUpdate(event) => Some(PositionState {
+                program: "vault",
+                vault: event.vault.to_string(),
+                staker: event.staker.to_string(),
+                position_id: event.stake_detail_id,
+                stake_amount: event.stake_amount,
+                unbonding: event.unbonding,
+                vault_total_staked: event.vault_total_staked,
+                timestamp: event.timestamp,
+            }),
+            ProgramEvent::FungstakeStake(event) => Some(PositionState {
+                program: "fungstake",
+                vault: event.vault.to_string(),
+                staker: event.staker.to_string(),
+                position_id: 0,
+                stake_amount: event.stake_amount,
+                unbonding: false,
+                vault_total_staked: event.vault_total_staked,
+                timestamp: event.timestamp,
+            }),
+            ProgramEvent::FungstakeDeStake(event) => Some(PositionState {
+                program: "fungstake",
+                vault: event.vault.to_string(),
+                staker: event.staker.to_string(),
+                position_id: 0,
+                stake_amount: event.stake_amount,
+                unbonding: false,
+                vault_total_staked: event.vault_total_staked,
+                timestamp: event.timestamp,
+            }),
+            ProgramEvent::VaultEarlyDeStake(_) | ProgramEvent::FungstakeClaimReward(_) => None,
+        }
+    }
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Store { conn })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// highest slot indexed so far
+    pub fn cursor(&self) -> rusqlite::Result<Option<u64>> {
+        self.conn
+            .query_row("SELECT slot FROM cursor WHERE id = 0", [], |row| row.get(0))
+            .optional()
+    }
+
+    /// apply the events of a transaction, returns how many were new.
+    /// Events already applied are skipped, so replaying a transaction changes nothing
+    pub fn apply(&mut self, transaction: &Transaction) -> rusqlite::Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut applied = 0;
+
+        for (index, event) in parse_logs(&transaction.logs).iter().enumerate() {
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO events (signature, event_index, slot, name)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![transaction.signature, index, transaction.slot, event.name()],
+            )?;
+            if inserted == 0 {
+                continue;
+            }
+            applied += 1;
+
+            if let Some(state) = PositionState::from_event(event) {
+                // an older replayed slot never overwrites a newer state
+                tx.execute(
+                    "INSERT INTO positions
+                        (program, vault, staker, position_id, stake_amount, unbonding, updated_slot, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                     ON CONFLICT (program, vault, staker, position_id) DO UPDATE SET
+                        stake_amount = excluded.stake_amount,
+                        unbonding = excluded.unbonding,
+                        updated_slot = excluded.updated_slot,
+                        updated_at = excluded.updated_at
+                     WHERE excluded.updated_slot >= positions.updated_slot",
+                    params![
+                        state.program,
+                        state.vault,
+                        state.staker,
+                        state.position_id as i64,
+                        state.stake_amount as i64,
+                        state.unbonding,
+                        transaction.slot,
+                        state.timestamp,
+                    ],
+                )?;
+                tx.execute(
+                    "INSERT INTO vaults (program, vault, total_staked, updated_slot, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT (program, vault) DO UPDATE SET
+                        total_staked = excluded.total_staked,
+                        updated_slot = excluded.updated_slot,
+                        updated_at = excluded.updated_at
+                     WHERE excluded.updated_slot >= vaults.updated_slot",
+                    params![
+                        state.program,
+                        state.vault,
+                        state.vault_total_staked as i64,
+                        transaction.slot,
+                        state.timestamp,
+                    ],
+                )?;
+            }
+
+            if let ProgramEvent::FungstakeClaimReward(event) = event {
+                tx.execute(
+                    "INSERT INTO claims (signature, event_index, slot, vault, staker, amount, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        transaction.signature,
+                        index,
+                        transaction.slot,
+                        event.vault.to_string(),
+                        event.staker.to_string(),
+                        event.amount as i64,
+                        event.timestamp,
+                    ],
+                )?;
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO cursor (id, slot) VALUES (0, ?1)
+             ON CONFLICT (id) DO UPDATE SET slot = max(slot, excluded.slot)",
+            params![transaction.slot],
+        )?;
+        tx.commit()?;
+        Ok(applied)
+    }
+}
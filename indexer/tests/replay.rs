@@ -0,0 +1,402 @@
+//! Recorded transactions replayed into a `Store`: rows after a replay, duplicated and
+//! re-landed transactions, and transactions replayed out of slot order.
+
+use std::path::PathBuf;
+
+use anchor_lang::Event;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use stake_client::{fungstake_program, test_utils::key, vault_program};
+use stake_indexer::{
+    logs::{
+        parse_logs, ClaimRewardEvent, DeStakeEvent, EarlyDeStakeEvent, PositionUpdateEvent,
+        StakeEvent,
+    },
+    source::{load_fixtures, Transaction},
+    store::Store,
+};
+
+/// logs of a top level instruction of `program_id` emitting `events`
+fn logs(program_id: &Pubkey, events: &[Vec<u8>]) -> Vec<String> {
+    let mut logs = vec![
+        format!("Program {program_id} invoke [1]"),
+        "Program log: Instruction: Stake".to_string(),
+    ];
+    logs.extend(
+        events
+            .iter()
+            .map(|event| format!("Program data: {}", STANDARD.encode(event))),
+    );
+    logs.push(format!(
+        "Program {program_id} consumed 21000 of 200000 compute units"
+    ));
+    logs.push(format!("Program {program_id} success"));
+    logs
+}
+
+fn transaction(slot: u64, signature: &str, logs: Vec<String>) -> Transaction {
+    Transaction {
+        slot,
+        signature: signature.to_string(),
+        logs,
+    }
+}
+
+fn position_update(staker: &Pubkey, id: u64, amount: u64, unbonding: bool, total: u64) -> Vec<u8> {
+    PositionUpdateEvent {
+        vault: key(1),
+        staker: *staker,
+        stake_detail_id: id,
+        stake_amount: amount,
+        unbonding,
+        vault_total_staked: total,
+        timestamp: 1_700_000_000 + id as i64,
+    }
+    .data()
+}
+
+fn vault_tx(slot: u64, signature: &str, events: &[Vec<u8>]) -> Transaction {
+    transaction(slot, signature, logs(&vault_program::ID, events))
+}
+
+/// (vault, staker, position_id, stake_amount, unbonding, updated_slot)
+type PositionRow = (String, String, i64, i64, bool, i64);
+
+fn positions(store: &Store, program: &str) -> Vec<PositionRow> {
+    let mut statement = store
+        .connection()
+        .prepare(
+            "SELECT vault, staker, position_id, stake_amount, unbonding, updated_slot
+             FROM positions WHERE program = ?1 ORDER BY staker, position_id",
+        )
+        .unwrap();
+    statement
+        .query_map([program], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        })
+        .unwrap()
+        .collect::<rusqlite::Result<_>>()
+        .unwrap()
+}
+
+/// (total_staked, updated_slot) of a vault
+fn vault_total(store: &Store, program: &str, vault: &Pubkey) -> (i64, i64) {
+    store
+        .connection()
+        .query_row(
+            "SELECT total_staked, updated_slot FROM vaults WHERE program = ?1 AND vault = ?2",
+            [program, &vault.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap()
+}
+
+fn count(store: &Store, table: &str) -> i64 {
+    store
+        .connection()
+        .query_row(&format!("SELECT count(*) FROM {table}"), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+}
+
+fn row(staker: &Pubkey, id: i64, amount: i64, unbonding: bool, slot: i64) -> PositionRow {
+    (
+        key(1).to_string(),
+        staker.to_string(),
+        id,
+        amount,
+        unbonding,
+        slot,
+    )
+}
+
+#[test]
+fn replay_materializes_positions_vaults_and_claims() {
+    let mut store = Store::open(":memory:").unwrap();
+    let (alice, bob) = (key(10), key(11));
+    let early_destake = EarlyDeStakeEvent {
+        vault: key(1),
+        staker: bob,
+        stake_detail_id: 1,
+        unstake_amount: 45,
+        penalty_amount: 5,
+        redistributed: true,
+        timestamp: 1_700_000_100,
+    }
+    .data();
+
+    let vault_transactions = [
+        vault_tx(
+            10,
+            "stake-alice",
+            &[position_update(&alice, 1, 100, false, 100)],
+        ),
+        vault_tx(11, "stake-bob", &[position_update(&bob, 1, 50, false, 150)]),
+        // request_unstake keeps the stake in the vault until withdraw
+        vault_tx(
+            12,
+            "unstake-alice",
+            &[position_update(&alice, 1, 100, true, 150)],
+        ),
+        vault_tx(
+            13,
+            "early-destake-bob",
+            &[position_update(&bob, 1, 0, false, 100), early_destake],
+        ),
+    ];
+    for transaction in &vault_transactions {
+        store.apply(transaction).unwrap();
+    }
+
+    let fungstake_vault = key(2);
+    let fungstake_events = [
+        StakeEvent {
+            vault: fungstake_vault,
+            staker: alice,
+            amount: 300,
+            stake_amount: 300,
+            vault_total_staked: 300,
+            timestamp: 1_700_000_200,
+        }
+        .data(),
+        DeStakeEvent {
+            vault: fungstake_vault,
+            staker: alice,
+            amount: 100,
+            stake_amount: 200,
+            vault_total_staked: 200,
+            timestamp: 1_700_000_300,
+        }
+        .data(),
+        ClaimRewardEvent {
+            vault: fungstake_vault,
+            staker: alice,
+            amount: 40,
+            refund: 0,
+            timestamp: 1_700_000_400,
+        }
+        .data(),
+    ];
+    let applied = store
+        .apply(&transaction(
+            14,
+            "fungstake-alice",
+            logs(&fungstake_program::ID, &fungstake_events),
+        ))
+        .unwrap();
+    assert_eq!(applied, 3);
+
+    assert_eq!(
+        positions(&store, "vault"),
+        [row(&alice, 1, 100, true, 12), row(&bob, 1, 0, false, 13)]
+    );
+    assert_eq!(vault_total(&store, "vault", &key(1)), (100, 13));
+
+    let fungstake_positions = positions(&store, "fungstake");
+    assert_eq!(fungstake_positions.len(), 1);
+    assert_eq!(
+        fungstake_positions[0],
+        (
+            fungstake_vault.to_string(),
+            alice.to_string(),
+            0,
+            200,
+            false,
+            14
+        )
+    );
+    assert_eq!(
+        vault_total(&store, "fungstake", &fungstake_vault),
+        (200, 14)
+    );
+
+    let claim: (String, i64, i64) = store
+        .connection()
+        .query_row(
+            "SELECT staker, amount, event_index FROM claims",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!(claim, (alice.to_string(), 40, 2));
+    assert_eq!(count(&store, "events"), 8);
+    assert_eq!(store.cursor().unwrap(), Some(14));
+}
+
+#[test]
+fn duplicated_and_relanded_transactions_are_applied_once() {
+    let mut store = Store::open(":memory:").unwrap();
+    let alice = key(10);
+    let stake = vault_tx(10, "stake", &[position_update(&alice, 1, 100, false, 100)]);
+    let destake = vault_tx(11, "destake", &[position_update(&alice, 1, 40, false, 40)]);
+
+    assert_eq!(store.apply(&stake).unwrap(), 1);
+    assert_eq!(store.apply(&destake).unwrap(), 1);
+    assert_eq!(store.apply(&stake).unwrap(), 0);
+    assert_eq!(store.apply(&destake).unwrap(), 0);
+    assert_eq!(positions(&store, "vault"), [row(&alice, 1, 40, false, 11)]);
+    assert_eq!(count(&store, "events"), 2);
+
+    // after a fork is abandoned its transactions can land again at a later slot with the
+    // same signature, the events they carry were already applied
+    let relanded = vault_tx(15, "stake", &[position_update(&alice, 1, 100, false, 100)]);
+    assert_eq!(store.apply(&relanded).unwrap(), 0);
+    assert_eq!(positions(&store, "vault"), [row(&alice, 1, 40, false, 11)]);
+    assert_eq!(vault_total(&store, "vault", &key(1)), (40, 11));
+    assert_eq!(store.cursor().unwrap(), Some(15));
+}
+
+#[test]
+fn older_slots_do_not_overwrite_newer_state() {
+    let mut store = Store::open(":memory:").unwrap();
+    let alice = key(10);
+    store
+        .apply(&vault_tx(
+            20,
+            "late",
+            &[position_update(&alice, 1, 70, false, 70)],
+        ))
+        .unwrap();
+
+    // a transaction missed by an earlier run and replayed from an older slot is recorded,
+    // but the position and vault keep their newer state
+    let missed = vault_tx(15, "missed", &[position_update(&alice, 1, 100, false, 100)]);
+    assert_eq!(store.apply(&missed).unwrap(), 1);
+    assert_eq!(positions(&store, "vault"), [row(&alice, 1, 70, false, 20)]);
+    assert_eq!(vault_total(&store, "vault", &key(1)), (70, 20));
+    assert_eq!(store.cursor().unwrap(), Some(20));
+
+    // transactions of the same slot are applied in order
+    store
+        .apply(&vault_tx(
+            20,
+            "same-slot",
+            &[position_update(&alice, 1, 0, false, 0)],
+        ))
+        .unwrap();
+    assert_eq!(positions(&store, "vault"), [row(&alice, 1, 0, false, 20)]);
+    assert_eq!(count(&store, "events"), 3);
+}
+
+#[test]
+fn data_logged_by_other_programs_is_not_an_event() {
+    let alice = key(10);
+    let event = position_update(&alice, 1, 100, false, 100);
+    let other_program = key(99);
+    let mut logs = logs(&vault_program::ID, std::slice::from_ref(&event));
+    // a CPI into another program logging the same bytes, before the vault logs again
+    let cpi = [
+        format!("Program {other_program} invoke [2]"),
+        format!("Program data: {}", STANDARD.encode(&event)),
+        format!("Program {other_program} success"),
+        format!("Program data: {}", STANDARD.encode(&event)),
+    ];
+    logs.splice(2..2, cpi);
+    // the same event from the fungstake program does not match its events
+    logs.extend(self::logs(&fungstake_program::ID, &[event]));
+
+    assert_eq!(parse_logs(&logs).len(), 2);
+    let mut store = Store::open(":memory:").unwrap();
+    assert_eq!(store.apply(&transaction(10, "cpi", logs)).unwrap(), 2);
+}
+
+/// `getTransaction` response as saved by the indexer fixtures
+fn recorded(transaction: &Transaction, err: Option<Value>) -> Value {
+    json!({
+        "slot": transaction.slot,
+        "blockTime": 1_700_000_000,
+        "transaction": {
+            "signatures": [transaction.signature],
+            "message": { "accountKeys": [], "instructions": [] },
+        },
+        "meta": {
+            "err": err,
+            "fee": 5000,
+            "logMessages": transaction.logs,
+        },
+    })
+}
+
+fn fixture_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn recorded_fixtures_replay_into_a_persisted_store() {
+    let dir = fixture_dir("recorded_fixtures");
+    let alice = key(10);
+    let stake = vault_tx(10, "stake", &[position_update(&alice, 1, 100, false, 100)]);
+    let failed = vault_tx(11, "failed", &[position_update(&alice, 1, 0, false, 0)]);
+    let top_up = vault_tx(12, "top-up", &[position_update(&alice, 1, 150, false, 150)]);
+    let destake = vault_tx(13, "destake", &[position_update(&alice, 1, 50, false, 50)]);
+
+    // one file per shape, out of slot order
+    let files = [
+        (
+            "response.json",
+            json!({ "jsonrpc": "2.0", "result": recorded(&destake, None), "id": 1 }),
+        ),
+        (
+            "array.json",
+            json!([
+                recorded(&top_up, None),
+                recorded(
+                    &failed,
+                    Some(json!({ "InstructionError": [0, { "Custom": 6000 }] }))
+                ),
+            ]),
+        ),
+        ("single.json", recorded(&stake, None)),
+    ];
+    let paths: Vec<PathBuf> = files
+        .iter()
+        .map(|(name, json)| {
+            let path = dir.join(name);
+            std::fs::write(&path, json.to_string()).unwrap();
+            path
+        })
+        .collect();
+
+    let loaded = load_fixtures(&paths, 0).unwrap();
+    let slots: Vec<u64> = loaded.iter().map(|transaction| transaction.slot).collect();
+    assert_eq!(slots, [10, 12, 13]);
+    let from_12: Vec<u64> = load_fixtures(&paths, 12)
+        .unwrap()
+        .iter()
+        .map(|transaction| transaction.slot)
+        .collect();
+    assert_eq!(from_12, [12, 13]);
+
+    let db = dir.join("index.sqlite");
+    {
+        let mut store = Store::open(&db).unwrap();
+        for transaction in &loaded {
+            store.apply(transaction).unwrap();
+        }
+    }
+
+    // a restarted indexer resumes from its cursor, the last slot is replayed without effect
+    let mut store = Store::open(&db).unwrap();
+    let cursor = store.cursor().unwrap().unwrap();
+    assert_eq!(cursor, 13);
+    let applied: usize = load_fixtures(&paths, cursor)
+        .unwrap()
+        .iter()
+        .map(|transaction| store.apply(transaction).unwrap())
+        .sum();
+    assert_eq!(applied, 0);
+    assert_eq!(positions(&store, "vault"), [row(&alice, 1, 50, false, 13)]);
+    assert_eq!(count(&store, "events"), 3);
+}
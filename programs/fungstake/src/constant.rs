@@ -2,4 +2,19 @@ pub mod constants {
     pub const VAULT_SEED: &[u8] = b"staking_vault";
     pub const STAKE_CONFIG_SEED: &[u8] = b"staking_config";
     pub const STAKE_INFO_SEED: &[u8] = b"stake_info";
+    pub const ALLOWLIST_SEED: &[u8] = b"allowlist";
+    /// decimals of soft_cap_usd
+    pub const USD_DECIMALS: u32 = 6;
+    pub const BPS_DENOMINATOR: u64 = 10_000;
+    /// scale of Vault.sale_rate
+    pub const SALE_RATE_PRECISION: u64 = 1_000_000_000;
+    /// a sale nobody could claim this long after end_time is void, its stakers destake in full
+    pub const SALE_REFUND_DELAY: i64 = 30 * 24 * 60 * 60;
+
+    /// layout version of StakeConfig, Vault and StakeInfo. Accounts created before the
+    /// layouts grew are version 1, 0 for vaults, or have no version field, and fail to
+    /// deserialize in every instruction until migrated, in this order: migrate_stake_config
+    /// and migrate_vault by the authority, then migrate_stake_info of each staker, which
+    /// anyone can pay for
+    pub const ACCOUNT_VERSION: u8 = 2;
 }
@@ -20,4 +20,52 @@ pub enum ErrorCode {
     OverflowError,
     #[msg("Already claimed")]
     AlreadyClaimed,
+    #[msg("Stake info still holds staked tokens or unclaimed reward")]
+    StakeInfoNotEmpty,
+    #[msg("Vault token balance does not cover its liabilities")]
+    SolvencyViolated,
+    #[msg("IncorrectAuthority")]
+    IncorrectAuthority,
+    #[msg("Price feed account is required while the USD soft cap is not reached")]
+    PriceFeedRequired,
+    #[msg("Not a Pyth price account or not the configured price feed")]
+    InvalidPriceFeed,
+    #[msg("Price is not currently trading")]
+    PriceUnavailable,
+    #[msg("Price is older than max_price_age")]
+    PriceStale,
+    #[msg("Price confidence interval is wider than max_confidence_bps")]
+    PriceConfidenceTooWide,
+    #[msg("Phase id, time window or weight is invalid")]
+    InvalidPhase,
+    #[msg("Phases can only be added before the first stake")]
+    PhasesLocked,
+    #[msg("No phase is open for staking")]
+    PhaseNotActive,
+    #[msg("Wallet is not on the allowlist of this phase")]
+    NotAllowlisted,
+    #[msg("Stake exceeds the per-wallet cap of this phase")]
+    PhaseCapExceeded,
+    #[msg("KYC attestation is required, verify it with an ed25519 instruction before stake")]
+    KycRequired,
+    #[msg("KYC attestation is not signed by the attestor or not for this wallet")]
+    InvalidKycAttestation,
+    #[msg("KYC attestation has expired")]
+    KycAttestationExpired,
+    #[msg("Stake exceeds the max allocation of the KYC attestation")]
+    KycAllocationExceeded,
+    #[msg("Sale rate and allocation must both be set or both be 0")]
+    InvalidSaleTerms,
+    #[msg("Sale terms can only be changed before the first stake")]
+    SaleTermsLocked,
+    #[msg("Stake is spent on the sale, claim to get the refund")]
+    StakeCommittedToSale,
+    #[msg("Sale reward was not funded before the refund deadline")]
+    SaleVoid,
+    #[msg("Account already has the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Reward token account is required once the vault reached tge")]
+    RewardAccountRequired,
+    #[msg("Phase weight cannot change once the phase started or got stake")]
+    PhaseWeightLocked,
 }
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct StakeEvent {
+    pub vault: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    /// StakeInfo.stake_amount after the stake
+    pub stake_amount: u64,
+    /// Vault.total_staked after the stake
+    pub vault_total_staked: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DeStakeEvent {
+    pub vault: Pubkey,
+    pub staker: Pubkey,
+    pub amount: u64,
+    /// StakeInfo.stake_amount after the destake
+    pub stake_amount: u64,
+    /// Vault.total_staked after the destake, unchanged once the soft cap is reached
+    pub vault_total_staked: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClaimRewardEvent {
+    pub vault: Pubkey,
+    pub staker: Pubkey,
+    /// reward tokens sent to the staker
+    pub amount: u64,
+    /// staking tokens refunded by an oversubscribed sale
+    pub refund: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,127 @@
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    StakeConfig, Vault,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::error::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VaultAuditReport {
+    /// tokens held by the vault staking token account
+    pub stake_balance: u64,
+    /// staked tokens not destaked yet, and sale proceeds not withdrawn yet
+    pub stake_liabilities: u64,
+    /// tokens held by the vault reward token account
+    pub reward_balance: u64,
+    /// reward shares not claimed yet, 0 before tge
+    pub unclaimed_reward: u64,
+}
+
+#[derive(Accounts)]
+pub struct AuditVault<'info> {
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    /// CHECK: currency_mint for rewarding, not staking
+    pub reward_currency_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: currency_mint for staking, not rewarding
+    pub stake_currency_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            reward_currency_mint.key().as_ref()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        associated_token::mint = reward_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_reward_token_account: Box<Account<'info, TokenAccount>>,
+}
+
+impl<'info> AuditVault<'info> {
+    /// permissionless solvency check, meant to be simulated by monitoring.
+    /// The report is returned as return data, or logged before failing with SolvencyViolated
+    pub fn process(&self) -> Result<VaultAuditReport> {
+        let vault = &self.vault;
+
+        // total_staked is the sum of snapshot amounts, it includes tokens destaked after end_time.
+        // Stake spent on a sale stays owed to the authority until withdrawn
+        let stake_liabilities =
+            vault.total_staked - vault.destaked_after_end - vault.withdrawn_proceeds;
+        let unclaimed_reward = if !vault.has_phase_pools() {
+            unclaimed_share(
+                vault.total_staked,
+                vault.claimed_snapshot_amount,
+                vault.total_reward,
+            )
+        } else {
+            let mut unclaimed_reward = 0;
+            for (index, phase) in vault.phases[..vault.phase_count as usize]
+                .iter()
+                .enumerate()
+            {
+                unclaimed_reward += unclaimed_share(
+                    phase.total_staked,
+                    phase.claimed_snapshot_amount,
+                    vault.phase_reward_pool(index)?,
+                );
+            }
+            unclaimed_reward
+        };
+
+        let report = VaultAuditReport {
+            stake_balance: self.vault_token_account.amount,
+            stake_liabilities,
+            reward_balance: self.vault_reward_token_account.amount,
+            unclaimed_reward,
+        };
+
+        // both liabilities are paid from the same account when rewards are in the staking token
+        let solvent = if self.vault_token_account.key() == self.vault_reward_token_account.key() {
+            (report.stake_balance as u128)
+                >= (stake_liabilities as u128) + (unclaimed_reward as u128)
+        } else {
+            report.stake_balance >= stake_liabilities && report.reward_balance >= unclaimed_reward
+        };
+
+        if !solvent {
+            msg!(
+                "solvency violated: stake_balance {} stake_liabilities {} reward_balance {} unclaimed_reward {}",
+                report.stake_balance,
+                stake_liabilities,
+                report.reward_balance,
+                unclaimed_reward
+            );
+            return Err(ErrorCode::SolvencyViolated.into());
+        }
+
+        Ok(report)
+    }
+}
+
+/// part of `reward` owed to the snapshot amounts which did not claim yet
+fn unclaimed_share(total_staked: u64, claimed_snapshot_amount: u64, reward: u64) -> u64 {
+    if total_staked == 0 {
+        return 0;
+    }
+    ((reward as u128) * ((total_staked - claimed_snapshot_amount) as u128) / (total_staked as u128))
+        as u64
+}
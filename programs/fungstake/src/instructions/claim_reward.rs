@@ -5,7 +5,10 @@ use anchor_spl::{
 };
 
 use crate::{
-    constant::constants::STAKE_INFO_SEED, error::ErrorCode, utils::token_transfer_with_signer,
+    constant::constants::STAKE_INFO_SEED,
+    error::ErrorCode,
+    event::{ClaimRewardEvent, DeStakeEvent},
+    utils::token_transfer_with_signer,
     StakeInfo,
 };
 use crate::{
@@ -50,6 +53,22 @@ pub struct ClaimReward<'info> {
     )]
     vault_reward_token_account: Box<Account<'info, TokenAccount>>,
 
+    /// pays the refunds of an oversubscribed sale
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = signer,
+    )]
+    user_stake_token_account: Box<Account<'info, TokenAccount>>,
+
     /// CHECK: pda should be init when stake
     #[account(
         mut,
@@ -101,23 +120,39 @@ impl<'info> ClaimReward<'info> {
 
         // For the first user who claims the reward, update vault's total reward
         if !vault.reach_tge {
+            // stakers of a void sale may have destaked already, nothing can be sold anymore
+            if vault.is_void_sale(current_timestamp) {
+                return Err(ErrorCode::SaleVoid.into());
+            }
+
             // this means relayer has not invoked tge yet, can't claim
             if self.vault_reward_token_account.amount == 0 {
                 return Err(ErrorCode::TgeNotYetReached.into());
             }
 
+            // a sale pays the sold reward tokens only, the rest of the balance is not shared
+            let total_reward = if vault.is_sale() {
+                vault.sold_reward()
+            } else {
+                self.vault_reward_token_account.amount
+            };
+            if self.vault_reward_token_account.amount < total_reward {
+                return Err(ErrorCode::TgeNotYetReached.into());
+            }
+
             vault.reach_tge = true;
             // update vault's reward balance
-            vault.total_reward = self.vault_reward_token_account.amount;
+            vault.total_reward = total_reward;
         }
 
-        let earned_amount = get_earned_amount(
-            self.user_stake_info_pda.snapshot_amount,
-            vault.total_staked,
-            vault.total_reward,
-        )?;
+        let stake_info = &mut self.user_stake_info_pda;
+        let earned_amount = get_earned_amount(stake_info, vault)?;
 
-        self.user_stake_info_pda.has_claimed = true;
+        stake_info.has_claimed = true;
+        vault.claimed_snapshot_amount += stake_info.snapshot_amount;
+        for index in 0..vault.phase_count as usize {
+            vault.phases[index].claimed_snapshot_amount += stake_info.phase_snapshot_amounts[index];
+        }
 
         token_transfer_with_signer(
             self.vault_reward_token_account.to_account_info(),
@@ -128,19 +163,80 @@ impl<'info> ClaimReward<'info> {
             earned_amount,
         )?;
 
+        // an oversubscribed sale spends the same share of everyone's stake, the rest is refunded
+        let mut refund_amount = 0;
+        if vault.is_sale() {
+            let spent_amount = pro_rata(
+                stake_info.snapshot_amount,
+                vault.total_staked,
+                vault.sold_stake(),
+            )?;
+            refund_amount = stake_info.stake_amount - spent_amount;
+            stake_info.stake_amount = 0;
+            vault.destaked_after_end += refund_amount;
+            vault.sale_proceeds += spent_amount;
+
+            token_transfer_with_signer(
+                self.vault_token_account.to_account_info(),
+                vault.to_account_info(),
+                self.user_stake_token_account.to_account_info(),
+                &self.token_program,
+                &[&vault.auth_seeds(&vault_config.key().to_bytes())],
+                refund_amount,
+            )?;
+
+            // the position is settled, indexers track it from destake events
+            emit!(DeStakeEvent {
+                vault: vault.key(),
+                staker: self.signer.key(),
+                amount: refund_amount,
+                stake_amount: stake_info.stake_amount,
+                vault_total_staked: vault.total_staked,
+                timestamp: current_timestamp,
+            });
+        }
+
+        emit!(ClaimRewardEvent {
+            vault: vault.key(),
+            staker: self.signer.key(),
+            amount: earned_amount,
+            refund: refund_amount,
+            timestamp: current_timestamp,
+        });
+
         Ok(())
     }
 }
 
-fn get_earned_amount(
-    staked_amount: u64,
-    total_staked_amount: u64,
-    total_reward: u64,
-) -> Result<u64> {
+/// pro-rata share of total_reward, or the sum of the pro-rata shares of each weighted phase pool
+fn get_earned_amount(stake_info: &StakeInfo, vault: &Vault) -> Result<u64> {
+    if !vault.has_phase_pools() {
+        return pro_rata(
+            stake_info.snapshot_amount,
+            vault.total_staked,
+            vault.total_reward,
+        );
+    }
+
+    let mut earned_amount = 0;
+    for index in 0..vault.phase_count as usize {
+        let phase_amount = stake_info.phase_snapshot_amounts[index];
+        if phase_amount > 0 {
+            earned_amount += pro_rata(
+                phase_amount,
+                vault.phases[index].total_staked,
+                vault.phase_reward_pool(index)?,
+            )?;
+        }
+    }
+    Ok(earned_amount)
+}
+
+fn pro_rata(staked_amount: u64, total_staked_amount: u64, total_reward: u64) -> Result<u64> {
     // Divide the losing pool by winning for earnings multiplier
     Ok((staked_amount as u128)
         .checked_mul(total_reward as u128)
-        .ok_or::<ErrorCode>(ErrorCode::OverflowError.into())?
+        .ok_or(ErrorCode::OverflowError)?
         .checked_div(total_staked_amount as u128)
-        .ok_or::<ErrorCode>(ErrorCode::OverflowError.into())? as u64)
+        .ok_or(ErrorCode::OverflowError)? as u64)
 }
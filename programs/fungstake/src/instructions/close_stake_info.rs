@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, STAKE_INFO_SEED, VAULT_SEED},
+    error::ErrorCode,
+    StakeConfig, StakeInfo, Vault,
+};
+
+#[derive(Accounts)]
+pub struct CloseStakeInfo<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            reward_currency_mint.key().as_ref()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// rent is returned to signer
+    #[account(
+        mut,
+        close = signer,
+        seeds = [STAKE_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+        constraint = stake_info.is_drained() @ErrorCode::StakeInfoNotEmpty,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// CHECK: the SPL token for rewarding, not staking
+    pub reward_currency_mint: Account<'info, Mint>,
+
+    // CHECK: the SPL token for staking, not rewarding
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> CloseStakeInfo<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
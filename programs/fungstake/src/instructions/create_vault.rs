@@ -5,7 +5,7 @@ use anchor_spl::{
 };
 
 use crate::{
-    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    constant::constants::{ACCOUNT_VERSION, STAKE_CONFIG_SEED, VAULT_SEED},
     StakeConfig, Vault, VAULT_SIZE,
 };
 use solana_program::sysvar::SysvarId;
@@ -64,6 +64,7 @@ impl<'info> CreateVault<'info> {
     pub fn process(&mut self, vault_bump: u8) -> Result<()> {
         let vault = &mut self.vault;
         vault.bump = [vault_bump];
+        vault.version = ACCOUNT_VERSION;
         vault.reward_currency_mint = self.reward_currency_mint.key();
         vault.total_staked = 0;
         vault.end_time = 0;
@@ -1,8 +1,8 @@
 use crate::{
-    constant::constants::STAKE_CONFIG_SEED, state::StakeInfo, utils::token_transfer_with_signer,
-    StakeConfig, Vault,
+    constant::constants::STAKE_CONFIG_SEED, event::DeStakeEvent, state::StakeInfo,
+    utils::token_transfer_with_signer, StakeConfig, Vault,
 };
-use anchor_lang::{prelude::*, system_program};
+use anchor_lang::{prelude::*, system_program, AccountsClose};
 use anchor_spl::{
     associated_token::{self, AssociatedToken},
     token::{self, Mint, Token, TokenAccount},
@@ -68,7 +68,7 @@ pub struct DeStake<'info> {
 }
 
 impl<'info> DeStake<'info> {
-    pub fn process(&mut self, amount: u64) -> Result<()> {
+    pub fn process(&mut self, amount: u64, auto_close: bool) -> Result<()> {
         let stake_info = &mut self.staker_info;
         let vault = &mut self.vault;
         let vault_config = &self.stake_config.to_account_info();
@@ -87,6 +87,11 @@ impl<'info> DeStake<'info> {
             return Err(ErrorCode::TgeNotYetReached.into());
         }
 
+        // staked tokens paid for the sale, claim_reward refunds the unspent part
+        if vault.is_sale() && vault.end_time > 0 && !vault.is_void_sale(current_timestamp) {
+            return Err(ErrorCode::StakeCommittedToSale.into());
+        }
+
         // eg: stake amount = 9, amount = 10 -> unstake_amount = 9
         let unstake_amount = std::cmp::min(stake_info.stake_amount, amount);
 
@@ -95,8 +100,20 @@ impl<'info> DeStake<'info> {
         if vault.end_time == 0 {
             vault.total_staked -= unstake_amount;
             stake_info.snapshot_amount = stake_info.stake_amount;
+            vault.release_phase_stake(stake_info, unstake_amount);
+        } else {
+            vault.destaked_after_end += unstake_amount;
         }
 
+        emit!(DeStakeEvent {
+            vault: vault.key(),
+            staker: self.signer.key(),
+            amount: unstake_amount,
+            stake_amount: stake_info.stake_amount,
+            vault_total_staked: vault.total_staked,
+            timestamp: current_timestamp,
+        });
+
         // transfer to user
         token_transfer_with_signer(
             self.vault_token_account.to_account_info(),
@@ -107,6 +124,11 @@ impl<'info> DeStake<'info> {
             unstake_amount,
         )?;
 
+        // return rent of drained stake info to signer, same as close_stake_info
+        if auto_close && stake_info.is_drained() {
+            stake_info.close(self.signer.to_account_info())?;
+        }
+
         Ok(())
     }
 }
@@ -1,4 +1,7 @@
-use crate::{constant::constants::STAKE_CONFIG_SEED, StakeConfig, STAKE_CONFIG_SIZE};
+use crate::{
+    constant::constants::{ACCOUNT_VERSION, STAKE_CONFIG_SEED},
+    StakeConfig, STAKE_CONFIG_SIZE,
+};
 use anchor_lang::prelude::*;
 
 use anchor_spl::token::{Mint, Token};
@@ -42,7 +45,7 @@ impl<'info> Initialize<'info> {
         stake_config.stake_currency_mint = self.stake_currency_mint.to_account_info().key();
         stake_config.bump = [bump];
         stake_config.lock_period = lock_period;
-        stake_config.version = 1;
+        stake_config.version = ACCOUNT_VERSION;
         stake_config.soft_cap = soft_cap;
         stake_config.lock_extend_time = lock_extend_time;
 
@@ -0,0 +1,51 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{ACCOUNT_VERSION, STAKE_CONFIG_SEED},
+    utils::migrate_account,
+    StakeConfig, STAKE_CONFIG_SIZE,
+};
+
+#[derive(Accounts)]
+pub struct MigrateStakeConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: legacy stake config which cannot be deserialized yet, checked by migrate_account
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub stake_config: UncheckedAccount<'info>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateStakeConfig<'info> {
+    /// new fields get the defaults of initialize: no price feed, so the soft cap stays in
+    /// tokens, and no KYC attestor
+    pub fn process(&mut self) -> Result<()> {
+        let authority = self.authority.key();
+        migrate_account(
+            &self.stake_config.to_account_info(),
+            &self.authority,
+            &self.system_program,
+            STAKE_CONFIG_SIZE,
+            |stake_config: &mut StakeConfig| {
+                if stake_config.authority != authority {
+                    return Err(ErrorCode::IncorrectAuthority.into());
+                }
+
+                stake_config.version = ACCOUNT_VERSION;
+                Ok(())
+            },
+        )
+    }
+}
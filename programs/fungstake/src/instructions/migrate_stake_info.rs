@@ -0,0 +1,67 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+
+use crate::{
+    constant::constants::{ACCOUNT_VERSION, STAKE_CONFIG_SEED, STAKE_INFO_SEED, VAULT_SEED},
+    utils::migrate_account,
+    StakeConfig, StakeInfo, Vault, STAKE_INFO_SIZE,
+};
+
+#[derive(Accounts)]
+#[instruction(staker: Pubkey)]
+pub struct MigrateStakeInfo<'info> {
+    /// anyone can pay for the migration, its result only depends on the legacy account
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            reward_currency_mint.key().as_ref()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: legacy stake info which cannot be deserialized yet, checked by migrate_account
+    #[account(
+        mut,
+        seeds = [STAKE_INFO_SEED, vault.key().as_ref(), staker.as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub stake_info: UncheckedAccount<'info>,
+
+    /// CHECK: currency_mint for rewarding, not staking
+    pub reward_currency_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: currency_mint for staking, not rewarding
+    pub stake_currency_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateStakeInfo<'info> {
+    /// phases can only be added before the first stake, so a legacy stake has no
+    /// phase snapshot amounts
+    pub fn process(&mut self, _: Pubkey) -> Result<()> {
+        migrate_account(
+            &self.stake_info.to_account_info(),
+            &self.signer,
+            &self.system_program,
+            STAKE_INFO_SIZE,
+            |stake_info: &mut StakeInfo| {
+                stake_info.version = ACCOUNT_VERSION;
+                Ok(())
+            },
+        )
+    }
+}
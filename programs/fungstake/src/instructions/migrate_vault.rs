@@ -0,0 +1,104 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{ACCOUNT_VERSION, STAKE_CONFIG_SEED, VAULT_SEED},
+    utils::migrate_account,
+    StakeConfig, Vault, VAULT_SIZE,
+};
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    /// CHECK: legacy vault which cannot be deserialized yet, checked by migrate_account
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            reward_currency_mint.key().as_ref()
+        ],
+        bump,
+        owner = crate::ID,
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// required once the vault reached tge, its balance gives the claimed rewards
+    #[account(
+        associated_token::mint = reward_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_reward_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: currency_mint for rewarding, not staking
+    pub reward_currency_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: currency_mint for staking, not rewarding
+    pub stake_currency_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateVault<'info> {
+    /// a legacy vault is a single open round sharing its reward pro-rata. It did not track destaked_after_end and
+    /// claimed_snapshot_amount, they are derived from the balances left by the destakes and
+    /// claims so that audit_vault reports the migrated vault as solvent as it was
+    pub fn process(&mut self) -> Result<()> {
+        let stake_balance = self.vault_token_account.amount;
+        let reward_balance = match &self.vault_reward_token_account {
+            // both are paid from the same account when rewards are in the staking token
+            Some(account) if account.key() == self.vault_token_account.key() => None,
+            Some(account) => Some(account.amount),
+            None => Some(0),
+        };
+        let has_reward_account = self.vault_reward_token_account.is_some();
+
+        migrate_account(
+            &self.vault.to_account_info(),
+            &self.authority,
+            &self.system_program,
+            VAULT_SIZE,
+            |vault: &mut Vault| {
+                if vault.reach_tge && !has_reward_account {
+                    return Err(ErrorCode::RewardAccountRequired.into());
+                }
+
+                let stake_left = std::cmp::min(stake_balance, vault.total_staked);
+                let reward_left = reward_balance.unwrap_or(stake_balance - stake_left);
+                vault.version = ACCOUNT_VERSION;
+                vault.destaked_after_end = vault.total_staked - stake_left;
+                vault.claimed_snapshot_amount = legacy_claimed_snapshot_amount(vault, reward_left);
+                Ok(())
+            },
+        )
+    }
+}
+
+/// snapshot amounts whose reward share is no longer in the vault, rounded so that the
+/// unclaimed share of the rest is covered by `reward_left`
+fn legacy_claimed_snapshot_amount(vault: &Vault, reward_left: u64) -> u64 {
+    if !vault.reach_tge || vault.total_reward == 0 {
+        return 0;
+    }
+
+    let unclaimed =
+        (reward_left as u128) * (vault.total_staked as u128) / (vault.total_reward as u128);
+    vault.total_staked - std::cmp::min(unclaimed, vault.total_staked as u128) as u64
+}
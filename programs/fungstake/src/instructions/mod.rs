@@ -1,11 +1,33 @@
+pub mod audit_vault;
 pub mod claim_reward;
+pub mod close_stake_info;
 pub mod create_vault;
 pub mod destake;
 pub mod initialize;
+pub mod migrate_stake_config;
+pub mod migrate_stake_info;
+pub mod migrate_vault;
+pub mod set_allowlist_entry;
+pub mod set_kyc_attestor;
+pub mod set_phase;
+pub mod set_price_feed;
+pub mod set_sale_terms;
 pub mod stake;
+pub mod withdraw_sale_proceeds;
 
+pub use audit_vault::*;
 pub use claim_reward::*;
+pub use close_stake_info::*;
 pub use create_vault::*;
 pub use destake::*;
 pub use initialize::*;
+pub use migrate_stake_config::*;
+pub use migrate_stake_info::*;
+pub use migrate_vault::*;
+pub use set_allowlist_entry::*;
+pub use set_kyc_attestor::*;
+pub use set_phase::*;
+pub use set_price_feed::*;
+pub use set_sale_terms::*;
 pub use stake::*;
+pub use withdraw_sale_proceeds::*;
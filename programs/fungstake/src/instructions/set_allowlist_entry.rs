@@ -0,0 +1,65 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{ALLOWLIST_SEED, STAKE_CONFIG_SEED, VAULT_SEED},
+    AllowlistEntry, StakeConfig, Vault, ALLOWLIST_ENTRY_SIZE, MAX_PHASES,
+};
+
+#[derive(Accounts)]
+#[instruction(phase_id: u8, wallet: Pubkey)]
+pub struct SetAllowlistEntry<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            reward_currency_mint.key().as_ref()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [ALLOWLIST_SEED, vault.key().as_ref(), &[phase_id], wallet.as_ref()],
+        bump,
+        payer = authority,
+        space = ALLOWLIST_ENTRY_SIZE,
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    pub reward_currency_mint: Account<'info, Mint>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetAllowlistEntry<'info> {
+    pub fn process(&mut self, phase_id: u8, wallet: Pubkey, allowed: bool, bump: u8) -> Result<()> {
+        if phase_id as usize >= MAX_PHASES {
+            return Err(ErrorCode::InvalidPhase.into());
+        }
+
+        let allowlist_entry = &mut self.allowlist_entry;
+        allowlist_entry.bump = [bump];
+        allowlist_entry.vault = self.vault.key();
+        allowlist_entry.wallet = wallet;
+        allowlist_entry.phase_id = phase_id;
+        allowlist_entry.allowed = allowed;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{constant::constants::STAKE_CONFIG_SEED, StakeConfig};
+
+#[derive(Accounts)]
+pub struct SetKycAttestor<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> SetKycAttestor<'info> {
+    /// Pubkey::default() stops requiring attestations. Attestations of a previous key
+    /// are rejected as soon as the key changes
+    pub fn process(&mut self, kyc_attestor: Pubkey) -> Result<()> {
+        self.stake_config.kyc_attestor = kyc_attestor;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    Phase, StakeConfig, Vault, MAX_PHASES,
+};
+
+#[derive(Accounts)]
+pub struct SetPhase<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            reward_currency_mint.key().as_ref()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    pub reward_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> SetPhase<'info> {
+    /// Add the next phase or update an existing one. Windows must follow the previous phase
+    /// and precede the next one. The weight of a phase is locked once it started or got
+    /// stake, its stakers joined for that share of the reward
+    pub fn process(
+        &mut self,
+        phase_id: u8,
+        start_time: i64,
+        end_time: i64,
+        wallet_cap: u64,
+        weight: u16,
+        requires_allowlist: bool,
+    ) -> Result<()> {
+        let vault = &mut self.vault;
+        let index = phase_id as usize;
+        let phase_count = vault.phase_count as usize;
+
+        if index >= MAX_PHASES || index > phase_count || start_time >= end_time || weight == 0 {
+            return Err(ErrorCode::InvalidPhase.into());
+        }
+        if index > 0 && start_time < vault.phases[index - 1].end_time {
+            return Err(ErrorCode::InvalidPhase.into());
+        }
+        if index + 1 < phase_count && end_time > vault.phases[index + 1].start_time {
+            return Err(ErrorCode::InvalidPhase.into());
+        }
+
+        // stakes made without phases could not be attributed to one
+        if (phase_count == 0 && vault.total_staked > 0) || vault.reach_tge {
+            return Err(ErrorCode::PhasesLocked.into());
+        }
+
+        let phase = &mut vault.phases[index];
+        if index < phase_count
+            && weight != phase.weight
+            && (phase.start_time <= Clock::get()?.unix_timestamp || phase.total_staked > 0)
+        {
+            return Err(ErrorCode::PhaseWeightLocked.into());
+        }
+
+        *phase = Phase {
+            start_time,
+            end_time,
+            wallet_cap,
+            weight,
+            requires_allowlist,
+            ..*phase
+        };
+        if index == phase_count {
+            vault.phase_count += 1;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{constant::constants::STAKE_CONFIG_SEED, oracle::PriceFeed, StakeConfig};
+
+#[derive(Accounts)]
+pub struct SetPriceFeed<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    /// CHECK: Pyth price account, parsed by PriceFeed::load. None switches back to soft_cap
+    pub price_feed: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> SetPriceFeed<'info> {
+    /// The soft cap is evaluated in USD from the price feed on each stake
+    pub fn process(
+        &mut self,
+        soft_cap_usd: u64,
+        max_price_age: u32,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        let stake_config = &mut self.stake_config;
+
+        match &self.price_feed {
+            Some(price_feed) => {
+                PriceFeed::load(price_feed)?;
+                stake_config.price_feed = price_feed.key();
+                stake_config.soft_cap_usd = soft_cap_usd;
+                stake_config.max_price_age = max_price_age;
+                stake_config.max_confidence_bps = max_confidence_bps;
+            }
+            None => {
+                stake_config.price_feed = Pubkey::default();
+                stake_config.soft_cap_usd = 0;
+                stake_config.max_price_age = 0;
+                stake_config.max_confidence_bps = 0;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    StakeConfig, Vault,
+};
+
+#[derive(Accounts)]
+pub struct SetSaleTerms<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            reward_currency_mint.key().as_ref()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    pub reward_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> SetSaleTerms<'info> {
+    /// Sell `sale_allocation` reward tokens at `sale_rate`, or share the reward pro-rata with
+    /// both set to 0. Stakers must know the price before they commit, so the terms are locked
+    /// once somebody staked
+    pub fn process(&mut self, sale_rate: u64, sale_allocation: u64) -> Result<()> {
+        let vault = &mut self.vault;
+
+        if (sale_rate == 0) != (sale_allocation == 0) {
+            return Err(ErrorCode::InvalidSaleTerms.into());
+        }
+        if vault.total_staked > 0 || vault.end_time > 0 {
+            return Err(ErrorCode::SaleTermsLocked.into());
+        }
+
+        vault.sale_rate = sale_rate;
+        vault.sale_allocation = sale_allocation;
+
+        Ok(())
+    }
+}
@@ -1,15 +1,18 @@
 use crate::{
-    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    constant::constants::{ACCOUNT_VERSION, STAKE_CONFIG_SEED, VAULT_SEED},
+    event::StakeEvent,
+    kyc::KycAttestation,
+    oracle::PriceFeed,
     state::StakeInfo,
     utils::token_transfer_user,
-    StakeConfig, Vault, STAKE_INFO_SIZE,
+    AllowlistEntry, StakeConfig, Vault, STAKE_INFO_SIZE,
 };
 use anchor_lang::{prelude::*, system_program};
 use anchor_spl::{
     associated_token::{self, AssociatedToken},
     token::{self, Mint, Token, TokenAccount},
 };
-use solana_program::clock::Clock;
+use solana_program::{clock::Clock, sysvar};
 
 use crate::constant::constants::STAKE_INFO_SEED;
 use crate::error::ErrorCode;
@@ -67,6 +70,19 @@ pub struct Stake<'info> {
     // CHECK: the SPL token for staking, not rewarding
     pub stake_currency_mint: Account<'info, Mint>,
 
+    /// CHECK: configured Pyth price account, required while the USD soft cap is not reached
+    #[account(
+        constraint = price_feed.key() == stake_config.price_feed @ErrorCode::InvalidPriceFeed
+    )]
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// entry of the signer for the active phase, required when it has an allowlist
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+
+    /// CHECK: required with a KYC attestor, the attestation is verified by the previous instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
     #[account(address = token::ID)]
     pub token_program: Program<'info, Token>,
     #[account(address = system_program::ID)]
@@ -76,12 +92,72 @@ pub struct Stake<'info> {
 }
 
 impl<'info> Stake<'info> {
+    /// soft_cap in tokens, or soft_cap_usd valued at the price feed once one is configured
+    fn soft_cap_reached(
+        stake_config: &StakeConfig,
+        total_staked: u64,
+        price_feed: &Option<UncheckedAccount<'info>>,
+        decimals: u8,
+        current_timestamp: i64,
+    ) -> Result<bool> {
+        if !stake_config.has_price_feed() {
+            return Ok(total_staked >= stake_config.soft_cap);
+        }
+
+        let price_feed = price_feed.as_ref().ok_or(ErrorCode::PriceFeedRequired)?;
+        let price = PriceFeed::load(price_feed)?;
+        price.check(
+            current_timestamp,
+            stake_config.max_price_age,
+            stake_config.max_confidence_bps,
+        )?;
+        Ok(price.usd_value(total_staked, decimals) >= stake_config.soft_cap_usd)
+    }
+
+    /// record the deposit in the active phase, checking its allowlist and wallet cap
+    fn record_phase_stake(
+        vault: &mut Vault,
+        stake_info: &mut StakeInfo,
+        allowlist_entry: &Option<Account<'info, AllowlistEntry>>,
+        vault_key: Pubkey,
+        signer: Pubkey,
+        amount: u64,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        let index = vault
+            .active_phase(current_timestamp)
+            .ok_or(ErrorCode::PhaseNotActive)?;
+        let phase = &mut vault.phases[index];
+
+        if phase.requires_allowlist {
+            let allowed = allowlist_entry.as_ref().is_some_and(|entry| {
+                entry.allowed
+                    && entry.vault == vault_key
+                    && entry.phase_id as usize == index
+                    && entry.wallet == signer
+            });
+            if !allowed {
+                return Err(ErrorCode::NotAllowlisted.into());
+            }
+        }
+
+        let phase_amount = stake_info.phase_snapshot_amounts[index] + amount;
+        if phase.wallet_cap > 0 && phase_amount > phase.wallet_cap {
+            return Err(ErrorCode::PhaseCapExceeded.into());
+        }
+
+        stake_info.phase_snapshot_amounts[index] = phase_amount;
+        phase.total_staked += amount;
+
+        Ok(())
+    }
+
     pub fn process(&mut self, amount: u64) -> Result<()> {
         let stake_info = &mut self.user_stake_info_pda;
         let vault = &mut self.vault;
         let stake_config = &mut self.stake_config;
 
-        if amount <= 0 {
+        if amount == 0 {
             return Err(ErrorCode::NoTokens.into());
         }
 
@@ -92,17 +168,61 @@ impl<'info> Stake<'info> {
             return Err(ErrorCode::VaultEnded.into());
         }
 
+        if stake_config.has_kyc_attestor() {
+            let instructions_sysvar = self
+                .instructions_sysvar
+                .as_ref()
+                .ok_or(ErrorCode::KycRequired)?;
+            KycAttestation::load(instructions_sysvar, &stake_config.kyc_attestor)?.check(
+                &stake_config.key(),
+                self.signer.key,
+                stake_info.stake_amount + amount,
+                current_timestamp,
+            )?;
+        }
+
+        if vault.has_phases() {
+            let vault_key = vault.key();
+            Self::record_phase_stake(
+                vault,
+                stake_info,
+                &self.allowlist_entry,
+                vault_key,
+                self.signer.key(),
+                amount,
+                current_timestamp,
+            )?;
+        }
+
+        stake_info.version = ACCOUNT_VERSION;
         stake_info.unstaked_at_time = current_timestamp + stake_config.lock_period as i64;
         stake_info.stake_amount += amount;
         stake_info.snapshot_amount = stake_info.stake_amount;
 
         vault.total_staked += amount;
         // check reach soft cap. Only update end_time one time
-        if !vault.reach_soft_cap && vault.total_staked >= stake_config.soft_cap {
+        if !vault.reach_soft_cap
+            && Self::soft_cap_reached(
+                stake_config,
+                vault.total_staked,
+                &self.price_feed,
+                self.stake_currency_mint.decimals,
+                current_timestamp,
+            )?
+        {
             vault.end_time = current_timestamp + stake_config.lock_extend_time as i64;
             vault.reach_soft_cap = true;
         }
 
+        emit!(StakeEvent {
+            vault: vault.key(),
+            staker: self.signer.key(),
+            amount,
+            stake_amount: stake_info.stake_amount,
+            vault_total_staked: vault.total_staked,
+            timestamp: current_timestamp,
+        });
+
         // transfer(cpi_ctx, stake_amount)?;
         token_transfer_user(
             self.user_token_account.to_account_info(),
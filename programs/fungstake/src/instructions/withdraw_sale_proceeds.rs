@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use crate::error::ErrorCode;
+use crate::utils::token_transfer_with_signer;
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    StakeConfig, Vault,
+};
+
+#[derive(Accounts)]
+pub struct WithdrawSaleProceeds<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            reward_currency_mint.key().as_ref()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = authority
+    )]
+    pub authority_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    pub reward_currency_mint: Account<'info, Mint>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = associated_token::ID)]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> WithdrawSaleProceeds<'info> {
+    /// Transfer the stake spent by the stakers who claimed so far. Proceeds accrue at claim,
+    /// so the refunds of the others are never withdrawn
+    pub fn process(&mut self) -> Result<()> {
+        let vault = &mut self.vault;
+        let vault_config = &self.stake_config.to_account_info();
+
+        let amount = vault.sale_proceeds - vault.withdrawn_proceeds;
+        if amount == 0 {
+            return Err(ErrorCode::NoTokens.into());
+        }
+        vault.withdrawn_proceeds += amount;
+
+        token_transfer_with_signer(
+            self.vault_token_account.to_account_info(),
+            vault.to_account_info(),
+            self.authority_token_account.to_account_info(),
+            &self.token_program,
+            &[&vault.auth_seeds(&vault_config.key().to_bytes())],
+            amount,
+        )?;
+
+        Ok(())
+    }
+}
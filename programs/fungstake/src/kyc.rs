@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use solana_program::{
+    ed25519_program,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+
+use crate::error::ErrorCode;
+
+pub const KYC_ATTESTATION_LEN: usize = 32 + 32 + 8 + 8;
+
+// ed25519 program instruction data: signature count, padding, then per signature
+// the offsets of signature, public key and message with their instruction indexes
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+/// offsets refer to data of the ed25519 instruction itself
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// message signed by the KYC service for a wallet, valid until expiry
+pub struct KycAttestation {
+    pub stake_config: Pubkey,
+    pub wallet: Pubkey,
+    pub expiry: i64,
+    /// max StakeInfo.stake_amount of the wallet
+    pub max_allocation: u64,
+}
+
+impl KycAttestation {
+    /// stake_config, wallet, expiry and max_allocation, integers little endian
+    pub fn message(&self) -> [u8; KYC_ATTESTATION_LEN] {
+        let mut message = [0; KYC_ATTESTATION_LEN];
+        message[..32].copy_from_slice(self.stake_config.as_ref());
+        message[32..64].copy_from_slice(self.wallet.as_ref());
+        message[64..72].copy_from_slice(&self.expiry.to_le_bytes());
+        message[72..].copy_from_slice(&self.max_allocation.to_le_bytes());
+        message
+    }
+
+    pub fn from_message(message: &[u8]) -> Option<Self> {
+        if message.len() != KYC_ATTESTATION_LEN {
+            return None;
+        }
+        Some(KycAttestation {
+            stake_config: Pubkey::try_from(&message[..32]).ok()?,
+            wallet: Pubkey::try_from(&message[32..64]).ok()?,
+            expiry: i64::from_le_bytes(message[64..72].try_into().ok()?),
+            max_allocation: u64::from_le_bytes(message[72..].try_into().ok()?),
+        })
+    }
+
+    /// Attestation of the ed25519 instruction right before the current one. The ed25519
+    /// program already failed the transaction if the signature is invalid, this checks
+    /// it was made by `attestor` over an attestation
+    pub fn load(instructions_sysvar: &AccountInfo, attestor: &Pubkey) -> Result<Self> {
+        let current_index = load_current_index_checked(instructions_sysvar)?;
+        if current_index == 0 {
+            return Err(ErrorCode::KycRequired.into());
+        }
+        let instruction =
+            load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+        if instruction.program_id != ed25519_program::ID {
+            return Err(ErrorCode::KycRequired.into());
+        }
+
+        let data = &instruction.data;
+        if data.len() < SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN || data[0] != 1 {
+            return Err(ErrorCode::InvalidKycAttestation.into());
+        }
+        let offset = |field: usize| {
+            let start = SIGNATURE_OFFSETS_START + 2 * field;
+            u16::from_le_bytes([data[start], data[start + 1]])
+        };
+        let (signature_index, pubkey_offset, pubkey_index) = (offset(1), offset(2), offset(3));
+        let (message_offset, message_len, message_index) = (offset(4), offset(5), offset(6));
+
+        // the signature, key and message could otherwise be read from other instructions
+        if signature_index != CURRENT_INSTRUCTION
+            || pubkey_index != CURRENT_INSTRUCTION
+            || message_index != CURRENT_INSTRUCTION
+        {
+            return Err(ErrorCode::InvalidKycAttestation.into());
+        }
+
+        let pubkey_offset = pubkey_offset as usize;
+        let pubkey = data.get(pubkey_offset..pubkey_offset + PUBKEY_LEN);
+        if pubkey != Some(attestor.as_ref()) {
+            return Err(ErrorCode::InvalidKycAttestation.into());
+        }
+
+        let message_offset = message_offset as usize;
+        data.get(message_offset..message_offset + message_len as usize)
+            .and_then(Self::from_message)
+            .ok_or(ErrorCode::InvalidKycAttestation.into())
+    }
+
+    /// `stake_amount` is the stake of the wallet after the stake
+    pub fn check(
+        &self,
+        stake_config: &Pubkey,
+        wallet: &Pubkey,
+        stake_amount: u64,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        if self.stake_config != *stake_config || self.wallet != *wallet {
+            return Err(ErrorCode::InvalidKycAttestation.into());
+        }
+        if current_timestamp > self.expiry {
+            return Err(ErrorCode::KycAttestationExpired.into());
+        }
+        if stake_amount > self.max_allocation {
+            return Err(ErrorCode::KycAllocationExceeded.into());
+        }
+
+        Ok(())
+    }
+}
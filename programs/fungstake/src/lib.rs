@@ -1,11 +1,14 @@
 use anchor_lang::prelude::*;
 
-mod constant;
+pub mod constant;
 mod error;
+pub mod event;
 pub mod state;
 pub use state::*;
 pub mod instructions;
 pub use instructions::*;
+pub mod kyc;
+pub mod oracle;
 pub mod utils;
 
 declare_id!("J4Awz2tgfFUqDZorkaT3FMnV5Hy6vh8AbwvAMLNzpKJ1");
@@ -32,8 +35,8 @@ pub mod fungstake {
         ctx.accounts.process(amount)
     }
 
-    pub fn destake(ctx: Context<DeStake>, amount: u64) -> Result<()> {
-        ctx.accounts.process(amount)
+    pub fn destake(ctx: Context<DeStake>, amount: u64, auto_close: bool) -> Result<()> {
+        ctx.accounts.process(amount, auto_close)
     }
 
     pub fn create_vault(ctx: Context<CreateVault>) -> Result<()> {
@@ -43,4 +46,79 @@ pub mod fungstake {
     pub fn claim_reward(ctx: Context<ClaimReward>) -> Result<()> {
         ctx.accounts.process()
     }
+
+    pub fn close_stake_info(ctx: Context<CloseStakeInfo>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn audit_vault(ctx: Context<AuditVault>) -> Result<VaultAuditReport> {
+        ctx.accounts.process()
+    }
+
+    pub fn set_price_feed(
+        ctx: Context<SetPriceFeed>,
+        soft_cap_usd: u64,
+        max_price_age: u32,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .process(soft_cap_usd, max_price_age, max_confidence_bps)
+    }
+
+    pub fn set_phase(
+        ctx: Context<SetPhase>,
+        phase_id: u8,
+        start_time: i64,
+        end_time: i64,
+        wallet_cap: u64,
+        weight: u16,
+        requires_allowlist: bool,
+    ) -> Result<()> {
+        ctx.accounts.process(
+            phase_id,
+            start_time,
+            end_time,
+            wallet_cap,
+            weight,
+            requires_allowlist,
+        )
+    }
+
+    pub fn set_allowlist_entry(
+        ctx: Context<SetAllowlistEntry>,
+        phase_id: u8,
+        wallet: Pubkey,
+        allowed: bool,
+    ) -> Result<()> {
+        ctx.accounts
+            .process(phase_id, wallet, allowed, ctx.bumps.allowlist_entry)
+    }
+
+    pub fn set_kyc_attestor(ctx: Context<SetKycAttestor>, kyc_attestor: Pubkey) -> Result<()> {
+        ctx.accounts.process(kyc_attestor)
+    }
+
+    pub fn set_sale_terms(
+        ctx: Context<SetSaleTerms>,
+        sale_rate: u64,
+        sale_allocation: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(sale_rate, sale_allocation)
+    }
+
+    pub fn withdraw_sale_proceeds(ctx: Context<WithdrawSaleProceeds>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn migrate_stake_config(ctx: Context<MigrateStakeConfig>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn migrate_stake_info(ctx: Context<MigrateStakeInfo>, staker: Pubkey) -> Result<()> {
+        ctx.accounts.process(staker)
+    }
 }
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::constant::constants::{BPS_DENOMINATOR, USD_DECIMALS};
+use crate::error::ErrorCode;
+
+// Pyth v2 price account layout, only the fields used here
+const MAGIC: u32 = 0xa1b2c3d4;
+const VERSION: u32 = 2;
+const ACCOUNT_TYPE_PRICE: u32 = 3;
+const STATUS_TRADING: u32 = 1;
+
+const MAGIC_OFFSET: usize = 0;
+const VERSION_OFFSET: usize = 4;
+const ACCOUNT_TYPE_OFFSET: usize = 8;
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+const MIN_LEN: usize = 240;
+
+/// aggregate price of a Pyth price account, `price * 10^expo` USD per token
+pub struct PriceFeed {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub status: u32,
+}
+
+fn read<const N: usize>(data: &[u8], offset: usize) -> [u8; N] {
+    data[offset..offset + N].try_into().unwrap()
+}
+
+impl PriceFeed {
+    pub fn load(account: &AccountInfo) -> Result<Self> {
+        let data = account.try_borrow_data()?;
+        if data.len() < MIN_LEN
+            || u32::from_le_bytes(read(&data, MAGIC_OFFSET)) != MAGIC
+            || u32::from_le_bytes(read(&data, VERSION_OFFSET)) != VERSION
+            || u32::from_le_bytes(read(&data, ACCOUNT_TYPE_OFFSET)) != ACCOUNT_TYPE_PRICE
+        {
+            return Err(ErrorCode::InvalidPriceFeed.into());
+        }
+
+        Ok(PriceFeed {
+            price: i64::from_le_bytes(read(&data, AGG_PRICE_OFFSET)),
+            conf: u64::from_le_bytes(read(&data, AGG_CONF_OFFSET)),
+            expo: i32::from_le_bytes(read(&data, EXPO_OFFSET)),
+            publish_time: i64::from_le_bytes(read(&data, TIMESTAMP_OFFSET)),
+            status: u32::from_le_bytes(read(&data, AGG_STATUS_OFFSET)),
+        })
+    }
+
+    /// The price must be trading, published at most max_age seconds ago and
+    /// have a confidence interval within max_confidence_bps of the price
+    pub fn check(
+        &self,
+        current_timestamp: i64,
+        max_age: u32,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        if self.status != STATUS_TRADING || self.price <= 0 {
+            return Err(ErrorCode::PriceUnavailable.into());
+        }
+        if current_timestamp.saturating_sub(self.publish_time) > max_age as i64 {
+            return Err(ErrorCode::PriceStale.into());
+        }
+        if self.conf as u128 * BPS_DENOMINATOR as u128
+            > self.price as u128 * max_confidence_bps as u128
+        {
+            return Err(ErrorCode::PriceConfidenceTooWide.into());
+        }
+
+        Ok(())
+    }
+
+    /// value of `amount` base units of a mint with `decimals`, in USD with USD_DECIMALS.
+    /// Saturates at u64::MAX, which is above any soft cap
+    pub fn usd_value(&self, amount: u64, decimals: u8) -> u64 {
+        let value = amount as u128 * self.price.max(0) as u128;
+        let exponent = USD_DECIMALS as i32 + self.expo - decimals as i32;
+        let value = if exponent >= 0 {
+            10u128
+                .checked_pow(exponent as u32)
+                .and_then(|scale| value.checked_mul(scale))
+                .unwrap_or(u128::MAX)
+        } else {
+            10u128
+                .checked_pow(exponent.unsigned_abs())
+                .map_or(0, |scale| value / scale)
+        };
+        u64::try_from(value).unwrap_or(u64::MAX)
+    }
+}
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+pub const ALLOWLIST_ENTRY_SIZE: usize = 8 + 1 + 32 + 32 + 1 + 1;
+
+#[account]
+pub struct AllowlistEntry {
+    /// Bump seed used to generate the program address / authority
+    pub bump: [u8; 1],
+    pub vault: Pubkey,
+    pub wallet: Pubkey,
+    /// index of the phase in Vault.phases
+    pub phase_id: u8,
+    pub allowed: bool,
+}
@@ -1,7 +1,9 @@
+mod allowlist_entry;
 mod stake_config;
 mod stake_info;
 mod vault;
 
+pub use allowlist_entry::*;
 pub use stake_config::*;
 pub use stake_info::*;
 pub use vault::*;
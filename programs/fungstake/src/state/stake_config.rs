@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::constant::constants;
 
-pub const STAKE_CONFIG_SIZE: usize = 8 + 1 + 1 + 32 + 32 + 4 + 4 + 8;
+pub const STAKE_CONFIG_SIZE: usize = 8 + 1 + 1 + 32 + 32 + 4 + 4 + 8 + 32 + 8 + 4 + 2 + 32;
 
 #[account]
 pub struct StakeConfig {
@@ -19,13 +19,31 @@ pub struct StakeConfig {
     pub lock_extend_time: u32,
     /// soft cap for token launch
     pub soft_cap: u64,
+    /// Pyth price account of the stake token in USD, default when soft_cap is used instead
+    pub price_feed: Pubkey,
+    /// soft cap in USD with USD_DECIMALS, used when price_feed is set
+    pub soft_cap_usd: u64,
+    /// max seconds since the price was published
+    pub max_price_age: u32,
+    /// max confidence interval relative to the price, in basis points
+    pub max_confidence_bps: u16,
+    /// key of the KYC service signing stake attestations, default when none are required
+    pub kyc_attestor: Pubkey,
 }
 
 impl StakeConfig {
+    pub fn has_price_feed(&self) -> bool {
+        self.price_feed != Pubkey::default()
+    }
+
+    pub fn has_kyc_attestor(&self) -> bool {
+        self.kyc_attestor != Pubkey::default()
+    }
+
     /// Seeds are unique to authority/pyth feed/currency mint combinations
-    pub fn auth_seeds<'a>(&'a self) -> [&'a [u8]; 4] {
+    pub fn auth_seeds(&self) -> [&[u8]; 4] {
         [
-            constants::STAKE_CONFIG_SEED.as_ref(),
+            constants::STAKE_CONFIG_SEED,
             self.authority.as_ref(),
             self.stake_currency_mint.as_ref(),
             self.bump.as_ref(),
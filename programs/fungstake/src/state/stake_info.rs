@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
 
-pub const STAKE_INFO_SIZE: usize = 8 + 1 + 8 + 8 + 8 + 1;
+use crate::MAX_PHASES;
+
+pub const STAKE_INFO_SIZE: usize = 8 + 1 + 8 + 8 + 8 + 1 + 8 * MAX_PHASES + 1;
 
 #[account]
 pub struct StakeInfo {
@@ -11,4 +13,15 @@ pub struct StakeInfo {
     pub snapshot_amount: u64,
     /// check if user has claimed the rewards
     pub has_claimed: bool,
+    /// part of snapshot_amount staked in each phase of the vault
+    pub phase_snapshot_amounts: [u64; MAX_PHASES],
+    /// layout version, see ACCOUNT_VERSION
+    pub version: u8,
+}
+
+impl StakeInfo {
+    /// nothing left to destake and no reward left to claim
+    pub fn is_drained(&self) -> bool {
+        self.stake_amount == 0 && (self.snapshot_amount == 0 || self.has_claimed)
+    }
 }
@@ -1,8 +1,31 @@
 use anchor_lang::prelude::*;
 
-use crate::constant::constants;
+use crate::constant::constants::{self, SALE_RATE_PRECISION, SALE_REFUND_DELAY};
+use crate::error::ErrorCode;
+use crate::StakeInfo;
 
-pub const VAULT_SIZE: usize = 8 + 1 + 1 + 32 + 8 + 8 + 1 + 8 + 1;
+/// number of launch phases a vault can have, e.g. guaranteed, FCFS and public rounds
+pub const MAX_PHASES: usize = 4;
+pub const PHASE_SIZE: usize = 8 + 8 + 8 + 2 + 1 + 8 + 8;
+pub const VAULT_SIZE: usize =
+    8 + 1 + 1 + 32 + 8 + 8 + 1 + 8 + 1 + 8 + 8 + 1 + PHASE_SIZE * MAX_PHASES + 8 + 8 + 8 + 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct Phase {
+    /// staking is open from start_time until end_time, excluded
+    pub start_time: i64,
+    pub end_time: i64,
+    /// max snapshot amount of a wallet in this phase, 0 for no cap
+    pub wallet_cap: u64,
+    /// share of total_reward, relative to the weights of the other phases with stake
+    pub weight: u16,
+    /// only wallets with an allowed AllowlistEntry can stake
+    pub requires_allowlist: bool,
+    /// sum of the phase snapshot amounts of stake infos
+    pub total_staked: u64,
+    /// phase snapshot amounts of stake infos which claimed their reward
+    pub claimed_snapshot_amount: u64,
+}
 
 #[account]
 pub struct Vault {
@@ -20,9 +43,102 @@ pub struct Vault {
     /// total reward
     pub total_reward: u64,
     pub reach_tge: bool,
+    /// sum of snapshot_amount of stake infos which claimed their reward
+    pub claimed_snapshot_amount: u64,
+    /// tokens destaked once end_time is set, total_staked keeps them for reward shares
+    pub destaked_after_end: u64,
+    /// number of configured phases, 0 for a single open round
+    pub phase_count: u8,
+    /// phases ordered by time, their windows do not overlap
+    pub phases: [Phase; MAX_PHASES],
+    /// reward tokens per staking token scaled by SALE_RATE_PRECISION, 0 to share the reward pro-rata
+    pub sale_rate: u64,
+    /// reward tokens for sale at sale_rate
+    pub sale_allocation: u64,
+    /// staking tokens spent by the stakers who claimed, owed to the authority
+    pub sale_proceeds: u64,
+    /// sale_proceeds already withdrawn by the authority
+    pub withdrawn_proceeds: u64,
 }
 
 impl Vault {
+    pub fn has_phases(&self) -> bool {
+        self.phase_count > 0
+    }
+
+    /// rewards are sold at a fixed rate, unspent stake is refunded at claim
+    pub fn is_sale(&self) -> bool {
+        self.sale_rate > 0
+    }
+
+    /// the sold reward was not funded, so nobody claimed, by SALE_REFUND_DELAY after end_time.
+    /// Stakers get their whole stake back through destake and claims are rejected
+    pub fn is_void_sale(&self, current_timestamp: i64) -> bool {
+        self.is_sale()
+            && self.end_time > 0
+            && !self.reach_tge
+            && current_timestamp > self.end_time + SALE_REFUND_DELAY
+    }
+
+    /// phases share total_reward by weight, a sale pays every staker at the same rate
+    pub fn has_phase_pools(&self) -> bool {
+        self.has_phases() && !self.is_sale()
+    }
+
+    /// staking tokens spent on the sale, at most the stake buying the whole allocation
+    pub fn sold_stake(&self) -> u64 {
+        let allocation_stake = (self.sale_allocation as u128) * (SALE_RATE_PRECISION as u128)
+            / (self.sale_rate as u128);
+        std::cmp::min(self.total_staked as u128, allocation_stake) as u64
+    }
+
+    /// reward tokens sold, the whole allocation once the sale is oversubscribed
+    pub fn sold_reward(&self) -> u64 {
+        let reward =
+            (self.total_staked as u128) * (self.sale_rate as u128) / (SALE_RATE_PRECISION as u128);
+        std::cmp::min(reward, self.sale_allocation as u128) as u64
+    }
+
+    /// index of the phase open for staking at `current_timestamp`
+    pub fn active_phase(&self, current_timestamp: i64) -> Option<usize> {
+        self.phases[..self.phase_count as usize]
+            .iter()
+            .position(|phase| {
+                phase.start_time <= current_timestamp && current_timestamp < phase.end_time
+            })
+    }
+
+    /// share of total_reward paid to the stakers of phase `index`.
+    /// Phases nobody staked in are left out so that no reward is stranded
+    pub fn phase_reward_pool(&self, index: usize) -> Result<u64> {
+        let phases = &self.phases[..self.phase_count as usize];
+        if phases[index].total_staked == 0 {
+            return Ok(0);
+        }
+
+        let total_weight: u64 = phases
+            .iter()
+            .filter(|phase| phase.total_staked > 0)
+            .map(|phase| phase.weight as u64)
+            .sum();
+        Ok(
+            ((self.total_reward as u128) * (phases[index].weight as u128))
+                .checked_div(total_weight as u128)
+                .ok_or(ErrorCode::OverflowError)? as u64,
+        )
+    }
+
+    /// remove `amount` destaked before end_time from the phases of `stake_info`, latest phase first
+    pub fn release_phase_stake(&mut self, stake_info: &mut StakeInfo, amount: u64) {
+        let mut remaining = amount;
+        for index in (0..self.phase_count as usize).rev() {
+            let released = std::cmp::min(remaining, stake_info.phase_snapshot_amounts[index]);
+            stake_info.phase_snapshot_amounts[index] -= released;
+            self.phases[index].total_staked -= released;
+            remaining -= released;
+        }
+    }
+
     /// Seeds are unique to authority/pyth feed/currency mint combinations
     pub fn auth_seeds<'a>(&'a self, vault_config: &'a [u8]) -> [&'a [u8]; 4] {
         [
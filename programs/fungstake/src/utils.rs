@@ -90,3 +90,30 @@ pub fn token_transfer_with_signer<'info>(
 
     Ok(())
 }
+
+// grow an account created with an older layout of T to `size`, the payer tops up the rent.
+// Appended fields start zeroed, `migrate` sets the ones with another default
+pub fn migrate_account<'info, T: AccountSerialize + AccountDeserialize>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    size: usize,
+    migrate: impl FnOnce(&mut T) -> Result<()>,
+) -> Result<()> {
+    if account.data_len() >= size {
+        return Err(error::ErrorCode::AccountAlreadyMigrated.into());
+    }
+
+    let rent = Rent::get()?
+        .minimum_balance(size)
+        .saturating_sub(account.lamports());
+    if rent > 0 {
+        sol_transfer_from_user(payer, account.clone(), system_program, rent)?;
+    }
+    account.realloc(size, true)?;
+
+    // the discriminator is checked here, so only accounts of type T are migrated
+    let mut data = T::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    migrate(&mut data)?;
+    data.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])
+}
@@ -3,4 +3,47 @@ pub mod constants {
     pub const STAKE_CONFIG_SEED: &[u8] = b"staking_config";
     pub const STAKER_INFO_SEED: &[u8] = b"staker_info";
     pub const STAKE_DETAIL_SEED: &[u8] = b"stake_detail";
+    pub const POSITION_MINT_SEED: &[u8] = b"position_mint";
+    pub const POSITION_RECEIPT_SEED: &[u8] = b"position_receipt";
+    pub const OPERATOR_SEED: &[u8] = b"operator";
+    pub const VOTER_WEIGHT_RECORD_SEED: &[u8] = b"voter_weight_record";
+    pub const MAX_VOTER_WEIGHT_RECORD_SEED: &[u8] = b"max_voter_weight_record";
+
+    /// layout version of StakeConfig, Vault, StakerInfo and StakeDetail. Accounts created
+    /// before the layouts grew are version 1, or have no version field, and fail to
+    /// deserialize in every instruction until migrated, in this order: migrate_stake_config
+    /// and migrate_vault by the authority, then migrate_staker_info and migrate_stake_detail
+    /// of each legacy position (ids 1..=current_id), which anyone can pay for
+    pub const ACCOUNT_VERSION: u8 = 2;
+
+    /// lock period bounds of a new stake config, in seconds
+    pub const DEFAULT_MIN_LOCK_PERIOD: u64 = 1;
+    pub const DEFAULT_MAX_LOCK_PERIOD: u64 = 5 * 365 * 24 * 60 * 60;
+
+    /// denominator for basis point values
+    pub const BPS_DENOMINATOR: u64 = 10_000;
+    /// scale factor of `Vault.acc_penalty_per_share`
+    pub const PENALTY_PRECISION: u128 = 1_000_000_000_000;
+    /// bound of `Vault.acc_penalty_per_share` under which `amount * acc_penalty_per_share`
+    /// cannot overflow for any u64 amount
+    pub const MAX_ACC_PENALTY_PER_SHARE: u128 = u128::MAX / u64::MAX as u128;
+    /// scale factor of voting power slopes and biases
+    pub const VOTING_POWER_PRECISION: u128 = 1_000_000_000_000;
+    /// voting power of non auto-renewed positions ends at the epoch boundary from their
+    /// unlock, in seconds
+    pub const VE_EPOCH: i64 = 28 * 24 * 60 * 60;
+    /// epochs ahead tracked by `StakeConfig.ve_slope_changes`, bounds the max lock period
+    pub const VE_SLOPE_CHANGES: usize = 66;
+
+    /// metaplex metadata of position receipts
+    pub const RECEIPT_NAME_PREFIX: &str = "Position #";
+    pub const RECEIPT_SYMBOL: &str = "STAKEPOS";
+    pub const MAX_RECEIPT_URI_LEN: usize = 120;
+
+    /// spl-governance account types (first byte of the account data) and the proposal
+    /// state a vote can be cast in, see spl_governance::state::enums
+    pub const GOVERNANCE_REALM_TYPES: [u8; 2] = [1, 16];
+    pub const GOVERNANCE_TYPES: [u8; 8] = [3, 4, 9, 10, 18, 19, 20, 21];
+    pub const GOVERNANCE_PROPOSAL_TYPES: [u8; 2] = [5, 14];
+    pub const PROPOSAL_STATE_VOTING: u8 = 2;
 }
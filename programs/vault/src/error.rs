@@ -26,4 +26,82 @@ pub enum ErrorCode {
     IncorrectStakeDetailId,
     #[msg("Incorrect Lock Period")]
     IncorrectLockPeriod,
+    #[msg("Early unstake is not allowed for this vault")]
+    EarlyUnstakeNotAllowed,
+    #[msg("Penalty basis points must not exceed 10000")]
+    InvalidPenaltyBps,
+    #[msg("Treasury token account is required")]
+    TreasuryTokenAccountRequired,
+    #[msg("Stake detail account does not match the given id")]
+    InvalidStakeDetailAccount,
+    #[msg("Stake detail is listed more than once")]
+    DuplicateStakeDetail,
+    #[msg("Split amount must be positive and less than the stake amount")]
+    InvalidSplitAmount,
+    #[msg("Recipient must be a different wallet")]
+    InvalidRecipient,
+    #[msg("Position is tokenized, use destake_with_receipt")]
+    PositionTokenized,
+    #[msg("Position still holds staked tokens")]
+    PositionNotEmpty,
+    #[msg("Vault capacity exceeded")]
+    VaultCapacityExceeded,
+    #[msg("Staker cap exceeded")]
+    StakerCapExceeded,
+    #[msg("Stake amount is below the vault minimum")]
+    StakeAmountTooLow,
+    #[msg("Vault is not accepting new stakes")]
+    VaultInactive,
+    #[msg("Vault still holds staked tokens")]
+    VaultNotEmpty,
+    #[msg("Vault has an unbonding period, use request_unstake and withdraw")]
+    UnbondingRequired,
+    #[msg("Vault has no unbonding period")]
+    UnbondingNotEnabled,
+    #[msg("Position is unbonding")]
+    PositionUnbonding,
+    #[msg("Position is not unbonding")]
+    PositionNotUnbonding,
+    #[msg("Renew window must be shorter than the lock period")]
+    InvalidRenewWindow,
+    #[msg("Vault does not support auto-renew")]
+    AutoRenewNotEnabled,
+    #[msg("Position is auto-renewed")]
+    PositionAutoRenew,
+    #[msg("Position is a revocable grant")]
+    PositionRevocable,
+    #[msg("Grant is not revocable")]
+    GrantNotRevocable,
+    #[msg("Vault does not belong to this stake config")]
+    InvalidVaultAccount,
+    #[msg("TWAB start must be before end")]
+    InvalidTwabRange,
+    #[msg("Balance checkpoints do not go back that far")]
+    TwabHistoryUnavailable,
+    #[msg("Commission cannot exceed 10000 bps")]
+    InvalidCommissionBps,
+    #[msg("Operator is inactive")]
+    OperatorInactive,
+    #[msg("Position is delegated")]
+    PositionDelegated,
+    #[msg("Position is not delegated")]
+    PositionNotDelegated,
+    #[msg("Operator account of the current delegation is required")]
+    OperatorAccountRequired,
+    #[msg("Operator does not match the delegation")]
+    InvalidOperator,
+    #[msg("Vault token balance does not cover its liabilities")]
+    SolvencyViolated,
+    #[msg("Receipt uri is too long")]
+    InvalidReceiptUri,
+    #[msg("Stake detail accounts must be closed first")]
+    OpenPositionsRemaining,
+    #[msg("Position is locked by a vote")]
+    PositionVoteLocked,
+    #[msg("Account already has the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Realm, governance and proposal accounts are required to cast a vote")]
+    ProposalRequired,
+    #[msg("Proposal is not voting in this realm on the stake currency")]
+    InvalidProposal,
 }
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::{StakeDetail, Vault};
+
+#[event]
+pub struct EarlyDeStakeEvent {
+    pub vault: Pubkey,
+    pub staker: Pubkey,
+    pub stake_detail_id: u64,
+    /// amount sent back to the staker, excluding redistributed penalty share
+    pub unstake_amount: u64,
+    /// amount forfeited as penalty
+    pub penalty_amount: u64,
+    /// true if penalty was shared among remaining stakers, false if sent to treasury
+    pub redistributed: bool,
+    pub timestamp: i64,
+}
+
+/// emitted whenever the stake amount or unbonding state of a position changes.
+/// It carries the resulting state, so replaying it is idempotent
+#[event]
+pub struct PositionUpdateEvent {
+    pub vault: Pubkey,
+    pub staker: Pubkey,
+    pub stake_detail_id: u64,
+    /// stake amount after the update, 0 once destaked or moved to another position
+    pub stake_amount: u64,
+    /// waiting for withdraw after request_unstake
+    pub unbonding: bool,
+    /// Vault.total_staked after the update
+    pub vault_total_staked: u64,
+    pub timestamp: i64,
+}
+
+pub fn emit_position_update(vault: &Account<Vault>, stake_detail: &StakeDetail, timestamp: i64) {
+    emit!(PositionUpdateEvent {
+        vault: vault.key(),
+        staker: stake_detail.staker,
+        stake_detail_id: stake_detail.id,
+        stake_amount: stake_detail.stake_amount,
+        unbonding: stake_detail.unbonding_started_at != 0,
+        vault_total_staked: vault.total_staked,
+        timestamp,
+    });
+}
+
+/// position closed after its stake moved to another position by merge or transfer
+pub fn emit_position_moved(vault: &Account<Vault>, stake_detail: &StakeDetail, timestamp: i64) {
+    emit!(PositionUpdateEvent {
+        vault: vault.key(),
+        staker: stake_detail.staker,
+        stake_detail_id: stake_detail.id,
+        stake_amount: 0,
+        unbonding: false,
+        vault_total_staked: vault.total_staked,
+        timestamp,
+    });
+}
@@ -0,0 +1,78 @@
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    StakeConfig, Vault,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::error::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VaultAuditReport {
+    /// tokens held by the vault token account
+    pub token_balance: u64,
+    pub total_staked: u64,
+    pub total_unbonding: u64,
+    pub undistributed_penalty: u64,
+    /// balance above staked and unbonding principal and undistributed penalty
+    pub surplus: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64)]
+pub struct AuditVault<'info> {
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub stake_currency_mint: Box<Account<'info, Mint>>,
+}
+
+impl<'info> AuditVault<'info> {
+    /// permissionless solvency check, meant to be simulated by monitoring.
+    /// The report is returned as return data, or logged before failing with SolvencyViolated
+    pub fn process(&self, _: u64) -> Result<VaultAuditReport> {
+        let vault = &self.vault;
+        let token_balance = self.vault_token_account.amount;
+        let liabilities = (vault.total_staked as u128)
+            + (vault.total_unbonding as u128)
+            + (vault.undistributed_penalty as u128);
+
+        if (token_balance as u128) < liabilities {
+            msg!(
+                "solvency violated: token_balance {} total_staked {} total_unbonding {} undistributed_penalty {}",
+                token_balance,
+                vault.total_staked,
+                vault.total_unbonding,
+                vault.undistributed_penalty
+            );
+            return Err(ErrorCode::SolvencyViolated.into());
+        }
+
+        Ok(VaultAuditReport {
+            token_balance,
+            total_staked: vault.total_staked,
+            total_unbonding: vault.total_unbonding,
+            undistributed_penalty: vault.undistributed_penalty,
+            surplus: ((token_balance as u128) - liabilities) as u64,
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{
+    constant::constants::{STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED},
+    state::StakerInfo,
+    StakeConfig, StakeDetail,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(id: u64, lock_period: u64)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    /// CHECK: only its address is used, so positions can still be closed after close_vault
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    /// rent is returned to signer
+    #[account(
+        mut,
+        close = signer,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        constraint = !stake_detail.is_tokenized() @ErrorCode::PositionTokenized,
+        constraint = stake_detail.stake_amount == 0 @ErrorCode::PositionNotEmpty,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> ClosePosition<'info> {
+    pub fn process(&mut self, _: u64, _: u64) -> Result<()> {
+        self.staker_info.open_positions -= 1;
+
+        Ok(())
+    }
+}
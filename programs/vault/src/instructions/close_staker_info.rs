@@ -0,0 +1,52 @@
+use crate::{
+    constant::constants::{STAKER_INFO_SEED, STAKE_CONFIG_SEED, VAULT_SEED},
+    state::StakerInfo,
+    StakeConfig,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64)]
+pub struct CloseStakerInfo<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    /// CHECK: only its address is used, so staker infos can still be closed after close_vault
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: stake detail ids restart from 1 when staker info is re-created,
+    /// so every stake detail must be closed first
+    #[account(
+        mut,
+        close = signer,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+        constraint = staker_info.open_positions == 0 @ErrorCode::OpenPositionsRemaining,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> CloseStakerInfo<'info> {
+    pub fn process(&mut self, _: u64) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    utils::token_transfer_with_signer,
+    StakeConfig, Vault,
+};
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64)]
+pub struct CloseVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: must match authority of stake_config
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    /// rent is returned to authority
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+        constraint = vault.total_staked == 0 @ErrorCode::VaultNotEmpty,
+        constraint = vault.total_unbonding == 0 @ErrorCode::VaultNotEmpty,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: receives rounding dust left in vault_token_account
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = authority,
+    )]
+    pub authority_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    #[account(address = associated_token::ID)]
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> CloseVault<'info> {
+    /// drained positions and staker infos left behind are closed by their owners with
+    /// close_position and close_staker_info, which only check the vault address
+    pub fn process(&mut self, _: u64) -> Result<()> {
+        let vault = &self.vault;
+        let lock_period_bytes = vault.lock_period.to_le_bytes();
+        let vault_seeds = vault.auth_seeds(&lock_period_bytes);
+
+        if self.vault_token_account.amount > 0 {
+            token_transfer_with_signer(
+                self.vault_token_account.to_account_info(),
+                vault.to_account_info(),
+                self.authority_token_account.to_account_info(),
+                &self.token_program,
+                &[&vault_seeds],
+                self.vault_token_account.amount,
+            )?;
+        }
+
+        token::close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            token::CloseAccount {
+                account: self.vault_token_account.to_account_info(),
+                destination: self.authority.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            &[&vault_seeds],
+        ))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    constant::constants::{MAX_VOTER_WEIGHT_RECORD_SEED, STAKE_CONFIG_SEED},
+    MaxVoterWeightRecord, StakeConfig, MAX_VOTER_WEIGHT_RECORD_SIZE,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+#[instruction(realm: Pubkey)]
+pub struct CreateMaxVoterWeightRecord<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        init,
+        seeds = [
+            MAX_VOTER_WEIGHT_RECORD_SEED,
+            realm.as_ref(),
+            stake_currency_mint.key().as_ref()
+        ],
+        bump,
+        payer = signer,
+        space = MAX_VOTER_WEIGHT_RECORD_SIZE,
+    )]
+    pub max_voter_weight_record: Box<Account<'info, MaxVoterWeightRecord>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateMaxVoterWeightRecord<'info> {
+    pub fn process(&mut self, realm: Pubkey) -> Result<()> {
+        let max_voter_weight_record = &mut self.max_voter_weight_record;
+        max_voter_weight_record.realm = realm;
+        max_voter_weight_record.governing_token_mint = self.stake_currency_mint.key();
+        // expired until the first update
+        max_voter_weight_record.max_voter_weight_expiry = Some(0);
+
+        Ok(())
+    }
+}
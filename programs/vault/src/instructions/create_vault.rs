@@ -6,7 +6,7 @@ use anchor_spl::{
 
 use crate::error::ErrorCode;
 use crate::{
-    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    constant::constants::{ACCOUNT_VERSION, STAKE_CONFIG_SEED, VAULT_SEED},
     StakeConfig, Vault, VAULT_SIZE,
 };
 use solana_program::sysvar::SysvarId;
@@ -62,12 +62,19 @@ pub struct CreateVault<'info> {
 
 impl<'info> CreateVault<'info> {
     pub fn process(&mut self, lock_period: u64, vault_bump: u8) -> Result<()> {
+        let stake_config = &self.stake_config;
+        if lock_period < stake_config.min_lock_period || lock_period > stake_config.max_lock_period
+        {
+            return Err(ErrorCode::IncorrectLockPeriod.into());
+        }
+
         let vault = &mut self.vault;
         vault.bump = [vault_bump];
-        vault.version = 1;
+        vault.version = ACCOUNT_VERSION;
         vault.vault_config = self.stake_config.key();
         vault.total_staked = 0;
         vault.lock_period = lock_period;
+        vault.is_active = true;
 
         Ok(())
     }
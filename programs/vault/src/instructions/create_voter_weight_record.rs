@@ -0,0 +1,52 @@
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, VOTER_WEIGHT_RECORD_SEED},
+    StakeConfig, VoterWeightRecord, VOTER_WEIGHT_RECORD_SIZE,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+#[instruction(realm: Pubkey)]
+pub struct CreateVoterWeightRecord<'info> {
+    /// governing token owner of the record
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        init,
+        seeds = [
+            VOTER_WEIGHT_RECORD_SEED,
+            realm.as_ref(),
+            stake_currency_mint.key().as_ref(),
+            signer.key.as_ref()
+        ],
+        bump,
+        payer = signer,
+        space = VOTER_WEIGHT_RECORD_SIZE,
+    )]
+    pub voter_weight_record: Box<Account<'info, VoterWeightRecord>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateVoterWeightRecord<'info> {
+    pub fn process(&mut self, realm: Pubkey) -> Result<()> {
+        let voter_weight_record = &mut self.voter_weight_record;
+        voter_weight_record.realm = realm;
+        voter_weight_record.governing_token_mint = self.stake_currency_mint.key();
+        voter_weight_record.governing_token_owner = self.signer.key();
+        // expired until the first update
+        voter_weight_record.voter_weight_expiry = Some(0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    constant::constants::{OPERATOR_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED},
+    state::StakerInfo,
+    utils::token_transfer_with_signer,
+    Operator, StakeConfig, StakeDetail, Vault,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use solana_program::clock::Clock;
+
+use crate::constant::constants::{STAKER_INFO_SEED, VAULT_SEED};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(id: u64, lock_period: u64)]
+pub struct Delegate<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        constraint = !stake_detail.is_tokenized() @ErrorCode::PositionTokenized,
+        constraint = !stake_detail.is_unbonding() @ErrorCode::PositionUnbonding,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    /// new operator
+    #[account(
+        mut,
+        seeds = [OPERATOR_SEED, stake_config.key().as_ref(), operator.operator.as_ref()],
+        bump,
+        constraint = operator.is_active @ErrorCode::OperatorInactive,
+    )]
+    pub operator: Box<Account<'info, Operator>>,
+
+    /// CHECK: only required when redelegating
+    #[account(mut)]
+    pub previous_operator: Option<Box<Account<'info, Operator>>>,
+
+    /// CHECK: only required when redelegating, receives the commission of previous_operator
+    #[account(
+        mut,
+        token::mint = stake_currency_mint,
+    )]
+    pub previous_operator_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Delegate<'info> {
+    /// delegate or redelegate a position, staked tokens stay in the vault
+    pub fn process(&mut self, _: u64, _: u64) -> Result<()> {
+        let vault = &mut self.vault;
+        let stake_detail = &mut self.stake_detail;
+        let operator = &mut self.operator;
+
+        if stake_detail.stake_amount == 0 {
+            return Err(ErrorCode::NotStaked.into());
+        }
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        if stake_detail.is_vote_locked(current_timestamp) {
+            return Err(ErrorCode::PositionVoteLocked.into());
+        }
+        if stake_detail.is_revocable_grant(current_timestamp) {
+            return Err(ErrorCode::PositionRevocable.into());
+        }
+
+        if stake_detail.is_delegated() {
+            let previous_operator = self
+                .previous_operator
+                .as_mut()
+                .ok_or(ErrorCode::OperatorAccountRequired)?;
+            let previous_operator_token_account = self
+                .previous_operator_token_account
+                .as_ref()
+                .ok_or(ErrorCode::OperatorAccountRequired)?;
+            if previous_operator.stake_config != self.stake_config.key()
+                || previous_operator.operator != stake_detail.delegate_to
+                || previous_operator_token_account.owner != stake_detail.delegate_to
+            {
+                return Err(ErrorCode::InvalidOperator.into());
+            }
+
+            // previous operator is paid for the time it was delegated to
+            let commission = stake_detail.settle_delegation(vault);
+            vault.pay_penalty_share(commission);
+            if commission > 0 {
+                token_transfer_with_signer(
+                    self.vault_token_account.to_account_info(),
+                    vault.to_account_info(),
+                    previous_operator_token_account.to_account_info(),
+                    &self.token_program,
+                    &[&vault.auth_seeds(&vault.lock_period.to_le_bytes())],
+                    commission,
+                )?;
+            }
+
+            // redelegating to the same operator keeps one entry
+            if previous_operator.key() == operator.key() {
+                operator.delegated_stake -= stake_detail.stake_amount;
+            } else {
+                previous_operator.delegated_stake -= stake_detail.stake_amount;
+            }
+        }
+
+        // update stake detail
+        stake_detail.delegate_to = operator.operator;
+        stake_detail.delegation_commission_bps = operator.commission_bps;
+        stake_detail.delegation_acc_penalty_per_share = vault.acc_penalty_per_share;
+
+        // update operator
+        operator.delegated_stake += stake_detail.stake_amount;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{
     constant::constants::{STAKE_CONFIG_SEED, STAKE_DETAIL_SEED},
+    event::emit_position_update,
     state::StakerInfo,
     utils::token_transfer_with_signer,
     StakeConfig, StakeDetail, Vault,
 };
-use anchor_lang::{prelude::*, system_program};
+use anchor_lang::{prelude::*, system_program, AccountsClose};
 use anchor_spl::{
     associated_token::{self, AssociatedToken},
     token::{self, Mint, Token, TokenAccount},
@@ -21,6 +22,7 @@ pub struct DeStake<'info> {
     pub signer: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
         bump,
     )]
@@ -55,6 +57,9 @@ pub struct DeStake<'info> {
        mut,
         seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
         bump,
+        constraint = !stake_detail.is_tokenized() @ErrorCode::PositionTokenized,
+        constraint = !stake_detail.is_unbonding() @ErrorCode::PositionUnbonding,
+        constraint = !stake_detail.is_delegated() @ErrorCode::PositionDelegated,
     )]
     pub stake_detail: Box<Account<'info, StakeDetail>>,
 
@@ -76,31 +81,49 @@ pub struct DeStake<'info> {
 }
 
 impl<'info> DeStake<'info> {
-    pub fn process(&mut self, _: u64, _: u64, amount: u64) -> Result<()> {
+    pub fn process(&mut self, _: u64, _: u64, amount: u64, auto_close: bool) -> Result<()> {
         let staker_info = &mut self.staker_info;
         let vault = &mut self.vault;
         let stake_detail = &mut self.stake_detail;
+        let stake_config = &mut self.stake_config;
 
         if stake_detail.stake_amount == 0 {
             return Err(ErrorCode::NotStaked.into());
         }
 
+        if vault.unbonding_period > 0 {
+            return Err(ErrorCode::UnbondingRequired.into());
+        }
+
         let current_timestamp = Clock::get()?.unix_timestamp;
-        if current_timestamp < stake_detail.unstaked_at_time {
+        if stake_detail.is_vote_locked(current_timestamp) {
+            return Err(ErrorCode::PositionVoteLocked.into());
+        }
+        if current_timestamp < stake_detail.unlock_time(vault, current_timestamp) {
             return Err(ErrorCode::UnbondingTimeNotOverYet.into());
         }
 
         // eg: stake amount = 9, amount = 10 -> unstake_amount = 9
         let unstake_amount = std::cmp::min(stake_detail.stake_amount, amount);
 
+        // share of penalties paid by early unstakers
+        let penalty_share =
+            vault.pending_penalty_share(stake_detail.stake_amount, stake_detail.penalty_debt)?;
+        vault.pay_penalty_share(penalty_share);
+
         // update stake detail
+        stake_config.remove_voting_power(stake_detail, vault.lock_period, current_timestamp);
         stake_detail.stake_amount -= unstake_amount;
+        stake_detail.penalty_debt = vault.penalty_debt(stake_detail.stake_amount)?;
+        stake_config.add_voting_power(stake_detail, vault.lock_period, current_timestamp);
 
         // update staker info
         staker_info.total_stake -= unstake_amount;
+        staker_info.write_checkpoint(current_timestamp);
 
         // update vault
         vault.total_staked -= unstake_amount;
+        emit_position_update(vault, stake_detail, current_timestamp);
 
         // transfer to user
         token_transfer_with_signer(
@@ -109,9 +132,15 @@ impl<'info> DeStake<'info> {
             self.staker_token_account.to_account_info(),
             &self.token_program,
             &[&vault.auth_seeds(&vault.lock_period.to_le_bytes())],
-            unstake_amount,
+            unstake_amount + penalty_share,
         )?;
 
+        // return rent of drained stake detail to signer, same as close_position
+        if auto_close && stake_detail.stake_amount == 0 {
+            staker_info.open_positions -= 1;
+            stake_detail.close(self.signer.to_account_info())?;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,160 @@
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, STAKE_DETAIL_SEED},
+    event::emit_position_update,
+    state::StakerInfo,
+    utils::token_transfer_with_signer,
+    StakeConfig, StakeDetail, Vault,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+use solana_program::clock::Clock;
+
+use crate::constant::constants::{STAKER_INFO_SEED, VAULT_SEED};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64)]
+pub struct DeStakeMany<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = signer,
+    )]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = associated_token::ID)]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DeStakeMany<'info> {
+    /// remaining_accounts: writable stake details of signer in this vault.
+    /// Still locked, tokenized, delegated or drained stake details are skipped
+    pub fn process(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        _: u64,
+    ) -> Result<()> {
+        let staker_info_key = self.staker_info.key();
+        let staker_info = &mut self.staker_info;
+        let vault = &mut self.vault;
+        let stake_config = &mut self.stake_config;
+
+        if vault.unbonding_period > 0 {
+            return Err(ErrorCode::UnbondingRequired.into());
+        }
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        let mut unstake_amount: u64 = 0;
+        let mut penalty_share: u64 = 0;
+
+        for stake_detail_info in remaining_accounts {
+            let mut stake_detail = Account::<StakeDetail>::try_from(stake_detail_info)?;
+
+            let (expected_key, _) = Pubkey::find_program_address(
+                &[
+                    STAKE_DETAIL_SEED,
+                    staker_info_key.as_ref(),
+                    &stake_detail.id.to_le_bytes(),
+                ],
+                &crate::ID,
+            );
+            if stake_detail_info.key() != expected_key {
+                return Err(ErrorCode::InvalidStakeDetailAccount.into());
+            }
+
+            if stake_detail.stake_amount == 0
+                || stake_detail.is_tokenized()
+                || stake_detail.is_unbonding()
+                || stake_detail.is_delegated()
+                || stake_detail.is_vote_locked(current_timestamp)
+                || current_timestamp < stake_detail.unlock_time(vault, current_timestamp)
+            {
+                continue;
+            }
+
+            let amount = stake_detail.stake_amount;
+            unstake_amount += amount;
+            penalty_share += vault
+                .pending_penalty_share(stake_detail.stake_amount, stake_detail.penalty_debt)?;
+
+            // update stake detail, persisted right away so duplicated accounts are skipped
+            stake_config.remove_voting_power(
+                &mut stake_detail,
+                vault.lock_period,
+                current_timestamp,
+            );
+            stake_detail.stake_amount = 0;
+            stake_detail.penalty_debt = 0;
+            stake_detail.exit(&crate::ID)?;
+
+            // update vault
+            vault.total_staked -= amount;
+            emit_position_update(vault, &stake_detail, current_timestamp);
+        }
+
+        if unstake_amount == 0 {
+            return Err(ErrorCode::NotStaked.into());
+        }
+        vault.pay_penalty_share(penalty_share);
+
+        // update staker info
+        staker_info.total_stake -= unstake_amount;
+        staker_info.write_checkpoint(current_timestamp);
+
+        // single transfer for all matured positions
+        token_transfer_with_signer(
+            self.vault_token_account.to_account_info(),
+            vault.to_account_info(),
+            self.staker_token_account.to_account_info(),
+            &self.token_program,
+            &[&vault.auth_seeds(&vault.lock_period.to_le_bytes())],
+            unstake_amount + penalty_share,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{
+    constant::constants::{POSITION_RECEIPT_SEED, STAKE_CONFIG_SEED, VAULT_SEED},
+    event::emit_position_update,
+    state::StakerInfo,
+    utils::{token_burn_user, token_transfer_with_signer},
+    PositionReceipt, StakeConfig, StakeDetail, Vault,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+use solana_program::clock::Clock;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64)]
+pub struct DeStakeWithReceipt<'info> {
+    /// holder of the receipt token, not necessarily the original staker
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub receipt_mint: Box<Account<'info, Mint>>,
+
+    /// closed to the staker once the position is redeemed
+    #[account(
+        mut,
+        seeds = [POSITION_RECEIPT_SEED, receipt_mint.key().as_ref()],
+        bump,
+        has_one = vault,
+        has_one = staker_info,
+        has_one = stake_detail,
+    )]
+    pub receipt: Box<Account<'info, PositionReceipt>>,
+
+    /// CHECK: original staker, who paid the receipt rent and gets it back
+    #[account(mut, address = stake_detail.staker)]
+    pub staker: UncheckedAccount<'info>,
+
+    /// CHECK: staker info of the original staker, validated by receipt
+    #[account(mut)]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    /// CHECK: validated by receipt
+    #[account(mut)]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    #[account(
+        mut,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = signer,
+    )]
+    pub signer_receipt_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = signer,
+    )]
+    pub staker_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = associated_token::ID)]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DeStakeWithReceipt<'info> {
+    /// Redeem the whole position by burning its receipt token. When the vault has an
+    /// unbonding period the first call starts unbonding and keeps the receipt, which
+    /// redeems the position once the period is over
+    pub fn process(&mut self, _: u64) -> Result<()> {
+        if self.stake_detail.stake_amount == 0 {
+            return Err(ErrorCode::NotStaked.into());
+        }
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        if self.stake_detail.is_unbonding() {
+            return self.withdraw(current_timestamp);
+        }
+
+        if self.stake_detail.is_vote_locked(current_timestamp) {
+            return Err(ErrorCode::PositionVoteLocked.into());
+        }
+        if current_timestamp
+            < self
+                .stake_detail
+                .unlock_time(&self.vault, current_timestamp)
+        {
+            return Err(ErrorCode::UnbondingTimeNotOverYet.into());
+        }
+
+        // same as destake, the unbonding period cannot be skipped by tokenizing a position
+        if self.vault.unbonding_period > 0 {
+            return self.request_unstake(current_timestamp);
+        }
+
+        let staker_info = &mut self.staker_info;
+        let vault = &mut self.vault;
+        let stake_detail = &mut self.stake_detail;
+        let stake_config = &mut self.stake_config;
+
+        let unstake_amount = stake_detail.stake_amount;
+
+        // share of penalties paid by early unstakers
+        let penalty_share =
+            vault.pending_penalty_share(stake_detail.stake_amount, stake_detail.penalty_debt)?;
+        vault.pay_penalty_share(penalty_share);
+
+        // update stake detail
+        stake_config.remove_voting_power(stake_detail, vault.lock_period, current_timestamp);
+        stake_detail.stake_amount = 0;
+        stake_detail.penalty_debt = 0;
+        stake_detail.receipt_mint = Pubkey::default();
+
+        // update staker info
+        staker_info.total_stake -= unstake_amount;
+        staker_info.write_checkpoint(current_timestamp);
+
+        // update vault
+        vault.total_staked -= unstake_amount;
+        emit_position_update(vault, stake_detail, current_timestamp);
+
+        self.redeem(unstake_amount + penalty_share)
+    }
+
+    /// same as request_unstake, the accrued penalty share is paid to the holder now
+    fn request_unstake(&mut self, current_timestamp: i64) -> Result<()> {
+        let vault = &mut self.vault;
+        let stake_detail = &mut self.stake_detail;
+        let stake_config = &mut self.stake_config;
+
+        // share of penalties paid by early unstakers
+        let penalty_share =
+            vault.pending_penalty_share(stake_detail.stake_amount, stake_detail.penalty_debt)?;
+        vault.pay_penalty_share(penalty_share);
+
+        // update stake detail, unbonding positions have no voting power
+        stake_config.remove_voting_power(stake_detail, vault.lock_period, current_timestamp);
+        stake_detail.unbonding_started_at = current_timestamp;
+        stake_detail.auto_renew = false;
+        stake_detail.penalty_debt = 0;
+
+        // update vault, unbonding tokens are no longer staked
+        vault.total_staked -= stake_detail.stake_amount;
+        vault.total_unbonding += stake_detail.stake_amount;
+        emit_position_update(vault, stake_detail, current_timestamp);
+
+        if penalty_share > 0 {
+            token_transfer_with_signer(
+                self.vault_token_account.to_account_info(),
+                vault.to_account_info(),
+                self.staker_token_account.to_account_info(),
+                &self.token_program,
+                &[&vault.auth_seeds(&vault.lock_period.to_le_bytes())],
+                penalty_share,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// same as withdraw, for a position unbonding since an earlier call
+    fn withdraw(&mut self, current_timestamp: i64) -> Result<()> {
+        let staker_info = &mut self.staker_info;
+        let vault = &mut self.vault;
+        let stake_detail = &mut self.stake_detail;
+
+        if current_timestamp < stake_detail.unbonding_started_at + vault.unbonding_period as i64 {
+            return Err(ErrorCode::UnbondingTimeNotOverYet.into());
+        }
+
+        let unstake_amount = stake_detail.stake_amount;
+
+        // update stake detail
+        stake_detail.stake_amount = 0;
+        stake_detail.unbonding_started_at = 0;
+        stake_detail.receipt_mint = Pubkey::default();
+
+        // update staker info
+        staker_info.total_stake -= unstake_amount;
+        staker_info.write_checkpoint(current_timestamp);
+
+        // update vault
+        vault.total_unbonding -= unstake_amount;
+        emit_position_update(vault, stake_detail, current_timestamp);
+
+        self.redeem(unstake_amount)
+    }
+
+    /// burn the receipt token, pay `amount` to the holder and close the receipt
+    fn redeem(&mut self, amount: u64) -> Result<()> {
+        token_burn_user(
+            self.receipt_mint.to_account_info(),
+            self.signer_receipt_token_account.to_account_info(),
+            &self.signer,
+            &self.token_program,
+            1,
+        )?;
+
+        // transfer to holder
+        token_transfer_with_signer(
+            self.vault_token_account.to_account_info(),
+            self.vault.to_account_info(),
+            self.staker_token_account.to_account_info(),
+            &self.token_program,
+            &[&self.vault.auth_seeds(&self.vault.lock_period.to_le_bytes())],
+            amount,
+        )?;
+
+        // rent goes back to the original staker, who paid it
+        self.receipt.close(self.staker.to_account_info())
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    constant::constants::{BPS_DENOMINATOR, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED},
+    event::emit_position_update,
+    event::EarlyDeStakeEvent,
+    state::StakerInfo,
+    utils::token_transfer_with_signer,
+    StakeConfig, StakeDetail, Vault,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+use solana_program::clock::Clock;
+
+use crate::constant::constants::{STAKER_INFO_SEED, VAULT_SEED};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(id: u64, lock_period: u64,)]
+pub struct EarlyDeStake<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+       mut,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        constraint = !stake_detail.is_tokenized() @ErrorCode::PositionTokenized,
+        constraint = !stake_detail.is_unbonding() @ErrorCode::PositionUnbonding,
+        constraint = !stake_detail.is_delegated() @ErrorCode::PositionDelegated,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = signer,
+    )]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: only required when the penalty is sent to treasury
+    #[account(
+        mut,
+        token::mint = stake_currency_mint,
+        token::authority = vault.treasury,
+    )]
+    pub treasury_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = associated_token::ID)]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> EarlyDeStake<'info> {
+    pub fn process(&mut self, id: u64, _: u64, amount: u64) -> Result<()> {
+        let staker_info = &mut self.staker_info;
+        let vault = &mut self.vault;
+        let stake_detail = &mut self.stake_detail;
+        let stake_config = &mut self.stake_config;
+
+        if stake_detail.stake_amount == 0 {
+            return Err(ErrorCode::NotStaked.into());
+        }
+
+        if !vault.allow_early_unstake {
+            return Err(ErrorCode::EarlyUnstakeNotAllowed.into());
+        }
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        if stake_detail.is_vote_locked(current_timestamp) {
+            return Err(ErrorCode::PositionVoteLocked.into());
+        }
+        if stake_detail.is_revocable_grant(current_timestamp) {
+            return Err(ErrorCode::PositionRevocable.into());
+        }
+
+        let unlock_time = stake_detail.unlock_time(vault, current_timestamp);
+        // matured positions have no penalty to pay and must go through unbonding
+        if vault.unbonding_period > 0 && current_timestamp >= unlock_time {
+            return Err(ErrorCode::UnbondingRequired.into());
+        }
+
+        let remaining_lock_time = std::cmp::max(unlock_time - current_timestamp, 0);
+
+        // eg: stake amount = 9, amount = 10 -> unstake_amount = 9
+        let unstake_amount = std::cmp::min(stake_detail.stake_amount, amount);
+        let penalty_amount = get_early_unstake_penalty(
+            unstake_amount,
+            vault.max_early_unstake_penalty_bps,
+            remaining_lock_time as u64,
+            vault.lock_period,
+        )?;
+
+        // share of penalties paid by other early unstakers
+        let penalty_share =
+            vault.pending_penalty_share(stake_detail.stake_amount, stake_detail.penalty_debt)?;
+        vault.pay_penalty_share(penalty_share);
+
+        // update stake detail
+        stake_config.remove_voting_power(stake_detail, vault.lock_period, current_timestamp);
+        stake_detail.stake_amount -= unstake_amount;
+        stake_config.add_voting_power(stake_detail, vault.lock_period, current_timestamp);
+
+        // update staker info
+        staker_info.total_stake -= unstake_amount;
+        staker_info.write_checkpoint(current_timestamp);
+
+        // update vault
+        vault.total_staked -= unstake_amount;
+
+        // the remaining stake of this position gets no part of its own penalty,
+        // nobody else left to redistribute to -> fallback to treasury
+        let redistributed = penalty_amount > 0
+            && vault.redistribute_penalty
+            && vault.share_penalty(penalty_amount, stake_detail.stake_amount);
+        // debt is taken after the redistribution so the position starts from the raised accumulator
+        stake_detail.penalty_debt = vault.penalty_debt(stake_detail.stake_amount)?;
+        emit_position_update(vault, stake_detail, current_timestamp);
+
+        if penalty_amount > 0 && !redistributed {
+            let treasury_token_account = self
+                .treasury_token_account
+                .as_ref()
+                .ok_or(ErrorCode::TreasuryTokenAccountRequired)?;
+
+            token_transfer_with_signer(
+                self.vault_token_account.to_account_info(),
+                vault.to_account_info(),
+                treasury_token_account.to_account_info(),
+                &self.token_program,
+                &[&vault.auth_seeds(&vault.lock_period.to_le_bytes())],
+                penalty_amount,
+            )?;
+        }
+
+        // transfer to user
+        token_transfer_with_signer(
+            self.vault_token_account.to_account_info(),
+            vault.to_account_info(),
+            self.staker_token_account.to_account_info(),
+            &self.token_program,
+            &[&vault.auth_seeds(&vault.lock_period.to_le_bytes())],
+            unstake_amount - penalty_amount + penalty_share,
+        )?;
+
+        emit!(EarlyDeStakeEvent {
+            vault: vault.key(),
+            staker: self.signer.key(),
+            stake_detail_id: id,
+            unstake_amount: unstake_amount - penalty_amount,
+            penalty_amount,
+            redistributed,
+            timestamp: current_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+/// penalty decays linearly from max_penalty_bps right after staking to 0 at unstaked_at_time
+fn get_early_unstake_penalty(
+    amount: u64,
+    max_penalty_bps: u16,
+    remaining_lock_time: u64,
+    lock_period: u64,
+) -> Result<u64> {
+    if lock_period == 0 {
+        return Ok(0);
+    }
+    let remaining_lock_time = std::cmp::min(remaining_lock_time, lock_period);
+
+    Ok((amount as u128)
+        .checked_mul(max_penalty_bps as u128)
+        .ok_or(ErrorCode::OverflowError)?
+        .checked_mul(remaining_lock_time as u128)
+        .ok_or(ErrorCode::OverflowError)?
+        .checked_div((lock_period as u128) * (BPS_DENOMINATOR as u128))
+        .ok_or(ErrorCode::OverflowError)? as u64)
+}
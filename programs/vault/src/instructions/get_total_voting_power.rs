@@ -0,0 +1,21 @@
+use crate::{constant::constants::STAKE_CONFIG_SEED, StakeConfig};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct GetTotalVotingPower<'info> {
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> GetTotalVotingPower<'info> {
+    /// total voting power of all vaults sharing this stake config, returned as return data
+    pub fn process(&self, timestamp: i64) -> Result<u64> {
+        Ok(self.stake_config.total_voting_power(timestamp))
+    }
+}
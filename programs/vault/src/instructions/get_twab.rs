@@ -0,0 +1,56 @@
+use crate::{
+    constant::constants::{STAKER_INFO_SEED, STAKE_CONFIG_SEED, VAULT_SEED},
+    state::StakerInfo,
+    StakeConfig, Vault,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey, lock_period: u64)]
+pub struct GetTwab<'info> {
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), owner.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> GetTwab<'info> {
+    /// time-weighted average of total_stake between start and end, returned as return data
+    pub fn process(&self, _: Pubkey, _: u64, start: i64, end: i64) -> Result<u64> {
+        if start >= end {
+            return Err(ErrorCode::InvalidTwabRange.into());
+        }
+
+        let staker_info = &self.staker_info;
+        let cumulative_start = staker_info
+            .cumulative_stake_at(start)
+            .ok_or(ErrorCode::TwabHistoryUnavailable)?;
+        let cumulative_end = staker_info
+            .cumulative_stake_at(end)
+            .ok_or(ErrorCode::TwabHistoryUnavailable)?;
+
+        Ok(((cumulative_end - cumulative_start) / (end - start) as u128) as u64)
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{
+    constant::constants::{STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED},
+    StakeConfig, StakeDetail, Vault,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct GetVotingPower<'info> {
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> GetVotingPower<'info> {
+    /// remaining_accounts: (vault, stake detail) pairs of positions owned by `owner`,
+    /// or delegated to `owner` when it is an operator.
+    /// Voting power is returned as return data so other programs can read it through CPI
+    pub fn process(
+        &self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        owner: Pubkey,
+        timestamp: i64,
+    ) -> Result<u64> {
+        owner_voting_power(
+            self.stake_config.key(),
+            remaining_accounts,
+            owner,
+            timestamp,
+        )
+    }
+}
+
+/// sum of voting power of (vault, stake detail) pairs voted by `owner` under `stake_config_key`.
+/// Delegated positions are voted by their operator, the others by their staker
+pub fn owner_voting_power<'info>(
+    stake_config_key: Pubkey,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    owner: Pubkey,
+    timestamp: i64,
+) -> Result<u64> {
+    let pairs = remaining_accounts.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err(ErrorCode::InvalidStakeDetailAccount.into());
+    }
+
+    let mut counted: Vec<Pubkey> = vec![];
+    let mut voting_power: u64 = 0;
+
+    for accounts in pairs {
+        let (vault_info, stake_detail_info) = (&accounts[0], &accounts[1]);
+        let vault = Account::<Vault>::try_from(vault_info)?;
+
+        let (expected_vault, _) = Pubkey::find_program_address(
+            &[
+                VAULT_SEED,
+                stake_config_key.as_ref(),
+                &vault.lock_period.to_le_bytes(),
+            ],
+            &crate::ID,
+        );
+        if vault_info.key() != expected_vault {
+            return Err(ErrorCode::InvalidVaultAccount.into());
+        }
+
+        let stake_detail = Account::<StakeDetail>::try_from(stake_detail_info)?;
+        let voter = if stake_detail.is_delegated() {
+            stake_detail.delegate_to
+        } else {
+            stake_detail.staker
+        };
+        if voter != owner {
+            return Err(ErrorCode::InvalidStakeDetailAccount.into());
+        }
+
+        let (staker_info_key, _) = Pubkey::find_program_address(
+            &[
+                STAKER_INFO_SEED,
+                vault_info.key.as_ref(),
+                stake_detail.staker.as_ref(),
+            ],
+            &crate::ID,
+        );
+        let (expected_key, _) = Pubkey::find_program_address(
+            &[
+                STAKE_DETAIL_SEED,
+                staker_info_key.as_ref(),
+                &stake_detail.id.to_le_bytes(),
+            ],
+            &crate::ID,
+        );
+        if stake_detail_info.key() != expected_key {
+            return Err(ErrorCode::InvalidStakeDetailAccount.into());
+        }
+
+        if counted.contains(&expected_key) {
+            return Err(ErrorCode::DuplicateStakeDetail.into());
+        }
+        counted.push(expected_key);
+
+        voting_power += stake_detail.voting_power(vault.lock_period, timestamp);
+    }
+
+    Ok(voting_power)
+}
@@ -0,0 +1,139 @@
+use crate::{
+    constant::constants::{
+        ACCOUNT_VERSION, STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED,
+    },
+    event::emit_position_update,
+    state::StakerInfo,
+    utils::token_transfer_user,
+    StakeConfig, StakeDetail, Vault, STAKER_INFO_SIZE, STAKE_DETAIL_SIZE,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+use solana_program::clock::Clock;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64)]
+pub struct GrantStake<'info> {
+    /// funds the position
+    #[account(mut)]
+    pub grantor: Signer<'info>,
+
+    /// CHECK: any wallet can receive a grant, the grantor pays for its staker info
+    pub beneficiary: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: staking ATA of vault
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), beneficiary.key.as_ref()],
+        bump,
+        payer = grantor,
+        space = STAKER_INFO_SIZE,
+    )]
+    pub beneficiary_staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        init,
+        seeds = [STAKE_DETAIL_SEED, beneficiary_staker_info.key().as_ref(), &(beneficiary_staker_info.current_id + 1).to_le_bytes()],
+        bump,
+        payer = grantor,
+        space = STAKE_DETAIL_SIZE
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = grantor,
+    )]
+    pub grantor_token_account: Box<Account<'info, TokenAccount>>,
+
+    // CHECK: the SPL token for staking, not rewarding
+    pub stake_currency_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    #[account(address = associated_token::ID)]
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> GrantStake<'info> {
+    pub fn process(&mut self, _: u64, amount: u64, revocable: bool) -> Result<()> {
+        let staker_info = &mut self.beneficiary_staker_info;
+        let vault = &mut self.vault;
+        let stake_detail = &mut self.stake_detail;
+        let stake_config = &mut self.stake_config;
+
+        if amount == 0 {
+            return Err(ErrorCode::NoTokens.into());
+        }
+
+        vault.check_new_stake(staker_info.total_stake, amount)?;
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+
+        // update stake detail
+        stake_detail.unstaked_at_time = current_timestamp + vault.lock_period as i64;
+        stake_detail.stake_amount = amount;
+        stake_detail.id = staker_info.current_id + 1;
+        stake_detail.staker = self.beneficiary.key();
+        stake_detail.version = ACCOUNT_VERSION;
+        stake_detail.penalty_debt = vault.penalty_debt(amount)?;
+        stake_detail.grantor = self.grantor.key();
+        stake_detail.revocable = revocable;
+        stake_detail.vesting_start = current_timestamp;
+        stake_config.add_voting_power(stake_detail, vault.lock_period, current_timestamp);
+
+        // update staker info
+        staker_info.total_stake += amount;
+        staker_info.write_checkpoint(current_timestamp);
+        staker_info.current_id += 1;
+        staker_info.version = ACCOUNT_VERSION;
+        staker_info.open_positions += 1;
+
+        // update vault
+        vault.total_staked += amount;
+        emit_position_update(vault, stake_detail, current_timestamp);
+
+        token_transfer_user(
+            self.grantor_token_account.to_account_info(),
+            &self.grantor,
+            self.vault_token_account.to_account_info(),
+            &self.token_program,
+            amount,
+        )?;
+
+        Ok(())
+    }
+}
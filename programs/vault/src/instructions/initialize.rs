@@ -1,4 +1,9 @@
-use crate::{constant::constants::STAKE_CONFIG_SEED, StakeConfig, STAKE_CONFIG_SIZE};
+use crate::{
+    constant::constants::{
+        ACCOUNT_VERSION, DEFAULT_MAX_LOCK_PERIOD, DEFAULT_MIN_LOCK_PERIOD, STAKE_CONFIG_SEED,
+    },
+    StakeConfig, STAKE_CONFIG_SIZE,
+};
 use anchor_lang::{prelude::*, system_program};
 
 use anchor_spl::token::{self, Mint, Token};
@@ -37,7 +42,9 @@ impl<'info> Initialize<'info> {
         stake_config.authority = self.signer.to_account_info().key();
         stake_config.stake_currency_mint = self.stake_currency_mint.to_account_info().key();
         stake_config.bump = [bump];
-        stake_config.version = 1;
+        stake_config.version = ACCOUNT_VERSION;
+        stake_config.min_lock_period = DEFAULT_MIN_LOCK_PERIOD;
+        stake_config.max_lock_period = DEFAULT_MAX_LOCK_PERIOD;
 
         Ok(())
     }
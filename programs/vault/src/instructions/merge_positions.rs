@@ -0,0 +1,137 @@
+use crate::{
+    constant::constants::{STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED},
+    event::{emit_position_moved, emit_position_update},
+    state::StakerInfo,
+    StakeConfig, StakeDetail, Vault,
+};
+use anchor_lang::{prelude::*, AccountsClose};
+use anchor_spl::token::Mint;
+use solana_program::clock::Clock;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64, target_id: u64)]
+pub struct MergePositions<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &target_id.to_le_bytes()],
+        bump,
+        constraint = !stake_detail.is_tokenized() @ErrorCode::PositionTokenized,
+        constraint = !stake_detail.is_unbonding() @ErrorCode::PositionUnbonding,
+        constraint = !stake_detail.is_delegated() @ErrorCode::PositionDelegated,
+        constraint = !stake_detail.auto_renew @ErrorCode::PositionAutoRenew,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> MergePositions<'info> {
+    /// remaining_accounts: stake details to merge into stake_detail, in the same order as source_ids.
+    /// Source stake details are closed and their rent is returned to signer
+    pub fn process(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        _: u64,
+        target_id: u64,
+        source_ids: Vec<u64>,
+    ) -> Result<()> {
+        if source_ids.len() != remaining_accounts.len() {
+            return Err(ErrorCode::InvalidStakeDetailAccount.into());
+        }
+
+        let staker_info_key = self.staker_info.key();
+        let stake_detail = &mut self.stake_detail;
+        let stake_config = &mut self.stake_config;
+        let lock_period = self.vault.lock_period;
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        if stake_detail.is_revocable_grant(current_timestamp) {
+            return Err(ErrorCode::PositionRevocable.into());
+        }
+        stake_config.remove_voting_power(stake_detail, lock_period, current_timestamp);
+
+        for (i, (source_id, source_info)) in source_ids.iter().zip(remaining_accounts).enumerate() {
+            if *source_id == target_id || source_ids[..i].contains(source_id) {
+                return Err(ErrorCode::DuplicateStakeDetail.into());
+            }
+
+            let (expected_key, _) = Pubkey::find_program_address(
+                &[
+                    STAKE_DETAIL_SEED,
+                    staker_info_key.as_ref(),
+                    &source_id.to_le_bytes(),
+                ],
+                &crate::ID,
+            );
+            if source_info.key() != expected_key {
+                return Err(ErrorCode::InvalidStakeDetailAccount.into());
+            }
+
+            let mut source = Account::<StakeDetail>::try_from(source_info)?;
+            if source.is_tokenized() {
+                return Err(ErrorCode::PositionTokenized.into());
+            }
+            if source.is_unbonding() {
+                return Err(ErrorCode::PositionUnbonding.into());
+            }
+            if source.auto_renew {
+                return Err(ErrorCode::PositionAutoRenew.into());
+            }
+            if source.is_revocable_grant(current_timestamp) {
+                return Err(ErrorCode::PositionRevocable.into());
+            }
+            if source.is_delegated() {
+                return Err(ErrorCode::PositionDelegated.into());
+            }
+
+            stake_config.remove_voting_power(&mut source, lock_period, current_timestamp);
+
+            // keep the latest unlock time of all merged positions
+            stake_detail.unstaked_at_time =
+                std::cmp::max(stake_detail.unstaked_at_time, source.unstaked_at_time);
+            stake_detail.stake_amount += source.stake_amount;
+            // summing debts keeps pending penalty shares of every source position
+            stake_detail.penalty_debt += source.penalty_debt;
+            stake_detail.vote_locked_until =
+                std::cmp::max(stake_detail.vote_locked_until, source.vote_locked_until);
+
+            emit_position_moved(&self.vault, &source, current_timestamp);
+            source.close(self.signer.to_account_info())?;
+        }
+        self.staker_info.open_positions -= source_ids.len() as u64;
+
+        stake_config.add_voting_power(stake_detail, lock_period, current_timestamp);
+        emit_position_update(&self.vault, stake_detail, current_timestamp);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+use solana_program::clock::Clock;
+
+use crate::{
+    constant::constants::{
+        ACCOUNT_VERSION, STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED,
+    },
+    event::emit_position_update,
+    state::StakerInfo,
+    utils::migrate_account,
+    StakeConfig, StakeDetail, Vault, STAKE_DETAIL_SIZE,
+};
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64, staker: Pubkey, id: u64)]
+pub struct MigrateStakeDetail<'info> {
+    /// anyone can pay for the migration, its result only depends on the legacy account
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), staker.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    /// CHECK: legacy stake detail which cannot be deserialized yet, checked by migrate_account
+    #[account(
+        mut,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub stake_detail: UncheckedAccount<'info>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateStakeDetail<'info> {
+    /// new fields default to a self staked, undelegated position without auto-renew.
+    /// A zero penalty_debt is right, it was staked since the vault accumulator started.
+    /// The position gets its voting power, it had none as a legacy account
+    pub fn process(&mut self, _: u64, _: Pubkey, _: u64) -> Result<()> {
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        let stake_config = &mut self.stake_config;
+        let vault = &self.vault;
+        migrate_account(
+            &self.stake_detail.to_account_info(),
+            &self.signer,
+            &self.system_program,
+            STAKE_DETAIL_SIZE,
+            |stake_detail: &mut StakeDetail| {
+                stake_detail.version = ACCOUNT_VERSION;
+                stake_config.add_voting_power(stake_detail, vault.lock_period, current_timestamp);
+                emit_position_update(vault, stake_detail, current_timestamp);
+                Ok(())
+            },
+        )
+    }
+}
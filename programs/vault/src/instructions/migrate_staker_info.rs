@@ -0,0 +1,68 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+use solana_program::clock::Clock;
+
+use crate::{
+    constant::constants::{ACCOUNT_VERSION, STAKER_INFO_SEED, STAKE_CONFIG_SEED, VAULT_SEED},
+    state::StakerInfo,
+    utils::migrate_account,
+    StakeConfig, Vault, STAKER_INFO_SIZE,
+};
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64, staker: Pubkey)]
+pub struct MigrateStakerInfo<'info> {
+    /// anyone can pay for the migration, its result only depends on the legacy account
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: legacy staker info which cannot be deserialized yet, checked by migrate_account
+    #[account(
+        mut,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), staker.as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub staker_info: UncheckedAccount<'info>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateStakerInfo<'info> {
+    /// legacy stake details were never closed, so ids 1..=current_id are all open.
+    /// The first checkpoint holds the legacy balance, TWAB before it is unknown
+    pub fn process(&mut self, _: u64, _: Pubkey) -> Result<()> {
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        migrate_account(
+            &self.staker_info.to_account_info(),
+            &self.signer,
+            &self.system_program,
+            STAKER_INFO_SIZE,
+            |staker_info: &mut StakerInfo| {
+                staker_info.version = ACCOUNT_VERSION;
+                staker_info.open_positions = staker_info.current_id;
+                staker_info.write_checkpoint(current_timestamp);
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,60 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{ACCOUNT_VERSION, STAKE_CONFIG_SEED, VAULT_SEED},
+    utils::migrate_account,
+    StakeConfig, Vault, VAULT_SIZE,
+};
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64)]
+pub struct MigrateVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: must match authority of stake_config
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    /// CHECK: legacy vault which cannot be deserialized yet, checked by migrate_account
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+        owner = crate::ID,
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateVault<'info> {
+    /// new fields get the defaults of create_vault: open, no caps, no early unstake,
+    /// unbonding or auto-renew. Legacy vaults never redistributed a penalty
+    pub fn process(&mut self, _: u64) -> Result<()> {
+        migrate_account(
+            &self.vault.to_account_info(),
+            &self.authority,
+            &self.system_program,
+            VAULT_SIZE,
+            |vault: &mut Vault| {
+                vault.version = ACCOUNT_VERSION;
+                vault.is_active = true;
+                Ok(())
+            },
+        )
+    }
+}
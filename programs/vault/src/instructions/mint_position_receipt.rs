@@ -0,0 +1,201 @@
+use crate::{
+    constant::constants::{
+        POSITION_MINT_SEED, POSITION_RECEIPT_SEED, RECEIPT_NAME_PREFIX, RECEIPT_SYMBOL,
+        STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED,
+    },
+    state::StakerInfo,
+    utils::token_mint_to_with_signer,
+    PositionReceipt, StakeConfig, StakeDetail, Vault, POSITION_RECEIPT_SIZE,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    metadata::{self, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3, Metadata},
+    token::{self, spl_token::instruction::AuthorityType, Mint, Token, TokenAccount},
+};
+use solana_program::clock::Clock;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(id: u64, lock_period: u64)]
+pub struct MintPositionReceipt<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        constraint = !stake_detail.is_tokenized() @ErrorCode::PositionTokenized,
+        constraint = !stake_detail.is_unbonding() @ErrorCode::PositionUnbonding,
+        constraint = !stake_detail.is_delegated() @ErrorCode::PositionDelegated,
+        constraint = !stake_detail.auto_renew @ErrorCode::PositionAutoRenew,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    #[account(
+        init,
+        seeds = [POSITION_MINT_SEED, stake_detail.key().as_ref()],
+        bump,
+        payer = signer,
+        mint::decimals = 0,
+        mint::authority = vault,
+    )]
+    pub receipt_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        seeds = [POSITION_RECEIPT_SEED, receipt_mint.key().as_ref()],
+        bump,
+        payer = signer,
+        space = POSITION_RECEIPT_SIZE
+    )]
+    pub receipt: Box<Account<'info, PositionReceipt>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = signer,
+    )]
+    pub signer_receipt_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: metaplex metadata of receipt_mint, created by the token metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), receipt_mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub receipt_metadata: UncheckedAccount<'info>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    #[account(address = associated_token::ID)]
+    associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> MintPositionReceipt<'info> {
+    pub fn process(&mut self, _: u64, _: u64, receipt_bump: u8) -> Result<()> {
+        let vault = &self.vault;
+        let stake_detail = &mut self.stake_detail;
+        let receipt = &mut self.receipt;
+
+        if stake_detail.stake_amount == 0 {
+            return Err(ErrorCode::NotStaked.into());
+        }
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        if stake_detail.is_revocable_grant(current_timestamp) {
+            return Err(ErrorCode::PositionRevocable.into());
+        }
+
+        let lock_period_bytes = vault.lock_period.to_le_bytes();
+        let vault_seeds = vault.auth_seeds(&lock_period_bytes);
+
+        token_mint_to_with_signer(
+            self.receipt_mint.to_account_info(),
+            vault.to_account_info(),
+            self.signer_receipt_token_account.to_account_info(),
+            &self.token_program,
+            &[&vault_seeds],
+            1,
+        )?;
+
+        // metadata shown by wallets, the uri serves the position attributes from the receipt
+        let uri = if self.stake_config.receipt_uri.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "{}{}",
+                self.stake_config.receipt_uri,
+                self.receipt_mint.key()
+            )
+        };
+        metadata::create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.to_account_info(),
+                CreateMetadataAccountsV3 {
+                    metadata: self.receipt_metadata.to_account_info(),
+                    mint: self.receipt_mint.to_account_info(),
+                    mint_authority: vault.to_account_info(),
+                    payer: self.signer.to_account_info(),
+                    update_authority: vault.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    rent: self.rent.to_account_info(),
+                },
+                &[&vault_seeds],
+            ),
+            DataV2 {
+                name: format!("{}{}", RECEIPT_NAME_PREFIX, stake_detail.id),
+                symbol: RECEIPT_SYMBOL.to_string(),
+                uri,
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            true,
+            true,
+            None,
+        )?;
+
+        // fix supply to 1
+        token::set_authority(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                token::SetAuthority {
+                    current_authority: vault.to_account_info(),
+                    account_or_mint: self.receipt_mint.to_account_info(),
+                },
+                &[&vault_seeds],
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+
+        // update receipt
+        receipt.bump = [receipt_bump];
+        receipt.mint = self.receipt_mint.key();
+        receipt.vault = vault.key();
+        receipt.staker_info = self.staker_info.key();
+        receipt.stake_detail = stake_detail.key();
+        receipt.stake_amount = stake_detail.stake_amount;
+        receipt.lock_period = vault.lock_period;
+        receipt.unstaked_at_time = stake_detail.unstaked_at_time;
+
+        // update stake detail
+        stake_detail.receipt_mint = self.receipt_mint.key();
+
+        Ok(())
+    }
+}
@@ -1,9 +1,89 @@
+pub mod audit_vault;
+pub mod close_position;
+pub mod close_staker_info;
+pub mod close_vault;
+pub mod create_max_voter_weight_record;
 pub mod create_vault;
+pub mod create_voter_weight_record;
+pub mod delegate;
 pub mod destake;
+pub mod destake_many;
+pub mod destake_with_receipt;
+pub mod early_destake;
+pub mod get_total_voting_power;
+pub mod get_twab;
+pub mod get_voting_power;
+pub mod grant_stake;
 pub mod initialize;
+pub mod merge_positions;
+pub mod migrate_stake_config;
+pub mod migrate_stake_detail;
+pub mod migrate_staker_info;
+pub mod migrate_vault;
+pub mod mint_position_receipt;
+pub mod register_operator;
+pub mod request_unstake;
+pub mod revoke_grant;
+pub mod set_auto_renew;
+pub mod set_early_unstake_config;
+pub mod set_lock_period_bounds;
+pub mod set_operator_config;
+pub mod set_operator_status;
+pub mod set_receipt_uri;
+pub mod set_renew_window;
+pub mod set_unbonding_period;
+pub mod set_vault_limits;
+pub mod set_vault_status;
+pub mod set_vote_lock_period;
+pub mod split_position;
 pub mod stake;
+pub mod transfer_position;
+pub mod undelegate;
+pub mod update_max_voter_weight_record;
+pub mod update_voter_weight_record;
+pub mod withdraw;
 
+pub use audit_vault::*;
+pub use close_position::*;
+pub use close_staker_info::*;
+pub use close_vault::*;
+pub use create_max_voter_weight_record::*;
 pub use create_vault::*;
+pub use create_voter_weight_record::*;
+pub use delegate::*;
 pub use destake::*;
+pub use destake_many::*;
+pub use destake_with_receipt::*;
+pub use early_destake::*;
+pub use get_total_voting_power::*;
+pub use get_twab::*;
+pub use get_voting_power::*;
+pub use grant_stake::*;
 pub use initialize::*;
+pub use merge_positions::*;
+pub use migrate_stake_config::*;
+pub use migrate_stake_detail::*;
+pub use migrate_staker_info::*;
+pub use migrate_vault::*;
+pub use mint_position_receipt::*;
+pub use register_operator::*;
+pub use request_unstake::*;
+pub use revoke_grant::*;
+pub use set_auto_renew::*;
+pub use set_early_unstake_config::*;
+pub use set_lock_period_bounds::*;
+pub use set_operator_config::*;
+pub use set_operator_status::*;
+pub use set_receipt_uri::*;
+pub use set_renew_window::*;
+pub use set_unbonding_period::*;
+pub use set_vault_limits::*;
+pub use set_vault_status::*;
+pub use set_vote_lock_period::*;
+pub use split_position::*;
 pub use stake::*;
+pub use transfer_position::*;
+pub use undelegate::*;
+pub use update_max_voter_weight_record::*;
+pub use update_voter_weight_record::*;
+pub use withdraw::*;
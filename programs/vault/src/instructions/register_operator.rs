@@ -0,0 +1,56 @@
+use crate::{
+    constant::constants::{BPS_DENOMINATOR, OPERATOR_SEED, STAKE_CONFIG_SEED},
+    Operator, StakeConfig, OPERATOR_SIZE,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct RegisterOperator<'info> {
+    /// operator wallet
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// operators are approved by the authority of stake_config
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        init,
+        seeds = [OPERATOR_SEED, stake_config.key().as_ref(), signer.key.as_ref()],
+        bump,
+        payer = signer,
+        space = OPERATOR_SIZE,
+    )]
+    pub operator: Box<Account<'info, Operator>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterOperator<'info> {
+    pub fn process(&mut self, commission_bps: u16, bump: u8) -> Result<()> {
+        if commission_bps as u64 > BPS_DENOMINATOR {
+            return Err(ErrorCode::InvalidCommissionBps.into());
+        }
+
+        let operator = &mut self.operator;
+        operator.bump = [bump];
+        operator.stake_config = self.stake_config.key();
+        operator.operator = self.signer.key();
+        operator.commission_bps = commission_bps;
+        operator.is_active = true;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, STAKE_DETAIL_SEED},
+    event::emit_position_update,
+    state::StakerInfo,
+    utils::token_transfer_with_signer,
+    StakeConfig, StakeDetail, Vault,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+use solana_program::clock::Clock;
+
+use crate::constant::constants::{STAKER_INFO_SEED, VAULT_SEED};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(id: u64, lock_period: u64,)]
+pub struct RequestUnstake<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        constraint = !stake_detail.is_tokenized() @ErrorCode::PositionTokenized,
+        constraint = !stake_detail.is_unbonding() @ErrorCode::PositionUnbonding,
+        constraint = !stake_detail.is_delegated() @ErrorCode::PositionDelegated,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = signer,
+    )]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = associated_token::ID)]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RequestUnstake<'info> {
+    /// Start unbonding the whole position. Its accrued penalty share is paid now,
+    /// afterwards it earns nothing until withdraw
+    pub fn process(&mut self, _: u64, _: u64) -> Result<()> {
+        let vault = &mut self.vault;
+        let stake_detail = &mut self.stake_detail;
+        let stake_config = &mut self.stake_config;
+
+        if stake_detail.stake_amount == 0 {
+            return Err(ErrorCode::NotStaked.into());
+        }
+
+        if vault.unbonding_period == 0 {
+            return Err(ErrorCode::UnbondingNotEnabled.into());
+        }
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        if stake_detail.is_vote_locked(current_timestamp) {
+            return Err(ErrorCode::PositionVoteLocked.into());
+        }
+        if current_timestamp < stake_detail.unlock_time(vault, current_timestamp) {
+            return Err(ErrorCode::UnbondingTimeNotOverYet.into());
+        }
+
+        // share of penalties paid by early unstakers
+        let penalty_share =
+            vault.pending_penalty_share(stake_detail.stake_amount, stake_detail.penalty_debt)?;
+        vault.pay_penalty_share(penalty_share);
+
+        // update stake detail, unbonding positions have no voting power
+        stake_config.remove_voting_power(stake_detail, vault.lock_period, current_timestamp);
+        stake_detail.unbonding_started_at = current_timestamp;
+        stake_detail.auto_renew = false;
+        stake_detail.penalty_debt = 0;
+
+        // update vault, unbonding tokens are no longer staked
+        vault.total_staked -= stake_detail.stake_amount;
+        vault.total_unbonding += stake_detail.stake_amount;
+        emit_position_update(vault, stake_detail, current_timestamp);
+
+        if penalty_share > 0 {
+            token_transfer_with_signer(
+                self.vault_token_account.to_account_info(),
+                vault.to_account_info(),
+                self.staker_token_account.to_account_info(),
+                &self.token_program,
+                &[&vault.auth_seeds(&vault.lock_period.to_le_bytes())],
+                penalty_share,
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{
+    constant::constants::{STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED},
+    event::emit_position_update,
+    state::StakerInfo,
+    utils::{proportion, token_transfer_with_signer},
+    StakeConfig, StakeDetail, Vault,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+use solana_program::clock::Clock;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(id: u64, lock_period: u64)]
+pub struct RevokeGrant<'info> {
+    #[account(mut)]
+    pub grantor: Signer<'info>,
+
+    /// CHECK: owner of the granted position
+    pub beneficiary: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), beneficiary.key.as_ref()],
+        bump,
+    )]
+    pub beneficiary_staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_DETAIL_SEED, beneficiary_staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        constraint = stake_detail.grantor == grantor.key() @ErrorCode::IncorrectAuthority,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    #[account(
+        init_if_needed,
+        payer = grantor,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = grantor,
+    )]
+    pub grantor_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = associated_token::ID)]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RevokeGrant<'info> {
+    /// Return the unvested portion to grantor, the vested portion stays locked for beneficiary.
+    /// Vote locks of the beneficiary do not block it
+    pub fn process(&mut self, _: u64, _: u64) -> Result<()> {
+        let staker_info = &mut self.beneficiary_staker_info;
+        let vault = &mut self.vault;
+        let stake_detail = &mut self.stake_detail;
+        let stake_config = &mut self.stake_config;
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        if !stake_detail.is_revocable_grant(current_timestamp) {
+            return Err(ErrorCode::GrantNotRevocable.into());
+        }
+
+        let unvested_amount = get_unvested_amount(
+            stake_detail.stake_amount,
+            stake_detail.vesting_start,
+            stake_detail.unstaked_at_time,
+            current_timestamp,
+        )?;
+
+        // penalty share of the unvested portion goes back with it, like split_position
+        let moved_penalty_debt = proportion(
+            stake_detail.penalty_debt,
+            unvested_amount,
+            stake_detail.stake_amount,
+        );
+        let penalty_share = vault
+            .penalty_debt(unvested_amount)?
+            .saturating_sub(moved_penalty_debt) as u64;
+        vault.pay_penalty_share(penalty_share);
+
+        // update stake detail, revocation is one-off
+        stake_config.remove_voting_power(stake_detail, vault.lock_period, current_timestamp);
+        stake_detail.stake_amount -= unvested_amount;
+        stake_detail.penalty_debt -= moved_penalty_debt;
+        stake_detail.revocable = false;
+        stake_config.add_voting_power(stake_detail, vault.lock_period, current_timestamp);
+
+        // update staker info
+        staker_info.total_stake -= unvested_amount;
+        staker_info.write_checkpoint(current_timestamp);
+
+        // update vault
+        vault.total_staked -= unvested_amount;
+        emit_position_update(vault, stake_detail, current_timestamp);
+
+        token_transfer_with_signer(
+            self.vault_token_account.to_account_info(),
+            vault.to_account_info(),
+            self.grantor_token_account.to_account_info(),
+            &self.token_program,
+            &[&vault.auth_seeds(&vault.lock_period.to_le_bytes())],
+            unvested_amount + penalty_share,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// grants vest linearly between vesting_start and unstaked_at_time
+fn get_unvested_amount(
+    amount: u64,
+    vesting_start: i64,
+    unstaked_at_time: i64,
+    current_timestamp: i64,
+) -> Result<u64> {
+    if current_timestamp >= unstaked_at_time || unstaked_at_time <= vesting_start {
+        return Ok(0);
+    }
+
+    let remaining = (unstaked_at_time - std::cmp::max(current_timestamp, vesting_start)) as u128;
+    Ok((amount as u128)
+        .checked_mul(remaining)
+        .ok_or(ErrorCode::OverflowError)?
+        .checked_div((unstaked_at_time - vesting_start) as u128)
+        .ok_or(ErrorCode::OverflowError)? as u64)
+}
@@ -0,0 +1,82 @@
+use crate::{
+    constant::constants::{STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED},
+    state::StakerInfo,
+    StakeConfig, StakeDetail, Vault,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use solana_program::clock::Clock;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(id: u64, lock_period: u64)]
+pub struct SetAutoRenew<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        constraint = !stake_detail.is_tokenized() @ErrorCode::PositionTokenized,
+        constraint = !stake_detail.is_unbonding() @ErrorCode::PositionUnbonding,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> SetAutoRenew<'info> {
+    pub fn process(&mut self, _: u64, _: u64, auto_renew: bool) -> Result<()> {
+        let vault = &self.vault;
+        let stake_detail = &mut self.stake_detail;
+        let stake_config = &mut self.stake_config;
+
+        if stake_detail.stake_amount == 0 {
+            return Err(ErrorCode::NotStaked.into());
+        }
+
+        if auto_renew && vault.renew_window == 0 {
+            return Err(ErrorCode::AutoRenewNotEnabled.into());
+        }
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        if stake_detail.is_revocable_grant(current_timestamp) {
+            return Err(ErrorCode::PositionRevocable.into());
+        }
+
+        // when disabling, the position matures at the end of the current cycle
+        stake_config.remove_voting_power(stake_detail, vault.lock_period, current_timestamp);
+        stake_detail.unstaked_at_time = stake_detail.unlock_time(vault, current_timestamp);
+        stake_detail.auto_renew = auto_renew;
+        if auto_renew {
+            stake_detail.renew_window = vault.renew_window;
+        }
+        stake_config.add_voting_power(stake_detail, vault.lock_period, current_timestamp);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{BPS_DENOMINATOR, STAKE_CONFIG_SEED, VAULT_SEED},
+    StakeConfig, Vault,
+};
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64)]
+pub struct SetEarlyUnstakeConfig<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: must match authority of stake_config
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+}
+
+impl<'info> SetEarlyUnstakeConfig<'info> {
+    pub fn process(
+        &mut self,
+        _: u64,
+        allow_early_unstake: bool,
+        max_early_unstake_penalty_bps: u16,
+        redistribute_penalty: bool,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        if max_early_unstake_penalty_bps as u64 > BPS_DENOMINATOR {
+            return Err(ErrorCode::InvalidPenaltyBps.into());
+        }
+
+        let vault = &mut self.vault;
+        vault.allow_early_unstake = allow_early_unstake;
+        vault.max_early_unstake_penalty_bps = max_early_unstake_penalty_bps;
+        vault.redistribute_penalty = redistribute_penalty;
+        vault.treasury = treasury;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::constant::constants::{VE_EPOCH, VE_SLOPE_CHANGES};
+use crate::error::ErrorCode;
+use crate::{constant::constants::STAKE_CONFIG_SEED, StakeConfig};
+
+#[derive(Accounts)]
+pub struct SetLockPeriodBounds<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: must match authority of stake_config
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> SetLockPeriodBounds<'info> {
+    /// Bounds only apply to new vaults. Voting power slope changes are only tracked
+    /// VE_SLOPE_CHANGES epochs ahead, longer locks are rejected
+    pub fn process(&mut self, min_lock_period: u64, max_lock_period: u64) -> Result<()> {
+        if min_lock_period == 0
+            || min_lock_period > max_lock_period
+            || max_lock_period > VE_EPOCH as u64 * VE_SLOPE_CHANGES as u64
+        {
+            return Err(ErrorCode::IncorrectLockPeriod.into());
+        }
+
+        let stake_config = &mut self.stake_config;
+        stake_config.min_lock_period = min_lock_period;
+        stake_config.max_lock_period = max_lock_period;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{
+    constant::constants::{BPS_DENOMINATOR, OPERATOR_SEED, STAKE_CONFIG_SEED},
+    Operator, StakeConfig,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SetOperatorConfig<'info> {
+    /// operator wallet
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [OPERATOR_SEED, stake_config.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub operator: Box<Account<'info, Operator>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> SetOperatorConfig<'info> {
+    /// a new commission only applies to new delegations. Whether the operator takes new
+    /// delegations is up to the authority, see set_operator_status
+    pub fn process(&mut self, commission_bps: u16) -> Result<()> {
+        if commission_bps as u64 > BPS_DENOMINATOR {
+            return Err(ErrorCode::InvalidCommissionBps.into());
+        }
+
+        self.operator.commission_bps = commission_bps;
+
+        Ok(())
+    }
+}
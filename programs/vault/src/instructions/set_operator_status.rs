@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{OPERATOR_SEED, STAKE_CONFIG_SEED},
+    Operator, StakeConfig,
+};
+
+#[derive(Accounts)]
+#[instruction(operator_wallet: Pubkey)]
+pub struct SetOperatorStatus<'info> {
+    /// operators are approved by the authority of stake_config
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [OPERATOR_SEED, stake_config.key().as_ref(), operator_wallet.as_ref()],
+        bump,
+    )]
+    pub operator: Box<Account<'info, Operator>>,
+}
+
+impl<'info> SetOperatorStatus<'info> {
+    /// inactive operators take no new delegations, existing ones can still be undelegated
+    pub fn process(&mut self, _: Pubkey, is_active: bool) -> Result<()> {
+        self.operator.is_active = is_active;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::constant::constants::MAX_RECEIPT_URI_LEN;
+use crate::error::ErrorCode;
+use crate::{constant::constants::STAKE_CONFIG_SEED, StakeConfig};
+
+#[derive(Accounts)]
+pub struct SetReceiptUri<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: must match authority of stake_config
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> SetReceiptUri<'info> {
+    /// Uri only applies to receipts minted afterwards, metadata is not updated retroactively
+    pub fn process(&mut self, receipt_uri: String) -> Result<()> {
+        if receipt_uri.len() > MAX_RECEIPT_URI_LEN {
+            return Err(ErrorCode::InvalidReceiptUri.into());
+        }
+
+        self.stake_config.receipt_uri = receipt_uri;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    StakeConfig, Vault,
+};
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64)]
+pub struct SetRenewWindow<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: must match authority of stake_config
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+}
+
+impl<'info> SetRenewWindow<'info> {
+    /// Positions already auto-renewed keep the window they opted in with
+    pub fn process(&mut self, _: u64, renew_window: u64) -> Result<()> {
+        let vault = &mut self.vault;
+        if renew_window >= vault.lock_period {
+            return Err(ErrorCode::InvalidRenewWindow.into());
+        }
+
+        vault.renew_window = renew_window;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    StakeConfig, Vault,
+};
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64)]
+pub struct SetUnbondingPeriod<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: must match authority of stake_config
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+}
+
+impl<'info> SetUnbondingPeriod<'info> {
+    /// Positions already unbonding use the new period when withdrawing
+    pub fn process(&mut self, _: u64, unbonding_period: u64) -> Result<()> {
+        self.vault.unbonding_period = unbonding_period;
+
+        Ok(())
+    }
+}
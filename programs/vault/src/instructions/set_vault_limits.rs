@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, VAULT_SEED},
+    StakeConfig, Vault,
+};
+
+#[derive(Accounts)]
+#[instruction(lock_period: u64)]
+pub struct SetVaultLimits<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: must match authority of stake_config
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+}
+
+impl<'info> SetVaultLimits<'info> {
+    /// Limits only apply to new stakes, existing positions above them are kept
+    pub fn process(
+        &mut self,
+        _: u64,
+        max_total_staked: u64,
+        max_stake_per_staker: u64,
+        min_stake_amount: u64,
+    ) -> Result<()> {
+        let vault = &mut self.vault;
+        vault.max_total_staked = max_total_staked;
+        vault.max_stake_per_staker = max_stake_per_staker;
+        vault.min_stake_amount = min_stake_amount;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::{constant::constants::STAKE_CONFIG_SEED, StakeConfig};
+
+#[derive(Accounts)]
+pub struct SetVoteLockPeriod<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: must match authority of stake_config
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+        constraint = stake_config.authority == authority.key() @ErrorCode::IncorrectAuthority
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> SetVoteLockPeriod<'info> {
+    /// Positions already locked by a vote keep their lock
+    pub fn process(&mut self, vote_lock_period: u64) -> Result<()> {
+        self.stake_config.vote_lock_period = vote_lock_period;
+
+        Ok(())
+    }
+}
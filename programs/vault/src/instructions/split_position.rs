@@ -0,0 +1,128 @@
+use crate::{
+    constant::constants::{
+        ACCOUNT_VERSION, STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED,
+    },
+    event::emit_position_update,
+    state::StakerInfo,
+    utils::proportion,
+    StakeConfig, StakeDetail, Vault, STAKE_DETAIL_SIZE,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+use solana_program::clock::Clock;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(id: u64, lock_period: u64)]
+pub struct SplitPosition<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        constraint = !stake_detail.is_tokenized() @ErrorCode::PositionTokenized,
+        constraint = !stake_detail.is_unbonding() @ErrorCode::PositionUnbonding,
+        constraint = !stake_detail.is_delegated() @ErrorCode::PositionDelegated,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    #[account(
+        init,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &(staker_info.current_id + 1).to_le_bytes()],
+        bump,
+        payer = signer,
+        space = STAKE_DETAIL_SIZE
+    )]
+    pub new_stake_detail: Box<Account<'info, StakeDetail>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SplitPosition<'info> {
+    pub fn process(&mut self, _: u64, _: u64, amount: u64) -> Result<()> {
+        let staker_info = &mut self.staker_info;
+        let stake_detail = &mut self.stake_detail;
+        let new_stake_detail = &mut self.new_stake_detail;
+        let stake_config = &mut self.stake_config;
+        let lock_period = self.vault.lock_period;
+
+        if amount == 0 || amount >= stake_detail.stake_amount {
+            return Err(ErrorCode::InvalidSplitAmount.into());
+        }
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        if stake_detail.is_revocable_grant(current_timestamp) {
+            return Err(ErrorCode::PositionRevocable.into());
+        }
+
+        // both parts must stay above the vault minimum
+        let min_stake_amount = self.vault.min_stake_amount;
+        if amount < min_stake_amount || stake_detail.stake_amount - amount < min_stake_amount {
+            return Err(ErrorCode::StakeAmountTooLow.into());
+        }
+
+        // move penalty debt pro-rata so both positions keep their pending penalty share
+        let moved_penalty_debt =
+            proportion(stake_detail.penalty_debt, amount, stake_detail.stake_amount);
+
+        stake_config.remove_voting_power(stake_detail, lock_period, current_timestamp);
+
+        // update new stake detail, unlock time is unchanged
+        new_stake_detail.unstaked_at_time = stake_detail.unstaked_at_time;
+        new_stake_detail.stake_amount = amount;
+        new_stake_detail.id = staker_info.current_id + 1;
+        new_stake_detail.staker = stake_detail.staker;
+        new_stake_detail.version = ACCOUNT_VERSION;
+        new_stake_detail.penalty_debt = moved_penalty_debt;
+        new_stake_detail.auto_renew = stake_detail.auto_renew;
+        new_stake_detail.renew_window = stake_detail.renew_window;
+        new_stake_detail.vote_locked_until = stake_detail.vote_locked_until;
+        new_stake_detail.grantor = stake_detail.grantor;
+        new_stake_detail.vesting_start = stake_detail.vesting_start;
+
+        // update stake detail
+        stake_detail.stake_amount -= amount;
+        stake_detail.penalty_debt -= moved_penalty_debt;
+
+        stake_config.add_voting_power(stake_detail, lock_period, current_timestamp);
+        stake_config.add_voting_power(new_stake_detail, lock_period, current_timestamp);
+        emit_position_update(&self.vault, stake_detail, current_timestamp);
+        emit_position_update(&self.vault, new_stake_detail, current_timestamp);
+
+        // update staker info
+        staker_info.current_id += 1;
+        staker_info.open_positions += 1;
+
+        Ok(())
+    }
+}
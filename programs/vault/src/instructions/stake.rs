@@ -1,8 +1,11 @@
 use crate::{
-    constant::constants::{STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED},
+    constant::constants::{
+        ACCOUNT_VERSION, STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED,
+    },
+    event::emit_position_update,
     state::StakerInfo,
     utils::token_transfer_user,
-    StakeConfig, StakeDetail, Vault, STAKER_INFO_SIZE, STAKE_DETAIL_SIZE,
+    Operator, StakeConfig, StakeDetail, Vault, STAKER_INFO_SIZE, STAKE_DETAIL_SIZE,
 };
 use anchor_lang::{prelude::*, system_program};
 use anchor_spl::{
@@ -21,6 +24,7 @@ pub struct Stake<'info> {
     pub signer: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
         bump,
     )]
@@ -70,6 +74,10 @@ pub struct Stake<'info> {
     )]
     pub user_token_account: Box<Account<'info, TokenAccount>>,
 
+    /// optional operator the new position is delegated to
+    #[account(mut)]
+    pub operator: Option<Box<Account<'info, Operator>>>,
+
     // CHECK: the SPL token for staking, not rewarding
     pub stake_currency_mint: Box<Account<'info, Mint>>,
 
@@ -86,10 +94,13 @@ impl<'info> Stake<'info> {
         let staker_info = &mut self.staker_info_pda;
         let vault = &mut self.vault;
         let stake_detail = &mut self.stake_detail_pda;
-        if amount <= 0 {
+        let stake_config = &mut self.stake_config;
+        if amount == 0 {
             return Err(ErrorCode::NoTokens.into());
         }
 
+        vault.check_new_stake(staker_info.total_stake, amount)?;
+
         let clock = Clock::get()?;
         let current_timestamp = clock.unix_timestamp;
 
@@ -98,13 +109,35 @@ impl<'info> Stake<'info> {
         stake_detail.stake_amount = amount;
         stake_detail.id = staker_info.current_id + 1;
         stake_detail.staker = self.signer.key();
+        stake_detail.version = ACCOUNT_VERSION;
+        stake_detail.penalty_debt = vault.penalty_debt(amount)?;
+        stake_config.add_voting_power(stake_detail, vault.lock_period, current_timestamp);
+
+        // same as delegate, the operator is paid on penalties redistributed from now on
+        if let Some(operator) = self.operator.as_mut() {
+            if operator.stake_config != stake_config.key() {
+                return Err(ErrorCode::InvalidOperator.into());
+            }
+            if !operator.is_active {
+                return Err(ErrorCode::OperatorInactive.into());
+            }
+
+            stake_detail.delegate_to = operator.operator;
+            stake_detail.delegation_commission_bps = operator.commission_bps;
+            stake_detail.delegation_acc_penalty_per_share = vault.acc_penalty_per_share;
+            operator.delegated_stake += amount;
+        }
 
         // update staker info
         staker_info.total_stake += amount;
+        staker_info.write_checkpoint(current_timestamp);
         staker_info.current_id += 1;
+        staker_info.version = ACCOUNT_VERSION;
+        staker_info.open_positions += 1;
 
         // update vault
         vault.total_staked += amount;
+        emit_position_update(vault, stake_detail, current_timestamp);
 
         // transfer(cpi_ctx, stake_amount)?;
         token_transfer_user(
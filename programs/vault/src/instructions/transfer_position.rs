@@ -0,0 +1,142 @@
+use crate::{
+    constant::constants::{
+        ACCOUNT_VERSION, STAKER_INFO_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED, VAULT_SEED,
+    },
+    event::{emit_position_moved, emit_position_update},
+    state::StakerInfo,
+    StakeConfig, StakeDetail, Vault, STAKER_INFO_SIZE, STAKE_DETAIL_SIZE,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+use solana_program::clock::Clock;
+
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(id: u64, lock_period: u64)]
+pub struct TransferPosition<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: any wallet can receive a position
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        constraint = !stake_detail.is_tokenized() @ErrorCode::PositionTokenized,
+        constraint = !stake_detail.is_unbonding() @ErrorCode::PositionUnbonding,
+        constraint = !stake_detail.is_delegated() @ErrorCode::PositionDelegated,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), recipient.key.as_ref()],
+        bump,
+        payer = signer,
+        space = STAKER_INFO_SIZE,
+    )]
+    pub recipient_staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        init,
+        seeds = [STAKE_DETAIL_SEED, recipient_staker_info.key().as_ref(), &(recipient_staker_info.current_id + 1).to_le_bytes()],
+        bump,
+        payer = signer,
+        space = STAKE_DETAIL_SIZE
+    )]
+    pub recipient_stake_detail: Box<Account<'info, StakeDetail>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TransferPosition<'info> {
+    pub fn process(&mut self, _: u64, _: u64) -> Result<()> {
+        let staker_info = &mut self.staker_info;
+        let stake_detail = &self.stake_detail;
+        let recipient_staker_info = &mut self.recipient_staker_info;
+        let recipient_stake_detail = &mut self.recipient_stake_detail;
+
+        if stake_detail.stake_amount == 0 {
+            return Err(ErrorCode::NotStaked.into());
+        }
+
+        if self.recipient.key() == self.signer.key() {
+            return Err(ErrorCode::InvalidRecipient.into());
+        }
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        if stake_detail.is_vote_locked(current_timestamp) {
+            return Err(ErrorCode::PositionVoteLocked.into());
+        }
+        if stake_detail.is_revocable_grant(current_timestamp) {
+            return Err(ErrorCode::PositionRevocable.into());
+        }
+
+        let max_stake_per_staker = self.vault.max_stake_per_staker;
+        if max_stake_per_staker > 0
+            && recipient_staker_info.total_stake + stake_detail.stake_amount > max_stake_per_staker
+        {
+            return Err(ErrorCode::StakerCapExceeded.into());
+        }
+
+        // update recipient stake detail, the lock is preserved
+        recipient_stake_detail.unstaked_at_time = stake_detail.unstaked_at_time;
+        recipient_stake_detail.stake_amount = stake_detail.stake_amount;
+        recipient_stake_detail.id = recipient_staker_info.current_id + 1;
+        recipient_stake_detail.staker = self.recipient.key();
+        recipient_stake_detail.version = ACCOUNT_VERSION;
+        recipient_stake_detail.penalty_debt = stake_detail.penalty_debt;
+        recipient_stake_detail.auto_renew = stake_detail.auto_renew;
+        recipient_stake_detail.renew_window = stake_detail.renew_window;
+        // voting power moves along, totals are unchanged
+        recipient_stake_detail.ve_slope = stake_detail.ve_slope;
+        recipient_stake_detail.ve_end = stake_detail.ve_end;
+        recipient_stake_detail.grantor = stake_detail.grantor;
+        recipient_stake_detail.vesting_start = stake_detail.vesting_start;
+
+        // update staker infos
+        staker_info.total_stake -= stake_detail.stake_amount;
+        recipient_staker_info.total_stake += stake_detail.stake_amount;
+        staker_info.write_checkpoint(current_timestamp);
+        recipient_staker_info.write_checkpoint(current_timestamp);
+        recipient_staker_info.current_id += 1;
+        recipient_staker_info.version = ACCOUNT_VERSION;
+        recipient_staker_info.open_positions += 1;
+        staker_info.open_positions -= 1;
+
+        emit_position_moved(&self.vault, stake_detail, current_timestamp);
+        emit_position_update(&self.vault, recipient_stake_detail, current_timestamp);
+
+        Ok(())
+    }
+}
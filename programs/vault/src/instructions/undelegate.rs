@@ -0,0 +1,110 @@
+use crate::{
+    constant::constants::{OPERATOR_SEED, STAKE_CONFIG_SEED, STAKE_DETAIL_SEED},
+    state::StakerInfo,
+    utils::token_transfer_with_signer,
+    Operator, StakeConfig, StakeDetail, Vault,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::constant::constants::{STAKER_INFO_SEED, VAULT_SEED};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(id: u64, lock_period: u64)]
+pub struct Undelegate<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        constraint = stake_detail.is_delegated() @ErrorCode::PositionNotDelegated,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    #[account(
+        mut,
+        seeds = [OPERATOR_SEED, stake_config.key().as_ref(), stake_detail.delegate_to.as_ref()],
+        bump,
+    )]
+    pub operator: Box<Account<'info, Operator>>,
+
+    #[account(
+        mut,
+        token::mint = stake_currency_mint,
+        token::authority = stake_detail.delegate_to,
+    )]
+    pub operator_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Undelegate<'info> {
+    /// works for inactive operators and vote locked positions too, so delegators are never
+    /// stuck. The vote lock stays on the position
+    pub fn process(&mut self, _: u64, _: u64) -> Result<()> {
+        let vault = &mut self.vault;
+        let stake_detail = &mut self.stake_detail;
+        let operator = &mut self.operator;
+
+        let commission = stake_detail.settle_delegation(vault);
+        vault.pay_penalty_share(commission);
+        if commission > 0 {
+            token_transfer_with_signer(
+                self.vault_token_account.to_account_info(),
+                vault.to_account_info(),
+                self.operator_token_account.to_account_info(),
+                &self.token_program,
+                &[&vault.auth_seeds(&vault.lock_period.to_le_bytes())],
+                commission,
+            )?;
+        }
+
+        // update operator
+        operator.delegated_stake -= stake_detail.stake_amount;
+
+        // update stake detail
+        stake_detail.delegate_to = Pubkey::default();
+        stake_detail.delegation_commission_bps = 0;
+        stake_detail.delegation_acc_penalty_per_share = 0;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    constant::constants::{MAX_VOTER_WEIGHT_RECORD_SEED, STAKE_CONFIG_SEED},
+    MaxVoterWeightRecord, StakeConfig,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use solana_program::clock::Clock;
+
+#[derive(Accounts)]
+#[instruction(realm: Pubkey)]
+pub struct UpdateMaxVoterWeightRecord<'info> {
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            MAX_VOTER_WEIGHT_RECORD_SEED,
+            realm.as_ref(),
+            stake_currency_mint.key().as_ref()
+        ],
+        bump,
+    )]
+    pub max_voter_weight_record: Box<Account<'info, MaxVoterWeightRecord>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> UpdateMaxVoterWeightRecord<'info> {
+    /// permissionless, the max weight only depends on the stake config
+    pub fn process(&mut self, _: Pubkey) -> Result<()> {
+        let clock = Clock::get()?;
+
+        let max_voter_weight_record = &mut self.max_voter_weight_record;
+        max_voter_weight_record.max_voter_weight =
+            self.stake_config.total_voting_power(clock.unix_timestamp);
+        max_voter_weight_record.max_voter_weight_expiry = Some(clock.slot);
+
+        Ok(())
+    }
+}
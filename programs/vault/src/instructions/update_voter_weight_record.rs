@@ -0,0 +1,129 @@
+use crate::{
+    constant::constants::{
+        GOVERNANCE_PROPOSAL_TYPES, GOVERNANCE_REALM_TYPES, GOVERNANCE_TYPES, PROPOSAL_STATE_VOTING,
+        STAKE_CONFIG_SEED, VOTER_WEIGHT_RECORD_SEED,
+    },
+    error::ErrorCode,
+    instructions::owner_voting_power,
+    StakeConfig, StakeDetail, VoterWeightAction, VoterWeightRecord,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use solana_program::clock::Clock;
+
+#[derive(Accounts)]
+#[instruction(realm: Pubkey)]
+pub struct UpdateVoterWeightRecord<'info> {
+    /// only the owner can update, a partial list of positions would lower the weight
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VOTER_WEIGHT_RECORD_SEED,
+            realm.as_ref(),
+            stake_currency_mint.key().as_ref(),
+            signer.key.as_ref()
+        ],
+        bump,
+    )]
+    pub voter_weight_record: Box<Account<'info, VoterWeightRecord>>,
+
+    /// CHECK: spl-governance realm, its owner is the governance program. Required for CastVote
+    pub governance_realm: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: governance of the proposal, validated in process
+    pub governance: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: proposal voted on, validated in process
+    pub proposal: Option<UncheckedAccount<'info>>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+}
+
+impl<'info> UpdateVoterWeightRecord<'info> {
+    /// remaining_accounts: (vault, stake detail) pairs of signer, same as get_voting_power.
+    /// Meant to be called right before the spl-governance instruction in the same transaction,
+    /// which checks `action` and `target` (the proposal of a vote).
+    /// For CastVote the stake details must be writable, they are locked for vote_lock_period
+    /// so the same voting power cannot be moved to another voter while the vote is open.
+    /// The lock needs `target` to be a proposal of the realm in voting state, a realm of
+    /// another program can only lock the signer's own positions
+    pub fn process(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        realm: Pubkey,
+        action: VoterWeightAction,
+        target: Option<Pubkey>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let voter_weight = owner_voting_power(
+            self.stake_config.key(),
+            remaining_accounts,
+            self.signer.key(),
+            clock.unix_timestamp,
+        )?;
+
+        if action == VoterWeightAction::CastVote {
+            self.check_proposal(realm, target)?;
+            let locked_until = clock.unix_timestamp + self.stake_config.vote_lock_period as i64;
+            // pairs were validated by owner_voting_power
+            for stake_detail_info in remaining_accounts.iter().skip(1).step_by(2) {
+                let mut stake_detail = Account::<StakeDetail>::try_from(stake_detail_info)?;
+                stake_detail.vote_locked_until =
+                    std::cmp::max(stake_detail.vote_locked_until, locked_until);
+                stake_detail.exit(&crate::ID)?;
+            }
+        }
+
+        let voter_weight_record = &mut self.voter_weight_record;
+        voter_weight_record.voter_weight = voter_weight;
+        voter_weight_record.voter_weight_expiry = Some(clock.slot);
+        voter_weight_record.weight_action = Some(action);
+        voter_weight_record.weight_action_target = target;
+
+        Ok(())
+    }
+
+    /// `target` must be a proposal in voting state on the stake currency, under a governance
+    /// of the realm, all owned by the program owning the realm
+    fn check_proposal(&self, realm_key: Pubkey, target: Option<Pubkey>) -> Result<()> {
+        let (Some(realm), Some(governance), Some(proposal)) =
+            (&self.governance_realm, &self.governance, &self.proposal)
+        else {
+            return Err(ErrorCode::ProposalRequired.into());
+        };
+
+        let governance_program = realm.owner;
+        let realm_data = realm.try_borrow_data()?;
+        let governance_data = governance.try_borrow_data()?;
+        let proposal_data = proposal.try_borrow_data()?;
+
+        let valid_realm = realm.key() == realm_key
+            && realm_data
+                .first()
+                .is_some_and(|account_type| GOVERNANCE_REALM_TYPES.contains(account_type));
+        let valid_governance = governance.owner == governance_program
+            && governance_data.len() >= 33
+            && GOVERNANCE_TYPES.contains(&governance_data[0])
+            && governance_data[1..33] == realm.key().to_bytes();
+        let valid_proposal = target == Some(proposal.key())
+            && proposal.owner == governance_program
+            && proposal_data.len() >= 66
+            && GOVERNANCE_PROPOSAL_TYPES.contains(&proposal_data[0])
+            && proposal_data[1..33] == governance.key().to_bytes()
+            && proposal_data[33..65] == self.stake_currency_mint.key().to_bytes()
+            && proposal_data[65] == PROPOSAL_STATE_VOTING;
+        if !valid_realm || !valid_governance || !valid_proposal {
+            return Err(ErrorCode::InvalidProposal.into());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    constant::constants::{STAKE_CONFIG_SEED, STAKE_DETAIL_SEED},
+    event::emit_position_update,
+    state::StakerInfo,
+    utils::token_transfer_with_signer,
+    StakeConfig, StakeDetail, Vault,
+};
+use anchor_lang::{prelude::*, system_program, AccountsClose};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+use solana_program::clock::Clock;
+
+use crate::constant::constants::{STAKER_INFO_SEED, VAULT_SEED};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(id: u64, lock_period: u64,)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [STAKE_CONFIG_SEED, stake_currency_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_config: Box<Account<'info, StakeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_SEED,
+            stake_config.key().as_ref(),
+            &lock_period.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [STAKER_INFO_SEED, vault.key().as_ref(), signer.key.as_ref()],
+        bump,
+    )]
+    pub staker_info: Box<Account<'info, StakerInfo>>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_DETAIL_SEED, staker_info.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        constraint = stake_detail.is_unbonding() @ErrorCode::PositionNotUnbonding,
+        constraint = !stake_detail.is_tokenized() @ErrorCode::PositionTokenized,
+    )]
+    pub stake_detail: Box<Account<'info, StakeDetail>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_currency_mint,
+        associated_token::authority = signer,
+    )]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    pub stake_currency_mint: Account<'info, Mint>,
+
+    #[account(address = associated_token::ID)]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Withdraw<'info> {
+    pub fn process(&mut self, _: u64, _: u64, auto_close: bool) -> Result<()> {
+        let staker_info = &mut self.staker_info;
+        let vault = &mut self.vault;
+        let stake_detail = &mut self.stake_detail;
+
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        if current_timestamp < stake_detail.unbonding_started_at + vault.unbonding_period as i64 {
+            return Err(ErrorCode::UnbondingTimeNotOverYet.into());
+        }
+
+        let unstake_amount = stake_detail.stake_amount;
+
+        // update stake detail
+        stake_detail.stake_amount = 0;
+        stake_detail.unbonding_started_at = 0;
+
+        // update staker info
+        staker_info.total_stake -= unstake_amount;
+        staker_info.write_checkpoint(current_timestamp);
+
+        // update vault
+        vault.total_unbonding -= unstake_amount;
+        emit_position_update(vault, stake_detail, current_timestamp);
+
+        // transfer to user
+        token_transfer_with_signer(
+            self.vault_token_account.to_account_info(),
+            vault.to_account_info(),
+            self.staker_token_account.to_account_info(),
+            &self.token_program,
+            &[&vault.auth_seeds(&vault.lock_period.to_le_bytes())],
+            unstake_amount,
+        )?;
+
+        // return rent of drained stake detail to signer, same as close_position
+        if auto_close {
+            staker_info.open_positions -= 1;
+            stake_detail.close(self.signer.to_account_info())?;
+        }
+
+        Ok(())
+    }
+}
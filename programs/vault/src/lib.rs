@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 
-mod constant;
+pub mod constant;
 mod error;
+pub mod event;
 pub mod state;
 pub use state::*;
 pub mod instructions;
@@ -22,11 +23,303 @@ pub mod vault {
         ctx.accounts.process(lock_period, amount)
     }
 
-    pub fn destake(ctx: Context<DeStake>, id: u64, lock_period: u64, amount: u64) -> Result<()> {
-        ctx.accounts.process(id, lock_period, amount)
+    pub fn destake(
+        ctx: Context<DeStake>,
+        id: u64,
+        lock_period: u64,
+        amount: u64,
+        auto_close: bool,
+    ) -> Result<()> {
+        ctx.accounts.process(id, lock_period, amount, auto_close)
     }
 
     pub fn create_vault(ctx: Context<CreateVault>, lock_period: u64) -> Result<()> {
         ctx.accounts.process(lock_period, ctx.bumps.vault)
     }
+
+    pub fn early_destake(
+        ctx: Context<EarlyDeStake>,
+        id: u64,
+        lock_period: u64,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(id, lock_period, amount)
+    }
+
+    pub fn set_early_unstake_config(
+        ctx: Context<SetEarlyUnstakeConfig>,
+        lock_period: u64,
+        allow_early_unstake: bool,
+        max_early_unstake_penalty_bps: u16,
+        redistribute_penalty: bool,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.process(
+            lock_period,
+            allow_early_unstake,
+            max_early_unstake_penalty_bps,
+            redistribute_penalty,
+            treasury,
+        )
+    }
+
+    pub fn merge_positions<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MergePositions<'info>>,
+        lock_period: u64,
+        target_id: u64,
+        source_ids: Vec<u64>,
+    ) -> Result<()> {
+        ctx.accounts
+            .process(ctx.remaining_accounts, lock_period, target_id, source_ids)
+    }
+
+    pub fn split_position(
+        ctx: Context<SplitPosition>,
+        id: u64,
+        lock_period: u64,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(id, lock_period, amount)
+    }
+
+    pub fn transfer_position(
+        ctx: Context<TransferPosition>,
+        id: u64,
+        lock_period: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(id, lock_period)
+    }
+
+    pub fn mint_position_receipt(
+        ctx: Context<MintPositionReceipt>,
+        id: u64,
+        lock_period: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(id, lock_period, ctx.bumps.receipt)
+    }
+
+    pub fn destake_with_receipt(ctx: Context<DeStakeWithReceipt>, lock_period: u64) -> Result<()> {
+        ctx.accounts.process(lock_period)
+    }
+
+    pub fn destake_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DeStakeMany<'info>>,
+        lock_period: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(ctx.remaining_accounts, lock_period)
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>, id: u64, lock_period: u64) -> Result<()> {
+        ctx.accounts.process(id, lock_period)
+    }
+
+    pub fn close_staker_info(ctx: Context<CloseStakerInfo>, lock_period: u64) -> Result<()> {
+        ctx.accounts.process(lock_period)
+    }
+
+    pub fn set_vault_limits(
+        ctx: Context<SetVaultLimits>,
+        lock_period: u64,
+        max_total_staked: u64,
+        max_stake_per_staker: u64,
+        min_stake_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(
+            lock_period,
+            max_total_staked,
+            max_stake_per_staker,
+            min_stake_amount,
+        )
+    }
+
+    pub fn set_lock_period_bounds(
+        ctx: Context<SetLockPeriodBounds>,
+        min_lock_period: u64,
+        max_lock_period: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(min_lock_period, max_lock_period)
+    }
+
+    pub fn set_vault_status(
+        ctx: Context<SetVaultStatus>,
+        lock_period: u64,
+        is_active: bool,
+    ) -> Result<()> {
+        ctx.accounts.process(lock_period, is_active)
+    }
+
+    pub fn close_vault(ctx: Context<CloseVault>, lock_period: u64) -> Result<()> {
+        ctx.accounts.process(lock_period)
+    }
+
+    pub fn set_unbonding_period(
+        ctx: Context<SetUnbondingPeriod>,
+        lock_period: u64,
+        unbonding_period: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(lock_period, unbonding_period)
+    }
+
+    pub fn request_unstake(ctx: Context<RequestUnstake>, id: u64, lock_period: u64) -> Result<()> {
+        ctx.accounts.process(id, lock_period)
+    }
+
+    pub fn withdraw(
+        ctx: Context<Withdraw>,
+        id: u64,
+        lock_period: u64,
+        auto_close: bool,
+    ) -> Result<()> {
+        ctx.accounts.process(id, lock_period, auto_close)
+    }
+
+    pub fn set_renew_window(
+        ctx: Context<SetRenewWindow>,
+        lock_period: u64,
+        renew_window: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(lock_period, renew_window)
+    }
+
+    pub fn enable_auto_renew(ctx: Context<SetAutoRenew>, id: u64, lock_period: u64) -> Result<()> {
+        ctx.accounts.process(id, lock_period, true)
+    }
+
+    pub fn disable_auto_renew(ctx: Context<SetAutoRenew>, id: u64, lock_period: u64) -> Result<()> {
+        ctx.accounts.process(id, lock_period, false)
+    }
+
+    pub fn grant_stake(
+        ctx: Context<GrantStake>,
+        lock_period: u64,
+        amount: u64,
+        revocable: bool,
+    ) -> Result<()> {
+        ctx.accounts.process(lock_period, amount, revocable)
+    }
+
+    pub fn revoke_grant(ctx: Context<RevokeGrant>, id: u64, lock_period: u64) -> Result<()> {
+        ctx.accounts.process(id, lock_period)
+    }
+
+    pub fn get_voting_power<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetVotingPower<'info>>,
+        owner: Pubkey,
+        timestamp: i64,
+    ) -> Result<u64> {
+        ctx.accounts
+            .process(ctx.remaining_accounts, owner, timestamp)
+    }
+
+    pub fn get_total_voting_power(
+        ctx: Context<GetTotalVotingPower>,
+        timestamp: i64,
+    ) -> Result<u64> {
+        ctx.accounts.process(timestamp)
+    }
+
+    pub fn create_voter_weight_record(
+        ctx: Context<CreateVoterWeightRecord>,
+        realm: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.process(realm)
+    }
+
+    pub fn update_voter_weight_record<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateVoterWeightRecord<'info>>,
+        realm: Pubkey,
+        action: VoterWeightAction,
+        target: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts
+            .process(ctx.remaining_accounts, realm, action, target)
+    }
+
+    pub fn create_max_voter_weight_record(
+        ctx: Context<CreateMaxVoterWeightRecord>,
+        realm: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.process(realm)
+    }
+
+    pub fn update_max_voter_weight_record(
+        ctx: Context<UpdateMaxVoterWeightRecord>,
+        realm: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.process(realm)
+    }
+
+    pub fn get_twab(
+        ctx: Context<GetTwab>,
+        owner: Pubkey,
+        lock_period: u64,
+        start: i64,
+        end: i64,
+    ) -> Result<u64> {
+        ctx.accounts.process(owner, lock_period, start, end)
+    }
+
+    pub fn register_operator(ctx: Context<RegisterOperator>, commission_bps: u16) -> Result<()> {
+        ctx.accounts.process(commission_bps, ctx.bumps.operator)
+    }
+
+    pub fn set_operator_config(ctx: Context<SetOperatorConfig>, commission_bps: u16) -> Result<()> {
+        ctx.accounts.process(commission_bps)
+    }
+
+    pub fn set_operator_status(
+        ctx: Context<SetOperatorStatus>,
+        operator_wallet: Pubkey,
+        is_active: bool,
+    ) -> Result<()> {
+        ctx.accounts.process(operator_wallet, is_active)
+    }
+
+    pub fn delegate(ctx: Context<Delegate>, id: u64, lock_period: u64) -> Result<()> {
+        ctx.accounts.process(id, lock_period)
+    }
+
+    pub fn undelegate(ctx: Context<Undelegate>, id: u64, lock_period: u64) -> Result<()> {
+        ctx.accounts.process(id, lock_period)
+    }
+
+    pub fn audit_vault(ctx: Context<AuditVault>, lock_period: u64) -> Result<VaultAuditReport> {
+        ctx.accounts.process(lock_period)
+    }
+
+    pub fn set_receipt_uri(ctx: Context<SetReceiptUri>, receipt_uri: String) -> Result<()> {
+        ctx.accounts.process(receipt_uri)
+    }
+
+    pub fn set_vote_lock_period(
+        ctx: Context<SetVoteLockPeriod>,
+        vote_lock_period: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(vote_lock_period)
+    }
+
+    pub fn migrate_stake_config(ctx: Context<MigrateStakeConfig>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn migrate_vault(ctx: Context<MigrateVault>, lock_period: u64) -> Result<()> {
+        ctx.accounts.process(lock_period)
+    }
+
+    pub fn migrate_staker_info(
+        ctx: Context<MigrateStakerInfo>,
+        lock_period: u64,
+        staker: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.process(lock_period, staker)
+    }
+
+    pub fn migrate_stake_detail(
+        ctx: Context<MigrateStakeDetail>,
+        lock_period: u64,
+        staker: Pubkey,
+        id: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(lock_period, staker, id)
+    }
 }
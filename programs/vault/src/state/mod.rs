@@ -1,7 +1,13 @@
+mod operator;
+mod position_receipt;
 mod stake_config;
 mod stake_info;
 mod vault;
+mod voter_weight_record;
 
+pub use operator::*;
+pub use position_receipt::*;
 pub use stake_config::*;
 pub use stake_info::*;
 pub use vault::*;
+pub use voter_weight_record::*;
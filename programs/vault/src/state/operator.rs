@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+pub const OPERATOR_SIZE: usize = 8 + 1 + 32 + 32 + 2 + 1 + 8;
+
+#[account]
+pub struct Operator {
+    /// Bump seed used to generate the program address / authority
+    pub bump: [u8; 1],
+    pub stake_config: Pubkey,
+    /// wallet of the operator, receives the commission
+    pub operator: Pubkey,
+    /// share of delegators' redistributed penalty kept by the operator
+    pub commission_bps: u16,
+    /// inactive operators cannot receive new delegations
+    pub is_active: bool,
+    /// total stake amount of positions delegated to this operator, across vaults
+    pub delegated_stake: u64,
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+pub const POSITION_RECEIPT_SIZE: usize = 8 + 1 + 32 + 32 + 32 + 32 + 8 + 8 + 8;
+
+/// On-chain metadata of a position receipt token, derived from the receipt mint
+#[account]
+pub struct PositionReceipt {
+    /// Bump seed used to generate the program address / authority
+    pub bump: [u8; 1],
+    /// 0-decimal mint with a supply of 1 representing the position
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    /// staker info the position was created under
+    pub staker_info: Pubkey,
+    pub stake_detail: Pubkey,
+    pub stake_amount: u64,
+    pub lock_period: u64,
+    pub unstaked_at_time: i64,
+}
@@ -1,8 +1,12 @@
 use anchor_lang::prelude::*;
 
-use crate::constant::constants;
+use crate::constant::constants::{self, MAX_RECEIPT_URI_LEN, VE_EPOCH, VE_SLOPE_CHANGES};
+use crate::StakeDetail;
 
-pub const STAKE_CONFIG_SIZE: usize = 8 + 1 + 1 + 32 + 32 + 4;
+pub const RECEIPT_URI_SIZE: usize = 4 + MAX_RECEIPT_URI_LEN;
+pub const SLOPE_CHANGES_SIZE: usize = 16 * VE_SLOPE_CHANGES;
+pub const STAKE_CONFIG_SIZE: usize =
+    8 + 1 + 1 + 32 + 32 + 4 + 8 + 8 + 16 + 16 + 8 + 16 + RECEIPT_URI_SIZE + SLOPE_CHANGES_SIZE + 8;
 
 #[account]
 pub struct StakeConfig {
@@ -13,16 +17,154 @@ pub struct StakeConfig {
     pub authority: Pubkey,
     /// currency mint of token to stake
     pub stake_currency_mint: Pubkey,
+    /// bounds of lock_period accepted by create_vault, in seconds
+    pub min_lock_period: u64,
+    pub max_lock_period: u64,
+    /// sum of decaying voting power at ve_checkpoint_at, scaled by VOTING_POWER_PRECISION
+    pub ve_bias: i128,
+    /// voting power lost per second by all decaying positions
+    pub ve_slope: u128,
+    pub ve_checkpoint_at: i64,
+    /// voting power of auto-renewed positions, which does not decay
+    pub ve_fixed_power: u128,
+    /// base uri of the metadata json of position receipts, the receipt mint is appended to it
+    pub receipt_uri: String,
+    /// slope ending at each of the next epochs, indexed by `(epoch_end / VE_EPOCH) % VE_SLOPE_CHANGES`
+    pub ve_slope_changes: [u128; VE_SLOPE_CHANGES],
+    /// positions counted in a CastVote voter weight are locked this long, should cover
+    /// the max voting time of the governance
+    pub vote_lock_period: u64,
 }
 
 impl StakeConfig {
     /// Seeds are unique to authority/pyth feed/currency mint combinations
-    pub fn auth_seeds<'a>(&'a self) -> [&'a [u8]; 4] {
+    pub fn auth_seeds(&self) -> [&[u8]; 4] {
         [
-            constants::STAKE_CONFIG_SEED.as_ref(),
+            constants::STAKE_CONFIG_SEED,
             self.authority.as_ref(),
             self.stake_currency_mint.as_ref(),
             self.bump.as_ref(),
         ]
     }
+
+    /// decay ve_bias up to `current_timestamp`, dropping the slopes of positions expired since
+    pub fn checkpoint_voting_power(&mut self, current_timestamp: i64) {
+        if current_timestamp <= self.ve_checkpoint_at {
+            return;
+        }
+
+        let (bias, slope) = self.decayed_voting_power(current_timestamp);
+        // slope changes of passed epochs are applied, free their slots for later epochs
+        let mut epoch_end = epoch_start(self.ve_checkpoint_at) + VE_EPOCH;
+        for _ in 0..VE_SLOPE_CHANGES {
+            if epoch_end > current_timestamp {
+                break;
+            }
+            self.ve_slope_changes[slope_change_index(epoch_end)] = 0;
+            epoch_end += VE_EPOCH;
+        }
+
+        self.ve_bias = bias;
+        self.ve_slope = slope;
+        self.ve_checkpoint_at = current_timestamp;
+    }
+
+    /// (bias, slope) at `timestamp`, applying slope changes of the epochs ended since ve_checkpoint_at
+    fn decayed_voting_power(&self, timestamp: i64) -> (i128, u128) {
+        let mut bias = self.ve_bias;
+        let mut slope = self.ve_slope;
+        let mut checkpoint_at = self.ve_checkpoint_at;
+
+        // every slope change is within VE_SLOPE_CHANGES epochs of the checkpoint
+        let mut epoch_end = epoch_start(checkpoint_at) + VE_EPOCH;
+        for _ in 0..VE_SLOPE_CHANGES {
+            if epoch_end > timestamp {
+                break;
+            }
+            bias -= (slope * (epoch_end - checkpoint_at) as u128) as i128;
+            slope -= self.ve_slope_changes[slope_change_index(epoch_end)];
+            checkpoint_at = epoch_end;
+            epoch_end += VE_EPOCH;
+        }
+
+        bias -= (slope as i128) * ((timestamp - checkpoint_at) as i128);
+        (bias, slope)
+    }
+
+    /// add the voting power of a position, its slope is kept on the position so it can be removed later
+    pub fn add_voting_power(
+        &mut self,
+        stake_detail: &mut StakeDetail,
+        lock_period: u64,
+        current_timestamp: i64,
+    ) {
+        self.checkpoint_voting_power(current_timestamp);
+        stake_detail.ve_slope = 0;
+        // power ends at the first epoch boundary from unlock, within the tracked epochs,
+        // so that locks ending inside the current epoch still have power
+        stake_detail.ve_end = std::cmp::min(
+            epoch_start(stake_detail.unstaked_at_time + VE_EPOCH - 1),
+            epoch_start(current_timestamp) + VE_EPOCH * VE_SLOPE_CHANGES as i64,
+        );
+
+        if stake_detail.stake_amount == 0
+            || stake_detail.is_unbonding()
+            || self.max_lock_period == 0
+            || (!stake_detail.auto_renew && stake_detail.unstaked_at_time <= current_timestamp)
+        {
+            return;
+        }
+
+        let slope = (stake_detail.stake_amount as u128) * constants::VOTING_POWER_PRECISION
+            / (self.max_lock_period as u128);
+        if stake_detail.auto_renew {
+            self.ve_fixed_power += slope * (lock_period as u128);
+        } else {
+            let remaining = (stake_detail.ve_end - current_timestamp) as u128;
+            self.ve_bias += (slope * remaining) as i128;
+            self.ve_slope += slope;
+            self.ve_slope_changes[slope_change_index(stake_detail.ve_end)] += slope;
+        }
+        stake_detail.ve_slope = slope;
+    }
+
+    /// remove the voting power previously added by add_voting_power
+    pub fn remove_voting_power(
+        &mut self,
+        stake_detail: &mut StakeDetail,
+        lock_period: u64,
+        current_timestamp: i64,
+    ) {
+        self.checkpoint_voting_power(current_timestamp);
+        let slope = stake_detail.ve_slope;
+        if slope == 0 {
+            return;
+        }
+
+        if stake_detail.auto_renew {
+            self.ve_fixed_power -= slope * (lock_period as u128);
+        } else if stake_detail.ve_end > current_timestamp {
+            // expired positions were already dropped by checkpoint_voting_power
+            let remaining = (stake_detail.ve_end - current_timestamp) as u128;
+            self.ve_bias -= (slope * remaining) as i128;
+            self.ve_slope -= slope;
+            self.ve_slope_changes[slope_change_index(stake_detail.ve_end)] -= slope;
+        }
+        stake_detail.ve_slope = 0;
+    }
+
+    /// total voting power at `timestamp`, extrapolated from the last checkpoint
+    pub fn total_voting_power(&self, timestamp: i64) -> u64 {
+        let (bias, _) = self.decayed_voting_power(timestamp);
+        let bias = bias + self.ve_fixed_power as i128;
+        (bias.max(0) as u128 / constants::VOTING_POWER_PRECISION) as u64
+    }
+}
+
+fn epoch_start(timestamp: i64) -> i64 {
+    timestamp.div_euclid(VE_EPOCH) * VE_EPOCH
+}
+
+fn slope_change_index(epoch_end: i64) -> usize {
+    (epoch_end.div_euclid(VE_EPOCH) as u64 % VE_SLOPE_CHANGES as u64) as usize
 }
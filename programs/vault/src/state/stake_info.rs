@@ -1,6 +1,22 @@
 use anchor_lang::prelude::*;
 
-pub const STAKER_INFO_SIZE: usize = 8 + 1 + 8 + 8;
+use crate::constant::constants::{BPS_DENOMINATOR, PENALTY_PRECISION, VOTING_POWER_PRECISION};
+use crate::Vault;
+
+/// number of balance checkpoints kept per staker, older ones are overwritten
+pub const STAKER_CHECKPOINTS: usize = 32;
+pub const BALANCE_CHECKPOINT_SIZE: usize = 8 + 8 + 16;
+pub const STAKER_INFO_SIZE: usize =
+    8 + 1 + 8 + 8 + BALANCE_CHECKPOINT_SIZE * STAKER_CHECKPOINTS + 8 + 8 + 1;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct BalanceCheckpoint {
+    pub timestamp: i64,
+    /// total_stake from timestamp until the next checkpoint
+    pub total_stake: u64,
+    /// stake-seconds accumulated up to timestamp
+    pub cumulative_stake: u128,
+}
 
 #[account]
 pub struct StakerInfo {
@@ -8,9 +24,68 @@ pub struct StakerInfo {
     pub bump: [u8; 1],
     pub total_stake: u64,
     pub current_id: u64,
+    /// ring of total_stake changes, the latest one is at (checkpoint_count - 1) % STAKER_CHECKPOINTS
+    pub checkpoints: [BalanceCheckpoint; STAKER_CHECKPOINTS],
+    /// number of checkpoints ever written
+    pub checkpoint_count: u64,
+    /// number of stake detail accounts not closed yet
+    pub open_positions: u64,
+    /// layout version, see ACCOUNT_VERSION
+    pub version: u8,
 }
 
-pub const STAKE_DETAIL_SIZE: usize = 8 + 1 + 8 + 8 + 8 + 32;
+impl StakerInfo {
+    /// record total_stake after it changed at `current_timestamp`
+    pub fn write_checkpoint(&mut self, current_timestamp: i64) {
+        if self.checkpoint_count > 0 {
+            let last = self.checkpoints[self.checkpoint_index(self.checkpoint_count - 1)];
+            // several changes in the same second only keep the last balance
+            if last.timestamp == current_timestamp {
+                let index = self.checkpoint_index(self.checkpoint_count - 1);
+                self.checkpoints[index].total_stake = self.total_stake;
+                return;
+            }
+        }
+
+        let cumulative_stake = self.cumulative_stake_at(current_timestamp).unwrap_or(0);
+        let index = self.checkpoint_index(self.checkpoint_count);
+        self.checkpoints[index] = BalanceCheckpoint {
+            timestamp: current_timestamp,
+            total_stake: self.total_stake,
+            cumulative_stake,
+        };
+        self.checkpoint_count += 1;
+    }
+
+    /// stake-seconds accumulated up to `timestamp`, None if it is older than the ring
+    pub fn cumulative_stake_at(&self, timestamp: i64) -> Option<u128> {
+        let written = std::cmp::min(self.checkpoint_count, STAKER_CHECKPOINTS as u64);
+        // newest to oldest
+        for i in 0..written {
+            let checkpoint = self.checkpoints[self.checkpoint_index(self.checkpoint_count - 1 - i)];
+            if checkpoint.timestamp <= timestamp {
+                let elapsed = (timestamp - checkpoint.timestamp) as u128;
+                return Some(
+                    checkpoint.cumulative_stake + (checkpoint.total_stake as u128) * elapsed,
+                );
+            }
+        }
+
+        // nothing was staked before the first checkpoint
+        if self.checkpoint_count <= STAKER_CHECKPOINTS as u64 {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    fn checkpoint_index(&self, position: u64) -> usize {
+        (position % STAKER_CHECKPOINTS as u64) as usize
+    }
+}
+
+pub const STAKE_DETAIL_SIZE: usize =
+    8 + 1 + 8 + 8 + 8 + 32 + 16 + 32 + 8 + 1 + 32 + 1 + 8 + 16 + 8 + 32 + 2 + 16 + 8 + 8 + 1;
 #[account]
 pub struct StakeDetail {
     /// Bump seed used to generate the program address / authority
@@ -19,4 +94,104 @@ pub struct StakeDetail {
     pub stake_amount: u64,
     pub unstaked_at_time: i64,
     pub staker: Pubkey,
+    /// redistributed penalty already accounted for this position
+    pub penalty_debt: u128,
+    /// receipt token representing this position, default if not tokenized
+    pub receipt_mint: Pubkey,
+    /// time request_unstake was called, 0 if not unbonding
+    pub unbonding_started_at: i64,
+    /// lock rolls over for another lock_period unless withdrawn within renew_window after maturity
+    pub auto_renew: bool,
+    /// wallet which funded this position through grant_stake, default if self staked
+    pub grantor: Pubkey,
+    /// grantor can take back the unvested portion before unstaked_at_time
+    pub revocable: bool,
+    /// grants vest linearly from vesting_start to unstaked_at_time
+    pub vesting_start: i64,
+    /// voting power lost per second, 0 if the position has no voting power
+    pub ve_slope: u128,
+    /// time the voting power of a non auto-renewed position reaches 0, the epoch boundary
+    /// from unstaked_at_time
+    pub ve_end: i64,
+    /// operator wallet this position is delegated to, default if not delegated
+    pub delegate_to: Pubkey,
+    /// commission of the operator at delegation time, later changes do not apply
+    pub delegation_commission_bps: u16,
+    /// Vault.acc_penalty_per_share when the operator commission was last settled
+    pub delegation_acc_penalty_per_share: u128,
+    /// Vault.renew_window when auto_renew was enabled, later changes do not apply
+    pub renew_window: u64,
+    /// counted in a CastVote voter weight, cannot leave its owner until then
+    pub vote_locked_until: i64,
+    /// layout version, see ACCOUNT_VERSION
+    pub version: u8,
+}
+
+impl StakeDetail {
+    /// tokenized positions can only be redeemed by burning the receipt token
+    pub fn is_tokenized(&self) -> bool {
+        self.receipt_mint != Pubkey::default()
+    }
+
+    /// delegated positions must be undelegated before their stake amount changes
+    pub fn is_delegated(&self) -> bool {
+        self.delegate_to != Pubkey::default()
+    }
+
+    /// operator commission on the penalty redistributed since the last settlement.
+    /// The commission is taken out of the pending penalty share of the position
+    pub fn settle_delegation(&mut self, vault: &Vault) -> u64 {
+        let accrued = (self.stake_amount as u128)
+            * (vault.acc_penalty_per_share - self.delegation_acc_penalty_per_share)
+            / PENALTY_PRECISION;
+        let commission =
+            (accrued * (self.delegation_commission_bps as u128) / (BPS_DENOMINATOR as u128)) as u64;
+
+        self.penalty_debt += commission as u128;
+        self.delegation_acc_penalty_per_share = vault.acc_penalty_per_share;
+        commission
+    }
+
+    /// voting power used for a vote cannot be moved or withdrawn to vote again
+    pub fn is_vote_locked(&self, current_timestamp: i64) -> bool {
+        current_timestamp < self.vote_locked_until
+    }
+
+    /// unbonding positions are excluded from Vault.total_staked and earn nothing
+    pub fn is_unbonding(&self) -> bool {
+        self.unbonding_started_at > 0
+    }
+
+    /// revocable grants cannot be restructured until revoked or matured
+    pub fn is_revocable_grant(&self, current_timestamp: i64) -> bool {
+        self.revocable && current_timestamp < self.unstaked_at_time
+    }
+
+    /// unlock time of the current lock cycle, computed lazily for auto-renewed positions
+    pub fn unlock_time(&self, vault: &Vault, current_timestamp: i64) -> i64 {
+        if !self.auto_renew || vault.lock_period == 0 || current_timestamp < self.unstaked_at_time {
+            return self.unstaked_at_time;
+        }
+
+        let lock_period = vault.lock_period as i64;
+        let cycles = (current_timestamp - self.unstaked_at_time) / lock_period;
+        let cycle_end = self.unstaked_at_time + cycles * lock_period;
+        // still inside the withdrawal window of the last cycle
+        if current_timestamp < cycle_end + self.renew_window as i64 {
+            cycle_end
+        } else {
+            cycle_end + lock_period
+        }
+    }
+
+    /// ve-style voting power, `stake_amount * remaining_lock / max_lock_period`.
+    /// Auto-renewed positions keep the power of a full lock_period
+    pub fn voting_power(&self, lock_period: u64, timestamp: i64) -> u64 {
+        let power = if self.auto_renew {
+            self.ve_slope * (lock_period as u128)
+        } else {
+            self.ve_slope * (self.ve_end - timestamp).max(0) as u128
+        };
+        (power / VOTING_POWER_PRECISION) as u64
+    }
 }
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
 
 use crate::constant::constants;
+use crate::error::ErrorCode;
 
-pub const VAULT_SIZE: usize = 8 + 1 + 1 + 32 + 8 + 8;
+pub const VAULT_SIZE: usize =
+    8 + 1 + 1 + 32 + 8 + 8 + 1 + 2 + 1 + 32 + 16 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8;
 
 #[account]
 pub struct Vault {
@@ -13,6 +15,32 @@ pub struct Vault {
     /// total staked
     pub total_staked: u64,
     pub lock_period: u64,
+    /// allow staker to unstake before unstaked_at_time with a penalty
+    pub allow_early_unstake: bool,
+    /// penalty applied when unstaking right after staking, decays linearly to 0 at unstaked_at_time
+    pub max_early_unstake_penalty_bps: u16,
+    /// if true, penalty is shared among remaining stakers, otherwise sent to treasury
+    pub redistribute_penalty: bool,
+    /// wallet receiving the penalty
+    pub treasury: Pubkey,
+    /// accumulated redistributed penalty per staked token, scaled by PENALTY_PRECISION
+    pub acc_penalty_per_share: u128,
+    /// cap on total_staked, 0 means unlimited
+    pub max_total_staked: u64,
+    /// cap on total_stake of a single staker, 0 means unlimited
+    pub max_stake_per_staker: u64,
+    /// minimum amount of a single stake detail
+    pub min_stake_amount: u64,
+    /// inactive vaults do not accept new stakes, withdrawals stay open
+    pub is_active: bool,
+    /// waiting time between request_unstake and withdraw, 0 means destake is immediate
+    pub unbonding_period: u64,
+    /// amount requested to unstake but not withdrawn yet
+    pub total_unbonding: u64,
+    /// time after maturity during which auto-renewed positions can be withdrawn, 0 disables auto-renew
+    pub renew_window: u64,
+    /// redistributed penalty not paid out to positions or operators yet
+    pub undistributed_penalty: u64,
 }
 
 impl Vault {
@@ -24,4 +52,67 @@ impl Vault {
             self.bump.as_ref(),
         ]
     }
+
+    /// penalty debt of a position holding `amount` at the current accumulator
+    pub fn penalty_debt(&self, amount: u64) -> Result<u128> {
+        Ok((amount as u128)
+            .checked_mul(self.acc_penalty_per_share)
+            .ok_or(ErrorCode::OverflowError)?
+            / constants::PENALTY_PRECISION)
+    }
+
+    /// redistributed penalty not yet paid out to a position
+    pub fn pending_penalty_share(&self, amount: u64, penalty_debt: u128) -> Result<u64> {
+        Ok(self.penalty_debt(amount)?.saturating_sub(penalty_debt) as u64)
+    }
+
+    /// share `penalty` among the stake of the other positions, `excluded_stake` being the
+    /// remaining stake of the payer. Returns false when the penalty must go to treasury instead:
+    /// nobody else is staked, or so little that the accumulator would leave the range in which
+    /// penalty debts cannot overflow
+    pub fn share_penalty(&mut self, penalty: u64, excluded_stake: u64) -> bool {
+        let other_stake = self.total_staked - excluded_stake;
+        if other_stake == 0 {
+            return false;
+        }
+
+        let acc_penalty_per_share = (penalty as u128) * constants::PENALTY_PRECISION
+            / (other_stake as u128)
+            + self.acc_penalty_per_share;
+        if acc_penalty_per_share > constants::MAX_ACC_PENALTY_PER_SHARE {
+            return false;
+        }
+
+        self.acc_penalty_per_share = acc_penalty_per_share;
+        self.undistributed_penalty += penalty;
+        true
+    }
+
+    /// penalty share or operator commission paid out of the vault. Shares are rounded down
+    /// so their sum never exceeds the redistributed penalty, saturating only absorbs the dust
+    pub fn pay_penalty_share(&mut self, amount: u64) {
+        self.undistributed_penalty = self.undistributed_penalty.saturating_sub(amount);
+    }
+
+    /// status and capacity checks for a new stake of `amount` by a staker holding `staker_total_stake`
+    pub fn check_new_stake(&self, staker_total_stake: u64, amount: u64) -> Result<()> {
+        if !self.is_active {
+            return Err(ErrorCode::VaultInactive.into());
+        }
+
+        if amount < self.min_stake_amount {
+            return Err(ErrorCode::StakeAmountTooLow.into());
+        }
+
+        if self.max_total_staked > 0 && self.total_staked + amount > self.max_total_staked {
+            return Err(ErrorCode::VaultCapacityExceeded.into());
+        }
+
+        if self.max_stake_per_staker > 0 && staker_total_stake + amount > self.max_stake_per_staker
+        {
+            return Err(ErrorCode::StakerCapExceeded.into());
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+/// layouts follow spl-governance-addin-api, the anchor discriminators match the ones expected by spl-governance
+pub const VOTER_WEIGHT_RECORD_SIZE: usize = 8 + 32 + 32 + 32 + 8 + 9 + 2 + 33 + 8;
+
+/// The governance action the voter weight is evaluated for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum VoterWeightAction {
+    CastVote,
+    CommentProposal,
+    CreateGovernance,
+    CreateProposal,
+    SignOffProposal,
+}
+
+#[account]
+pub struct VoterWeightRecord {
+    /// The Realm the VoterWeightRecord belongs to
+    pub realm: Pubkey,
+    /// stake currency mint of the stake config
+    pub governing_token_mint: Pubkey,
+    /// staker the voter weight is computed for
+    pub governing_token_owner: Pubkey,
+    /// voting power of locked stake details
+    pub voter_weight: u64,
+    /// slot of the last update, voting power decays so the weight expires right away
+    pub voter_weight_expiry: Option<u64>,
+    pub weight_action: Option<VoterWeightAction>,
+    pub weight_action_target: Option<Pubkey>,
+    /// Reserved space for future versions
+    pub reserved: [u8; 8],
+}
+
+pub const MAX_VOTER_WEIGHT_RECORD_SIZE: usize = 8 + 32 + 32 + 8 + 9 + 8;
+
+#[account]
+pub struct MaxVoterWeightRecord {
+    /// The Realm the MaxVoterWeightRecord belongs to
+    pub realm: Pubkey,
+    /// stake currency mint of the stake config
+    pub governing_token_mint: Pubkey,
+    /// total voting power of the stake config
+    pub max_voter_weight: u64,
+    /// slot of the last update
+    pub max_voter_weight_expiry: Option<u64>,
+    /// Reserved space for future versions
+    pub reserved: [u8; 8],
+}
@@ -3,6 +3,12 @@ use anchor_spl::token::{self, Token};
 use solana_program::program::{invoke, invoke_signed};
 use std::ops::{Div, Mul};
 
+/// `value * part / total` rounded down, without overflowing for any u128 value
+pub fn proportion(value: u128, part: u64, total: u64) -> u128 {
+    let (part, total) = (part as u128, total as u128);
+    value / total * part + value % total * part / total
+}
+
 pub fn convert_to_float(value: u64, decimals: u8) -> f64 {
     (value as f64).div(f64::powf(10.0, decimals as f64))
 }
@@ -90,3 +96,74 @@ pub fn token_transfer_with_signer<'info>(
 
     Ok(())
 }
+
+//  mint token with PDA as mint authority
+pub fn token_mint_to_with_signer<'info>(
+    mint: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    let cpi_ctx: CpiContext<_> = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token::MintTo {
+            mint,
+            to,
+            authority,
+        },
+        signer_seeds,
+    );
+    token::mint_to(cpi_ctx, amount)?;
+
+    Ok(())
+}
+
+//  burn token from user
+pub fn token_burn_user<'info>(
+    mint: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    authority: &Signer<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_ctx: CpiContext<_> = CpiContext::new(
+        token_program.to_account_info(),
+        token::Burn {
+            mint,
+            from,
+            authority: authority.to_account_info(),
+        },
+    );
+    token::burn(cpi_ctx, amount)?;
+
+    Ok(())
+}
+
+// grow an account created with an older layout of T to `size`, the payer tops up the rent.
+// Appended fields start zeroed, `migrate` sets the ones with another default
+pub fn migrate_account<'info, T: AccountSerialize + AccountDeserialize>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    size: usize,
+    migrate: impl FnOnce(&mut T) -> Result<()>,
+) -> Result<()> {
+    if account.data_len() >= size {
+        return Err(error::ErrorCode::AccountAlreadyMigrated.into());
+    }
+
+    let rent = Rent::get()?
+        .minimum_balance(size)
+        .saturating_sub(account.lamports());
+    if rent > 0 {
+        sol_transfer_from_user(payer, account.clone(), system_program, rent)?;
+    }
+    account.realloc(size, true)?;
+
+    // the discriminator is checked here, so only accounts of type T are migrated
+    let mut data = T::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    migrate(&mut data)?;
+    data.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])
+}
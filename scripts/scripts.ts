@@ -130,6 +130,7 @@ const stake = async (lockPeriod: number, amount: number) => {
       signer: payer.publicKey,
       stakeCurrencyMint: stakeCurrencyMint,
       stakeDetailPda: userStakeDetailPda,
+      operator: null,
     })
     .transaction();
 
@@ -143,7 +144,7 @@ const unStake = async (lockPeriod: number, id: number, amount: number) => {
   const stakeCurrencyMint = globalConfig.STAKE_CURRENCY_MINT;
 
   const tx = await program.methods
-    .destake(new BN(id), new BN(lockPeriod), new BN(amount))
+    .destake(new BN(id), new BN(lockPeriod), new BN(amount), false)
     .accounts({
       signer: payer.publicKey,
       stakeCurrencyMint: stakeCurrencyMint,
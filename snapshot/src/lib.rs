@@ -0,0 +1,319 @@
+//! Per wallet staking snapshot built from offline account dumps, used for airdrops.
+//!
+//! Vault program positions are tied to their vault by deriving the stake detail address
+//! from each vault and the staker stored in the position. Fungstake `StakeInfo` does not
+//! store its wallet, so it can only be attributed to wallets passed to [`Snapshot::build`].
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{self, Write},
+};
+
+use serde::Serialize;
+use solana_program::pubkey::Pubkey;
+use stake_client::{
+    accounts::{decode_program_account, fungstake, vault, ProgramAccount},
+    dump::DumpedAccount,
+    pda,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Program {
+    Vault,
+    Fungstake,
+}
+
+/// stake of a wallet in one vault, or in every vault of a stake config once aggregated
+#[derive(Clone)]
+pub struct Balance {
+    pub program: Program,
+    pub stake_config: Pubkey,
+    /// None if the stake config is missing from the dump
+    pub stake_currency_mint: Option<Pubkey>,
+    /// None once aggregated
+    pub vault: Option<Pubkey>,
+    /// vault program only, None once aggregated
+    pub lock_period: Option<u64>,
+    pub wallet: Pubkey,
+    /// stake still locked or matured, excluding unbonding positions
+    pub staked: u64,
+    /// stake waiting for withdraw after request_unstake
+    pub unbonding: u64,
+    pub positions: u64,
+}
+
+pub struct Snapshot {
+    pub balances: Vec<Balance>,
+    /// positions holding stake which could not be tied to a vault of the dump or a known wallet
+    pub unattributed: Vec<Pubkey>,
+    /// vault program StakerInfo whose total_stake differs from the sum of its positions
+    pub staker_info_mismatches: Vec<Pubkey>,
+    /// staking accounts which failed to decode, e.g. with an older layout not migrated yet.
+    /// Their stake is missing from the balances
+    pub undecodable: Vec<Pubkey>,
+}
+
+type BalanceKey = (Program, Pubkey, Pubkey);
+
+impl Snapshot {
+    /// `wallets` are looked up in every fungstake vault, they are not needed for the vault program
+    pub fn build(accounts: &[DumpedAccount], wallets: &[Pubkey]) -> Self {
+        let mut vault_configs = HashMap::new();
+        let mut vault_vaults = vec![];
+        let mut staker_infos = HashMap::new();
+        let mut stake_details = vec![];
+        let mut fungstake_configs = vec![];
+        let mut fungstake_vaults = vec![];
+        let mut stake_infos = HashMap::new();
+        let mut undecodable = vec![];
+
+        for account in accounts {
+            let Ok(decoded) = decode_program_account(&account.owner, &account.data) else {
+                undecodable.push(account.pubkey);
+                continue;
+            };
+            match decoded {
+                Some(ProgramAccount::VaultStakeConfig(config)) => {
+                    vault_configs.insert(account.pubkey, *config);
+                }
+                Some(ProgramAccount::VaultVault(vault)) => {
+                    vault_vaults.push((account.pubkey, vault))
+                }
+                Some(ProgramAccount::VaultStakerInfo(staker_info)) => {
+                    staker_infos.insert(account.pubkey, *staker_info);
+                }
+                Some(ProgramAccount::VaultStakeDetail(detail)) => {
+                    stake_details.push((account.pubkey, detail))
+                }
+                Some(ProgramAccount::FungstakeStakeConfig(config)) => {
+                    fungstake_configs.push((account.pubkey, config))
+                }
+                Some(ProgramAccount::FungstakeVault(vault)) => {
+                    fungstake_vaults.push((account.pubkey, vault))
+                }
+                Some(ProgramAccount::FungstakeStakeInfo(stake_info)) => {
+                    stake_infos.insert(account.pubkey, stake_info);
+                }
+                None => {}
+            }
+        }
+
+        let mut snapshot = Snapshot {
+            balances: vec![],
+            unattributed: vec![],
+            staker_info_mismatches: vec![],
+            undecodable,
+        };
+        snapshot.add_vault_positions(&vault_configs, &vault_vaults, &staker_infos, &stake_details);
+        snapshot.add_fungstake_positions(
+            &fungstake_configs,
+            &fungstake_vaults,
+            &stake_infos,
+            wallets,
+        );
+        snapshot
+    }
+
+    fn add_vault_positions(
+        &mut self,
+        configs: &HashMap<Pubkey, vault::StakeConfig>,
+        vaults: &[(Pubkey, vault::Vault)],
+        staker_infos: &HashMap<Pubkey, vault::StakerInfo>,
+        stake_details: &[(Pubkey, vault::StakeDetail)],
+    ) {
+        let mut balances: BTreeMap<BalanceKey, Balance> = BTreeMap::new();
+        let mut staker_info_keys = HashMap::new();
+
+        for (detail_key, detail) in stake_details {
+            if detail.stake_amount == 0 {
+                continue;
+            }
+
+            let found = vaults.iter().find(|(vault_key, _)| {
+                let staker_info = *staker_info_keys
+                    .entry((*vault_key, detail.staker))
+                    .or_insert_with(|| pda::vault::staker_info(vault_key, &detail.staker));
+                pda::vault::stake_detail(&staker_info, detail.id) == *detail_key
+            });
+            let Some((vault_key, vault)) = found else {
+                self.unattributed.push(*detail_key);
+                continue;
+            };
+
+            let balance = balances
+                .entry((Program::Vault, *vault_key, detail.staker))
+                .or_insert_with(|| Balance {
+                    program: Program::Vault,
+                    stake_config: vault.vault_config,
+                    stake_currency_mint: configs
+                        .get(&vault.vault_config)
+                        .map(|config| config.stake_currency_mint),
+                    vault: Some(*vault_key),
+                    lock_period: Some(vault.lock_period),
+                    wallet: detail.staker,
+                    staked: 0,
+                    unbonding: 0,
+                    positions: 0,
+                });
+            if detail.unbonding_started_at != 0 {
+                balance.unbonding += detail.stake_amount;
+            } else {
+                balance.staked += detail.stake_amount;
+            }
+            balance.positions += 1;
+        }
+
+        for ((_, vault_key, wallet), balance) in &balances {
+            let staker_info_key = staker_info_keys[&(*vault_key, *wallet)];
+            if let Some(staker_info) = staker_infos.get(&staker_info_key) {
+                if staker_info.total_stake != balance.staked + balance.unbonding {
+                    self.staker_info_mismatches.push(staker_info_key);
+                }
+            }
+        }
+
+        self.balances.extend(balances.into_values());
+    }
+
+    fn add_fungstake_positions(
+        &mut self,
+        configs: &[(Pubkey, fungstake::StakeConfig)],
+        vaults: &[(Pubkey, fungstake::Vault)],
+        stake_infos: &HashMap<Pubkey, fungstake::StakeInfo>,
+        wallets: &[Pubkey],
+    ) {
+        let mut balances: BTreeMap<BalanceKey, Balance> = BTreeMap::new();
+        let mut attributed = HashSet::new();
+
+        for (vault_key, vault) in vaults {
+            // vaults do not store their config, it is found by deriving the vault address
+            let Some((config_key, config)) = configs.iter().find(|(config_key, _)| {
+                pda::fungstake::vault(config_key, &vault.reward_currency_mint) == *vault_key
+            }) else {
+                continue;
+            };
+
+            for wallet in wallets {
+                let stake_info_key = pda::fungstake::stake_info(vault_key, wallet);
+                let Some(stake_info) = stake_infos.get(&stake_info_key) else {
+                    continue;
+                };
+                attributed.insert(stake_info_key);
+                if stake_info.stake_amount == 0 {
+                    continue;
+                }
+
+                balances.insert(
+                    (Program::Fungstake, *vault_key, *wallet),
+                    Balance {
+                        program: Program::Fungstake,
+                        stake_config: *config_key,
+                        stake_currency_mint: Some(config.stake_currency_mint),
+                        vault: Some(*vault_key),
+                        lock_period: None,
+                        wallet: *wallet,
+                        staked: stake_info.stake_amount,
+                        unbonding: 0,
+                        positions: 1,
+                    },
+                );
+            }
+        }
+
+        self.unattributed.extend(
+            stake_infos
+                .iter()
+                .filter(|(key, stake_info)| {
+                    stake_info.stake_amount > 0 && !attributed.contains(*key)
+                })
+                .map(|(key, _)| *key),
+        );
+        self.balances.extend(balances.into_values());
+    }
+
+    /// balances summed per wallet across every vault (lock period) of a stake config
+    pub fn aggregated(&self) -> Vec<Balance> {
+        let mut aggregated: BTreeMap<BalanceKey, Balance> = BTreeMap::new();
+        for balance in &self.balances {
+            aggregated
+                .entry((balance.program, balance.stake_config, balance.wallet))
+                .and_modify(|total| {
+                    total.staked += balance.staked;
+                    total.unbonding += balance.unbonding;
+                    total.positions += balance.positions;
+                })
+                .or_insert_with(|| Balance {
+                    vault: None,
+                    lock_period: None,
+                    ..balance.clone()
+                });
+        }
+        aggregated.into_values().collect()
+    }
+}
+
+/// exported row, pubkeys are base58 and missing values are empty in CSV and null in JSON
+#[derive(Serialize)]
+struct Record {
+    program: Program,
+    stake_config: String,
+    stake_currency_mint: Option<String>,
+    vault: Option<String>,
+    lock_period: Option<u64>,
+    wallet: String,
+    staked: u64,
+    unbonding: u64,
+    positions: u64,
+}
+
+impl From<&Balance> for Record {
+    fn from(balance: &Balance) -> Self {
+        Record {
+            program: balance.program,
+            stake_config: balance.stake_config.to_string(),
+            stake_currency_mint: balance.stake_currency_mint.map(|mint| mint.to_string()),
+            vault: balance.vault.map(|vault| vault.to_string()),
+            lock_period: balance.lock_period,
+            wallet: balance.wallet.to_string(),
+            staked: balance.staked,
+            unbonding: balance.unbonding,
+            positions: balance.positions,
+        }
+    }
+}
+
+pub fn write_json(balances: &[Balance], writer: impl Write) -> serde_json::Result<()> {
+    let records: Vec<Record> = balances.iter().map(Record::from).collect();
+    serde_json::to_writer_pretty(writer, &records)
+}
+
+pub fn write_csv(balances: &[Balance], mut writer: impl Write) -> io::Result<()> {
+    writeln!(
+        writer,
+        "program,stake_config,stake_currency_mint,vault,lock_period,wallet,staked,unbonding,positions"
+    )?;
+    for balance in balances {
+        let program = match balance.program {
+            Program::Vault => "vault",
+            Program::Fungstake => "fungstake",
+        };
+        writeln!(
+            writer,
+            "{program},{},{},{},{},{},{},{},{}",
+            balance.stake_config,
+            optional(balance.stake_currency_mint),
+            optional(balance.vault),
+            optional(balance.lock_period),
+            balance.wallet,
+            balance.staked,
+            balance.unbonding,
+            balance.positions,
+        )?;
+    }
+    Ok(())
+}
+
+fn optional(value: Option<impl ToString>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
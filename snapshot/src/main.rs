@@ -0,0 +1,90 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use solana_program::pubkey::Pubkey;
+use stake_client::dump::load_accounts;
+use stake_snapshot::{write_csv, write_json, Snapshot};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Csv,
+    Json,
+}
+
+/// Export per wallet stake of the vault and fungstake programs from account dumps
+#[derive(Parser)]
+#[command(name = "stake-snapshot", version)]
+struct Cli {
+    /// `solana account --output json` files, saved `getProgramAccounts` responses or directories of them
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+    /// wallets to look up in fungstake vaults, one base58 pubkey per line
+    #[arg(long)]
+    wallets: Option<PathBuf>,
+    /// sum each wallet across the lock periods of a stake config
+    #[arg(long)]
+    aggregate: bool,
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+    /// file to write, stdout by default
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+fn read_wallets(path: &PathBuf) -> Result<Vec<Pubkey>> {
+    fs::read_to_string(path)
+        .with_context(|| format!("reading {}", path.display()))?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| Pubkey::from_str(line).with_context(|| format!("invalid wallet {line}")))
+        .collect()
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let mut accounts = vec![];
+    for input in &cli.inputs {
+        accounts
+            .extend(load_accounts(input).with_context(|| format!("reading {}", input.display()))?);
+    }
+    let wallets = match &cli.wallets {
+        Some(path) => read_wallets(path)?,
+        None => vec![],
+    };
+
+    let snapshot = Snapshot::build(&accounts, &wallets);
+    for key in &snapshot.unattributed {
+        eprintln!("warning: position {key} has stake but no known vault or wallet");
+    }
+    for key in &snapshot.staker_info_mismatches {
+        eprintln!("warning: StakerInfo {key} total_stake differs from its positions");
+    }
+    for key in &snapshot.undecodable {
+        eprintln!("warning: account {key} could not be decoded, its stake is left out");
+    }
+
+    let balances = if cli.aggregate {
+        snapshot.aggregated()
+    } else {
+        snapshot.balances
+    };
+
+    let mut writer: Box<dyn Write> = match &cli.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+    match cli.format {
+        Format::Csv => write_csv(&balances, &mut writer)?,
+        Format::Json => write_json(&balances, &mut writer)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
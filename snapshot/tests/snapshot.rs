@@ -0,0 +1,354 @@
+//! Snapshots of a dump holding positions of both programs, with the cases skipped or
+//! reported by the snapshot: closed positions, positions outside the dump, unknown
+//! fungstake wallets and accounts which fail to decode.
+
+use std::{collections::BTreeMap, fs};
+
+use anchor_lang::Discriminator;
+use serde_json::{json, Value};
+use solana_program::pubkey::Pubkey;
+use stake_client::{
+    accounts::{fungstake, vault},
+    dump::{load_accounts, DumpedAccount},
+    fungstake_program, pda,
+    test_utils::{account_json, dumped, key, zeroed},
+    vault_program,
+};
+use stake_snapshot::{write_csv, write_json, Balance, Program, Snapshot};
+
+const MONTH: u64 = 30 * 86_400;
+const QUARTER: u64 = 90 * 86_400;
+
+struct Fixture {
+    accounts: Vec<DumpedAccount>,
+    mint: Pubkey,
+    stake_config: Pubkey,
+    vaults: [Pubkey; 2],
+    alice: Pubkey,
+    bob: Pubkey,
+    carol: Pubkey,
+    dave: Pubkey,
+    erin: Pubkey,
+    frank: Pubkey,
+    /// vault of a stake config missing from the dump
+    orphan_vault: Pubkey,
+    /// stake detail which is not at the address derived from any vault
+    stray_detail: Pubkey,
+    fungstake_config: Pubkey,
+    fungstake_vault: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let (mint, other_mint, fungstake_mint, reward_mint) = (key(1), key(2), key(3), key(4));
+        let (alice, bob, carol, dave, erin, frank) =
+            (key(10), key(11), key(12), key(13), key(14), key(15));
+        let mut accounts = vec![];
+
+        let stake_config = pda::vault::stake_config(&mint);
+        let mut config: vault::StakeConfig = zeroed(vault_program::STAKE_CONFIG_SIZE);
+        config.stake_currency_mint = mint;
+        accounts.push(dumped(stake_config, vault_program::ID, &config));
+
+        let mut vault_account = |stake_config: &Pubkey, lock_period| {
+            let address = pda::vault::vault(stake_config, lock_period);
+            let mut vault: vault::Vault = zeroed(vault_program::VAULT_SIZE);
+            vault.vault_config = *stake_config;
+            vault.lock_period = lock_period;
+            accounts.push(dumped(address, vault_program::ID, &vault));
+            address
+        };
+        let vaults = [
+            vault_account(&stake_config, MONTH),
+            vault_account(&stake_config, QUARTER),
+        ];
+        let orphan_vault = vault_account(&pda::vault::stake_config(&other_mint), MONTH);
+
+        // (vault, staker, id, stake_amount, unbonding)
+        let positions = [
+            (vaults[0], alice, 1, 100, false),
+            (vaults[0], alice, 2, 50, true),
+            (vaults[1], alice, 1, 200, false),
+            // fully destaked, not closed yet
+            (vaults[0], bob, 1, 0, false),
+            (vaults[0], bob, 2, 70, false),
+            (orphan_vault, carol, 1, 40, false),
+        ];
+        for (vault_key, staker, id, amount, unbonding) in positions {
+            let staker_info = pda::vault::staker_info(&vault_key, &staker);
+            let mut detail: vault::StakeDetail = zeroed(vault_program::STAKE_DETAIL_SIZE);
+            detail.id = id;
+            detail.staker = staker;
+            detail.stake_amount = amount;
+            if unbonding {
+                detail.unbonding_started_at = 1_700_000_000;
+            }
+            accounts.push(dumped(
+                pda::vault::stake_detail(&staker_info, id),
+                vault_program::ID,
+                &detail,
+            ));
+        }
+        let stray_detail = key(50);
+        let mut detail: vault::StakeDetail = zeroed(vault_program::STAKE_DETAIL_SIZE);
+        detail.id = 9;
+        detail.staker = alice;
+        detail.stake_amount = 10;
+        accounts.push(dumped(stray_detail, vault_program::ID, &detail));
+
+        // alice's month StakerInfo matches her positions, her quarter one does not
+        for (vault_key, total_stake) in [(vaults[0], 150), (vaults[1], 999)] {
+            let mut staker_info: vault::StakerInfo = zeroed(vault_program::STAKER_INFO_SIZE);
+            staker_info.total_stake = total_stake;
+            accounts.push(dumped(
+                pda::vault::staker_info(&vault_key, &alice),
+                vault_program::ID,
+                &staker_info,
+            ));
+        }
+
+        let fungstake_config = pda::fungstake::stake_config(&fungstake_mint);
+        let mut config: fungstake::StakeConfig = zeroed(fungstake_program::STAKE_CONFIG_SIZE);
+        config.stake_currency_mint = fungstake_mint;
+        accounts.push(dumped(fungstake_config, fungstake_program::ID, &config));
+        let fungstake_vault = pda::fungstake::vault(&fungstake_config, &reward_mint);
+        let mut vault: fungstake::Vault = zeroed(fungstake_program::VAULT_SIZE);
+        vault.reward_currency_mint = reward_mint;
+        accounts.push(dumped(fungstake_vault, fungstake_program::ID, &vault));
+        for (wallet, stake_amount) in [(dave, 500), (erin, 0), (frank, 30)] {
+            let mut stake_info: fungstake::StakeInfo = zeroed(fungstake_program::STAKE_INFO_SIZE);
+            stake_info.stake_amount = stake_amount;
+            accounts.push(dumped(
+                pda::fungstake::stake_info(&fungstake_vault, &wallet),
+                fungstake_program::ID,
+                &stake_info,
+            ));
+        }
+
+        // accounts of other programs are ignored
+        accounts.push(DumpedAccount {
+            pubkey: key(60),
+            owner: solana_program::system_program::ID,
+            lamports: 1,
+            data: vault::StakeDetail::DISCRIMINATOR.to_vec(),
+        });
+
+        Fixture {
+            accounts,
+            mint,
+            stake_config,
+            vaults,
+            alice,
+            bob,
+            carol,
+            dave,
+            erin,
+            frank,
+            orphan_vault,
+            stray_detail,
+            fungstake_config,
+            fungstake_vault,
+        }
+    }
+
+    /// frank is left out, his stake info cannot be attributed
+    fn wallets(&self) -> Vec<Pubkey> {
+        vec![self.alice, self.bob, self.dave, self.erin]
+    }
+}
+
+/// (staked, unbonding, positions) by (vault, wallet)
+fn totals(balances: &[Balance]) -> BTreeMap<(Option<Pubkey>, Pubkey), (u64, u64, u64)> {
+    balances
+        .iter()
+        .map(|balance| {
+            (
+                (balance.vault, balance.wallet),
+                (balance.staked, balance.unbonding, balance.positions),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn snapshot_sums_positions_per_vault_and_wallet() {
+    let fixture = Fixture::new();
+    let snapshot = Snapshot::build(&fixture.accounts, &fixture.wallets());
+    let [month, quarter] = fixture.vaults;
+
+    assert_eq!(
+        totals(&snapshot.balances),
+        BTreeMap::from([
+            ((Some(month), fixture.alice), (100, 50, 2)),
+            ((Some(quarter), fixture.alice), (200, 0, 1)),
+            ((Some(month), fixture.bob), (70, 0, 1)),
+            ((Some(fixture.orphan_vault), fixture.carol), (40, 0, 1)),
+            ((Some(fixture.fungstake_vault), fixture.dave), (500, 0, 1)),
+        ])
+    );
+    let staked: u64 = snapshot.balances.iter().map(|b| b.staked).sum();
+    let unbonding: u64 = snapshot.balances.iter().map(|b| b.unbonding).sum();
+    assert_eq!((staked, unbonding), (910, 50));
+
+    for balance in &snapshot.balances {
+        if balance.vault == Some(month) || balance.vault == Some(quarter) {
+            assert!(balance.program == Program::Vault);
+            assert_eq!(balance.stake_config, fixture.stake_config);
+            assert_eq!(balance.stake_currency_mint, Some(fixture.mint));
+            let lock_period = if balance.vault == Some(month) {
+                MONTH
+            } else {
+                QUARTER
+            };
+            assert_eq!(balance.lock_period, Some(lock_period));
+        } else if balance.vault == Some(fixture.orphan_vault) {
+            // the stake config of the vault is not in the dump
+            assert_eq!(balance.stake_currency_mint, None);
+        } else {
+            assert!(balance.program == Program::Fungstake);
+            assert_eq!(balance.stake_config, fixture.fungstake_config);
+            assert_eq!(balance.lock_period, None);
+        }
+    }
+
+    let mut unattributed = snapshot.unattributed.clone();
+    unattributed.sort();
+    let mut expected = vec![
+        fixture.stray_detail,
+        pda::fungstake::stake_info(&fixture.fungstake_vault, &fixture.frank),
+    ];
+    expected.sort();
+    assert_eq!(unattributed, expected);
+    assert_eq!(
+        snapshot.staker_info_mismatches,
+        [pda::vault::staker_info(&quarter, &fixture.alice)]
+    );
+    assert!(snapshot.undecodable.is_empty());
+}
+
+#[test]
+fn aggregated_snapshot_sums_the_lock_periods_of_a_stake_config() {
+    let fixture = Fixture::new();
+    let snapshot = Snapshot::build(&fixture.accounts, &fixture.wallets());
+    let aggregated = snapshot.aggregated();
+
+    assert_eq!(
+        totals(&aggregated),
+        BTreeMap::from([
+            ((None, fixture.alice), (300, 50, 3)),
+            ((None, fixture.bob), (70, 0, 1)),
+            ((None, fixture.carol), (40, 0, 1)),
+            ((None, fixture.dave), (500, 0, 1)),
+        ])
+    );
+    assert!(aggregated
+        .iter()
+        .all(|balance| balance.lock_period.is_none()));
+    // totals are kept
+    let staked = |balances: &[Balance]| balances.iter().map(|b| b.staked).sum::<u64>();
+    assert_eq!(staked(&aggregated), staked(&snapshot.balances));
+}
+
+#[test]
+fn fungstake_stakes_need_their_wallet() {
+    let fixture = Fixture::new();
+    let snapshot = Snapshot::build(&fixture.accounts, &[]);
+    assert!(snapshot
+        .balances
+        .iter()
+        .all(|balance| balance.program == Program::Vault));
+    // the stray detail, dave and frank have stake, erin's stake info is empty
+    assert_eq!(snapshot.unattributed.len(), 3);
+}
+
+#[test]
+fn accounts_failing_to_decode_are_reported() {
+    let mut fixture = Fixture::new();
+    let built = Snapshot::build(&fixture.accounts, &fixture.wallets());
+
+    // a stake info written with the layout of the first release, not migrated yet
+    let legacy = key(70);
+    let mut data = fungstake::StakeInfo::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&[0; 25]);
+    fixture.accounts.push(DumpedAccount {
+        pubkey: legacy,
+        owner: fungstake_program::ID,
+        lamports: 1,
+        data,
+    });
+
+    let snapshot = Snapshot::build(&fixture.accounts, &fixture.wallets());
+    assert_eq!(snapshot.undecodable, [legacy]);
+    assert_eq!(totals(&snapshot.balances), totals(&built.balances));
+}
+
+#[test]
+fn snapshot_of_a_program_accounts_response_file() {
+    let fixture = Fixture::new();
+    let result: Vec<Value> = fixture.accounts.iter().map(account_json).collect();
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("program_accounts.json");
+    fs::write(
+        &path,
+        json!({ "jsonrpc": "2.0", "result": result, "id": 1 }).to_string(),
+    )
+    .unwrap();
+
+    let loaded = load_accounts(&path).unwrap();
+    let from_file = Snapshot::build(&loaded, &fixture.wallets());
+    let built = Snapshot::build(&fixture.accounts, &fixture.wallets());
+    assert_eq!(totals(&from_file.balances), totals(&built.balances));
+}
+
+#[test]
+fn exports_leave_missing_values_empty() {
+    let fixture = Fixture::new();
+    let snapshot = Snapshot::build(&fixture.accounts, &fixture.wallets());
+    let carol = snapshot
+        .balances
+        .iter()
+        .find(|balance| balance.wallet == fixture.carol)
+        .unwrap();
+
+    let mut csv = vec![];
+    write_csv(std::slice::from_ref(carol), &mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "program,stake_config,stake_currency_mint,vault,lock_period,wallet,staked,unbonding,positions",
+            &format!(
+                "vault,{},,{},{MONTH},{},40,0,1",
+                carol.stake_config, fixture.orphan_vault, fixture.carol
+            ),
+        ]
+    );
+
+    let mut json = vec![];
+    write_json(&snapshot.aggregated(), &mut json).unwrap();
+    let records: Vec<Value> = serde_json::from_slice(&json).unwrap();
+    let carol = records
+        .iter()
+        .find(|record| record["wallet"] == fixture.carol.to_string())
+        .unwrap();
+    assert_eq!(
+        *carol,
+        json!({
+            "program": "vault",
+            "stake_config": carol["stake_config"],
+            "stake_currency_mint": null,
+            "vault": null,
+            "lock_period": null,
+            "wallet": fixture.carol.to_string(),
+            "staked": 40,
+            "unbonding": 0,
+            "positions": 1,
+        })
+    );
+    let dave = records
+        .iter()
+        .find(|record| record["wallet"] == fixture.dave.to_string())
+        .unwrap();
+    assert_eq!(dave["program"], "fungstake");
+    assert_eq!(dave["staked"], 500);
+}
@@ -3,3 +3,15 @@ export const STAKE_CONFIG_SEED = "staking_config";
 export const STAKER_INFO_SEED = "staker_info";
 export const STAKE_INFO_SEED = "stake_info";
 export const STAKE_DETAIL_SEED = "stake_detail";
+export const POSITION_MINT_SEED = "position_mint";
+export const POSITION_RECEIPT_SEED = "position_receipt";
+export const VOTER_WEIGHT_RECORD_SEED = "voter_weight_record";
+export const MAX_VOTER_WEIGHT_RECORD_SEED = "max_voter_weight_record";
+export const OPERATOR_SEED = "operator";
+export const ALLOWLIST_SEED = "allowlist";
+// mock Pyth price accounts loaded by Anchor.toml, $2.50 +- $0.001
+export const PRICE_FEED = "4G7tDMtytaL7jAgLvTPjdRoj594qkcSJp836rR3f2trN";
+export const STALE_PRICE_FEED = "DEV3Uk6WxTi5teidNAcEqJgnXTRjJ7XwkMhi7XpTgwa6";
+// Metaplex token metadata program, cloned from mainnet by Anchor.toml
+export const TOKEN_METADATA_PROGRAM_ID =
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
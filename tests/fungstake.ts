@@ -3,10 +3,13 @@ import { BN, Program } from "@coral-xyz/anchor";
 import { Fungstake } from "../target/types/fungstake";
 import {
   Connection,
+  Ed25519Program,
   Keypair,
   LAMPORTS_PER_SOL,
   PublicKey,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
   TransactionConfirmationStrategy,
+  TransactionInstruction,
 } from "@solana/web3.js";
 import {
   createMint,
@@ -15,7 +18,14 @@ import {
   mintTo,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
-import { STAKE_CONFIG_SEED, STAKE_INFO_SEED, VAULT_SEED } from "./constants";
+import {
+  ALLOWLIST_SEED,
+  PRICE_FEED,
+  STAKE_CONFIG_SEED,
+  STAKE_INFO_SEED,
+  STALE_PRICE_FEED,
+  VAULT_SEED,
+} from "./constants";
 import { assert } from "chai";
 import { setTimeout } from "timers/promises";
 
@@ -36,6 +46,17 @@ describe("fungstake", () => {
   const program = anchor.workspace.Fungstake as Program<Fungstake>;
   const connection = program.provider.connection;
 
+  const expectError = async (tx: Promise<string>, code: string) => {
+    try {
+      await tx;
+      assert.fail(`expected ${code}`);
+    } catch (error) {
+      assert.include(JSON.stringify(error), code);
+    }
+  };
+  const chainTime = async () =>
+    await connection.getBlockTime(await connection.getSlot());
+
   // create tx map config
   before(async () => {
     await Promise.all(
@@ -163,6 +184,9 @@ describe("fungstake", () => {
         signer: payer.publicKey,
         stakeCurrencyMint: stakeCurrencyMint,
         rewardCurrencyMint: rewardCurrencyMint,
+        priceFeed: null,
+        allowlistEntry: null,
+        instructionsSysvar: null,
       })
       .rpc();
     console.log("Your transaction signature stake", tx);
@@ -198,6 +222,9 @@ describe("fungstake", () => {
         signer: payer.publicKey,
         stakeCurrencyMint: stakeCurrencyMint,
         rewardCurrencyMint: rewardCurrencyMint,
+        priceFeed: null,
+        allowlistEntry: null,
+        instructionsSysvar: null,
       })
       .rpc();
     userStakeInfo = await program.account.stakeInfo.fetch(userStakePda);
@@ -272,7 +299,7 @@ describe("fungstake", () => {
     let willThrow = false;
     try {
       await program.methods
-        .destake(new BN(10))
+        .destake(new BN(10), false)
         .accounts({
           signer: payer.publicKey,
           stakeCurrencyMint: stakeCurrencyMint,
@@ -288,7 +315,7 @@ describe("fungstake", () => {
     // case 2:  success
     await setTimeout((lockPeriod + 1) * 1000);
     await program.methods
-      .destake(new BN(10))
+      .destake(new BN(10), false)
       .accounts({
         signer: payer.publicKey,
         stakeCurrencyMint: stakeCurrencyMint,
@@ -324,6 +351,9 @@ describe("fungstake", () => {
         signer: user2.publicKey,
         stakeCurrencyMint: stakeCurrencyMint,
         rewardCurrencyMint: rewardCurrencyMint,
+        priceFeed: null,
+        allowlistEntry: null,
+        instructionsSysvar: null,
       })
       .signers([user2])
       .rpc();
@@ -374,7 +404,7 @@ describe("fungstake", () => {
     let willThrow = false;
     try {
       await program.methods
-        .destake(new BN(10))
+        .destake(new BN(10), false)
         .accounts({
           signer: payer.publicKey,
           stakeCurrencyMint: stakeCurrencyMint,
@@ -433,7 +463,7 @@ describe("fungstake", () => {
     await setTimeout((lockExtendTime + 2) * 1000);
 
     await program.methods
-      .destake(new BN(10))
+      .destake(new BN(10), false)
       .accounts({
         signer: payer.publicKey,
         stakeCurrencyMint: stakeCurrencyMint,
@@ -569,4 +599,719 @@ describe("fungstake", () => {
 
     assert.equal(willThrow, true);
   });
+
+  it("Close stake info", async () => {
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        rewardCurrencyMint.toBytes(),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+
+    // case 1: cannot close while tokens are still staked
+    let willThrow = false;
+    try {
+      await program.methods
+        .closeStakeInfo()
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+          rewardCurrencyMint: rewardCurrencyMint,
+        })
+        .rpc();
+    } catch (error) {
+      willThrow = true;
+      assert.include(error.toString(), "StakeInfoNotEmpty");
+    }
+    assert.equal(willThrow, true);
+
+    // case 2: full destake after claiming closes the stake info
+    const userStake = await program.account.stakeInfo.fetch(userStakePda);
+    await program.methods
+      .destake(userStake.stakeAmount, true)
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        rewardCurrencyMint: rewardCurrencyMint,
+      })
+      .rpc();
+    assert.isNull(await program.account.stakeInfo.fetchNullable(userStakePda));
+  });
+
+  it("It audit vault solvency", async () => {
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        rewardCurrencyMint.toBytes(),
+      ],
+      program.programId
+    );
+
+    const vault = await program.account.vault.fetch(vaultPda);
+    const report = await program.methods
+      .auditVault()
+      .accounts({
+        stakeCurrencyMint: stakeCurrencyMint,
+        rewardCurrencyMint: rewardCurrencyMint,
+      })
+      .view();
+
+    // payer claimed its share, only the other stakers' shares are still owed
+    const unclaimedReward = vault.totalReward
+      .mul(vault.totalStaked.sub(vault.claimedSnapshotAmount))
+      .div(vault.totalStaked);
+    assert.equal(report.unclaimedReward.toNumber(), unclaimedReward.toNumber());
+    assert.equal(
+      report.stakeLiabilities.toNumber(),
+      vault.totalStaked.sub(vault.destakedAfterEnd).toNumber()
+    );
+    assert.isAtLeast(
+      report.stakeBalance.toNumber(),
+      report.stakeLiabilities.toNumber()
+    );
+    assert.isAtLeast(
+      report.rewardBalance.toNumber(),
+      report.unclaimedReward.toNumber()
+    );
+  });
+
+  it("It reach USD soft cap from price feed", async () => {
+    const usdStakeMint = await createMint(
+      connection,
+      payer.payer,
+      payer.publicKey,
+      payer.publicKey,
+      0
+    );
+    const priceFeed = new PublicKey(PRICE_FEED);
+    const stalePriceFeed = new PublicKey(STALE_PRICE_FEED);
+    // $100 with 6 decimals, reached by 40 tokens at $2.50
+    const softCapUsd = 100_000_000;
+
+    await program.methods
+      .initialize(lockPeriod, lockExtendTime, new BN(softCap))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: usdStakeMint,
+      })
+      .rpc();
+    await program.methods
+      .createVault()
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: usdStakeMint,
+        rewardCurrencyMint: rewardCurrencyMint,
+      })
+      .rpc();
+
+    const userStakeTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer.payer,
+      usdStakeMint,
+      payer.publicKey
+    );
+    await mintTo(
+      connection,
+      payer.payer,
+      usdStakeMint,
+      userStakeTokenAccount.address,
+      payer.payer,
+      1000
+    );
+
+    const setPriceFeed = (feed: PublicKey, maxConfidenceBps: number) =>
+      program.methods
+        .setPriceFeed(new BN(softCapUsd), 60, maxConfidenceBps)
+        .accounts({
+          authority: payer.publicKey,
+          stakeCurrencyMint: usdStakeMint,
+          priceFeed: feed,
+        })
+        .rpc();
+    const stake = (amount: number, feed: PublicKey | null) =>
+      program.methods
+        .stake(new BN(amount))
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: usdStakeMint,
+          rewardCurrencyMint: rewardCurrencyMint,
+          priceFeed: feed,
+          allowlistEntry: null,
+          instructionsSysvar: null,
+        })
+        .rpc();
+    await setPriceFeed(stalePriceFeed, 100);
+    await expectError(stake(10, stalePriceFeed), "PriceStale");
+
+    // the confidence of the mock price is 4 bps
+    await setPriceFeed(priceFeed, 1);
+    await expectError(stake(10, priceFeed), "PriceConfidenceTooWide");
+
+    await setPriceFeed(priceFeed, 100);
+    await expectError(stake(10, null), "PriceFeedRequired");
+    await expectError(stake(10, stalePriceFeed), "InvalidPriceFeed");
+
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), usdStakeMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        rewardCurrencyMint.toBytes(),
+      ],
+      program.programId
+    );
+    const config = await program.account.stakeConfig.fetch(configPda);
+    assert.equal(config.priceFeed.toBase58(), PRICE_FEED);
+    assert.equal(config.softCapUsd.toNumber(), softCapUsd);
+
+    // $75
+    await stake(30, priceFeed);
+    let vault = await program.account.vault.fetch(vaultPda);
+    assert.equal(vault.reachSoftCap, false);
+
+    // $100, far below the token soft cap
+    await stake(10, priceFeed);
+    vault = await program.account.vault.fetch(vaultPda);
+    assert.equal(vault.totalStaked.toNumber(), 40);
+    assert.equal(vault.reachSoftCap, true);
+    assert.isAbove(vault.endTime.toNumber(), 0);
+  });
+
+  it("It stake in launch phases and claim per-phase rewards", async () => {
+    const phaseStakeMint = await createMint(
+      connection,
+      payer.payer,
+      payer.publicKey,
+      payer.publicKey,
+      0
+    );
+    // reached by the public round
+    const phaseSoftCap = 200;
+
+    await program.methods
+      .initialize(lockPeriod, lockExtendTime, new BN(phaseSoftCap))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: phaseStakeMint,
+      })
+      .rpc();
+    await program.methods
+      .createVault()
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: phaseStakeMint,
+        rewardCurrencyMint: rewardCurrencyMint,
+      })
+      .rpc();
+
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), phaseStakeMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        rewardCurrencyMint.toBytes(),
+      ],
+      program.programId
+    );
+    let [payerEntry] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(ALLOWLIST_SEED),
+        vaultPda.toBytes(),
+        Buffer.from([0]),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+
+    // allowlisted guaranteed round capped at 100 per wallet, then a public
+    // round with a third of its reward weight
+    const now = await chainTime();
+    const guaranteedEnd = now + 6;
+    const phaseAccounts = {
+      authority: payer.publicKey,
+      stakeCurrencyMint: phaseStakeMint,
+      rewardCurrencyMint: rewardCurrencyMint,
+    };
+    await program.methods
+      .setPhase(
+        0,
+        new BN(now - 10),
+        new BN(guaranteedEnd),
+        new BN(100),
+        3,
+        true
+      )
+      .accounts(phaseAccounts)
+      .rpc();
+    await program.methods
+      .setPhase(
+        1,
+        new BN(guaranteedEnd),
+        new BN(now + 1000),
+        new BN(0),
+        1,
+        false
+      )
+      .accounts(phaseAccounts)
+      .rpc();
+    await program.methods
+      .setAllowlistEntry(0, payer.publicKey, true)
+      .accounts(phaseAccounts)
+      .rpc();
+
+    for (const keypair of [payer.payer, user2]) {
+      const tokenAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        keypair,
+        phaseStakeMint,
+        keypair.publicKey
+      );
+      await mintTo(
+        connection,
+        payer.payer,
+        phaseStakeMint,
+        tokenAccount.address,
+        payer.payer,
+        1000
+      );
+    }
+
+    const stake = (
+      signer: Keypair,
+      amount: number,
+      allowlistEntry: PublicKey | null
+    ) =>
+      program.methods
+        .stake(new BN(amount))
+        .accounts({
+          signer: signer.publicKey,
+          stakeCurrencyMint: phaseStakeMint,
+          rewardCurrencyMint: rewardCurrencyMint,
+          priceFeed: null,
+          allowlistEntry,
+          instructionsSysvar: null,
+        })
+        .signers([signer])
+        .rpc();
+
+    await expectError(stake(user2, 50, null), "NotAllowlisted");
+    await expectError(stake(payer.payer, 150, payerEntry), "PhaseCapExceeded");
+    await stake(payer.payer, 100, payerEntry);
+
+    while ((await chainTime()) < guaranteedEnd) {
+      await setTimeout(1000);
+    }
+    await stake(user2, 100, null);
+
+    let vault = await program.account.vault.fetch(vaultPda);
+    assert.equal(vault.phaseCount, 2);
+    assert.equal(vault.phases[0].totalStaked.toNumber(), 100);
+    assert.equal(vault.phases[1].totalStaked.toNumber(), 100);
+    assert.equal(vault.reachSoftCap, true);
+
+    // weights of started phases are locked, their stakers joined for them
+    await expectError(
+      program.methods
+        .setPhase(
+          1,
+          new BN(guaranteedEnd),
+          new BN(now + 1000),
+          new BN(0),
+          2,
+          false
+        )
+        .accounts(phaseAccounts)
+        .rpc(),
+      "PhaseWeightLocked"
+    );
+
+    const stakeInfo = async (owner: PublicKey) => {
+      let [stakeInfoPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from(STAKE_INFO_SEED), vaultPda.toBytes(), owner.toBytes()],
+        program.programId
+      );
+      return await program.account.stakeInfo.fetch(stakeInfoPda);
+    };
+    const payerStake = await stakeInfo(payer.publicKey);
+    const user2Stake = await stakeInfo(user2.publicKey);
+    assert.equal(payerStake.phaseSnapshotAmounts[0].toNumber(), 100);
+    assert.equal(user2Stake.phaseSnapshotAmounts[1].toNumber(), 100);
+
+    // tge: 400 reward tokens split 3:1 between the two phase pools
+    const vaultRewardAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer.payer,
+      rewardCurrencyMint,
+      vaultPda,
+      true
+    );
+    await mintTo(
+      connection,
+      payer.payer,
+      rewardCurrencyMint,
+      vaultRewardAccount.address,
+      payer.payer,
+      400
+    );
+    await setTimeout((lockExtendTime + 2) * 1000);
+
+    for (const [keypair, reward] of [
+      [payer.payer, 300],
+      [user2, 100],
+    ] as [Keypair, number][]) {
+      const rewardAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        keypair,
+        rewardCurrencyMint,
+        keypair.publicKey
+      );
+      await program.methods
+        .claimReward()
+        .accounts({
+          signer: keypair.publicKey,
+          stakeCurrencyMint: phaseStakeMint,
+          rewardCurrencyMint,
+        })
+        .signers([keypair])
+        .rpc();
+      const after = await getAccount(connection, rewardAccount.address);
+      assert.equal(Number(after.amount) - Number(rewardAccount.amount), reward);
+    }
+  });
+
+  it("It stake with a KYC attestation", async () => {
+    const kycStakeMint = await createMint(
+      connection,
+      payer.payer,
+      payer.publicKey,
+      payer.publicKey,
+      0
+    );
+    const attestor = Keypair.generate();
+
+    await program.methods
+      .initialize(lockPeriod, lockExtendTime, new BN(softCap))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: kycStakeMint,
+      })
+      .rpc();
+    await program.methods
+      .createVault()
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: kycStakeMint,
+        rewardCurrencyMint: rewardCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .setKycAttestor(attestor.publicKey)
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: kycStakeMint,
+      })
+      .rpc();
+
+    const userStakeTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer.payer,
+      kycStakeMint,
+      payer.publicKey
+    );
+    await mintTo(
+      connection,
+      payer.payer,
+      kycStakeMint,
+      userStakeTokenAccount.address,
+      payer.payer,
+      1000
+    );
+
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), kycStakeMint.toBytes()],
+      program.programId
+    );
+    // stake config, wallet, expiry and max allocation, little endian
+    const attestation = (
+      signer: Keypair,
+      expiry: number,
+      maxAllocation: number
+    ) =>
+      Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: Buffer.concat([
+          configPda.toBuffer(),
+          payer.publicKey.toBuffer(),
+          new BN(expiry).toArrayLike(Buffer, "le", 8),
+          new BN(maxAllocation).toArrayLike(Buffer, "le", 8),
+        ]),
+      });
+    const stake = (amount: number, preInstructions: TransactionInstruction[]) =>
+      program.methods
+        .stake(new BN(amount))
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: kycStakeMint,
+          rewardCurrencyMint: rewardCurrencyMint,
+          priceFeed: null,
+          allowlistEntry: null,
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .preInstructions(preInstructions)
+        .rpc();
+
+    const now = await chainTime();
+    await expectError(stake(10, []), "KycRequired");
+    await expectError(
+      stake(10, [attestation(Keypair.generate(), now + 60, 100)]),
+      "InvalidKycAttestation"
+    );
+    await expectError(
+      stake(10, [attestation(attestor, now - 60, 100)]),
+      "KycAttestationExpired"
+    );
+    await expectError(
+      stake(150, [attestation(attestor, now + 60, 100)]),
+      "KycAllocationExceeded"
+    );
+    await stake(100, [attestation(attestor, now + 60, 100)]);
+
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        rewardCurrencyMint.toBytes(),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    const stakeInfo = await program.account.stakeInfo.fetch(userStakePda);
+    assert.equal(stakeInfo.stakeAmount.toNumber(), 100);
+  });
+
+  it("It refund oversubscribed sale stake at claim", async () => {
+    const saleStakeMint = await createMint(
+      connection,
+      payer.payer,
+      payer.publicKey,
+      payer.publicKey,
+      0
+    );
+    const saleSoftCap = 400;
+
+    await program.methods
+      .initialize(lockPeriod, lockExtendTime, new BN(saleSoftCap))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: saleStakeMint,
+      })
+      .rpc();
+    await program.methods
+      .createVault()
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: saleStakeMint,
+        rewardCurrencyMint: rewardCurrencyMint,
+      })
+      .rpc();
+
+    // 400 reward tokens at 2 per staking token, bought with 200 staked
+    const saleAccounts = {
+      authority: payer.publicKey,
+      stakeCurrencyMint: saleStakeMint,
+      rewardCurrencyMint: rewardCurrencyMint,
+    };
+    const saleRate = new BN(2_000_000_000);
+    await expectError(
+      program.methods
+        .setSaleTerms(saleRate, new BN(0))
+        .accounts(saleAccounts)
+        .rpc(),
+      "InvalidSaleTerms"
+    );
+    await program.methods
+      .setSaleTerms(saleRate, new BN(400))
+      .accounts(saleAccounts)
+      .rpc();
+
+    const stakeAccounts = new Map<Keypair, PublicKey>();
+    for (const keypair of [payer.payer, user2]) {
+      const tokenAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        keypair,
+        saleStakeMint,
+        keypair.publicKey
+      );
+      await mintTo(
+        connection,
+        payer.payer,
+        saleStakeMint,
+        tokenAccount.address,
+        payer.payer,
+        1000
+      );
+      stakeAccounts.set(keypair, tokenAccount.address);
+    }
+    const balance = async (keypair: Keypair) =>
+      Number(
+        (await getAccount(connection, stakeAccounts.get(keypair))).amount
+      );
+
+    // 400 staked for 200 worth of reward: half of every stake is refunded
+    for (const [keypair, amount] of [
+      [user2, 100],
+      [payer.payer, 300],
+    ] as [Keypair, number][]) {
+      await program.methods
+        .stake(new BN(amount))
+        .accounts({
+          signer: keypair.publicKey,
+          stakeCurrencyMint: saleStakeMint,
+          rewardCurrencyMint: rewardCurrencyMint,
+          priceFeed: null,
+          allowlistEntry: null,
+          instructionsSysvar: null,
+        })
+        .signers([keypair])
+        .rpc();
+    }
+    await expectError(
+      program.methods
+        .setSaleTerms(saleRate, new BN(800))
+        .accounts(saleAccounts)
+        .rpc(),
+      "SaleTermsLocked"
+    );
+
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), saleStakeMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        rewardCurrencyMint.toBytes(),
+      ],
+      program.programId
+    );
+    const vaultRewardAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer.payer,
+      rewardCurrencyMint,
+      vaultPda,
+      true
+    );
+    await mintTo(
+      connection,
+      payer.payer,
+      rewardCurrencyMint,
+      vaultRewardAccount.address,
+      payer.payer,
+      399
+    );
+    await setTimeout((lockExtendTime + 2) * 1000);
+
+    await expectError(
+      program.methods
+        .destake(new BN(100), false)
+        .accounts({
+          signer: user2.publicKey,
+          stakeCurrencyMint: saleStakeMint,
+          rewardCurrencyMint: rewardCurrencyMint,
+        })
+        .signers([user2])
+        .rpc(),
+      "StakeCommittedToSale"
+    );
+
+    const claim = (keypair: Keypair) =>
+      program.methods
+        .claimReward()
+        .accounts({
+          signer: keypair.publicKey,
+          stakeCurrencyMint: saleStakeMint,
+          rewardCurrencyMint,
+        })
+        .signers([keypair])
+        .rpc();
+
+    // every sold reward token must be funded before the first claim
+    await expectError(claim(user2), "TgeNotYetReached");
+    await mintTo(
+      connection,
+      payer.payer,
+      rewardCurrencyMint,
+      vaultRewardAccount.address,
+      payer.payer,
+      1
+    );
+
+    for (const [keypair, reward, refund] of [
+      [payer.payer, 300, 150],
+      [user2, 100, 50],
+    ] as [Keypair, number, number][]) {
+      const rewardAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        keypair,
+        rewardCurrencyMint,
+        keypair.publicKey
+      );
+      const stakeBefore = await balance(keypair);
+      await claim(keypair);
+      const after = await getAccount(connection, rewardAccount.address);
+      assert.equal(Number(after.amount) - Number(rewardAccount.amount), reward);
+      assert.equal((await balance(keypair)) - stakeBefore, refund);
+    }
+
+    let vault = await program.account.vault.fetch(vaultPda);
+    assert.equal(vault.totalReward.toNumber(), 400);
+    assert.equal(vault.saleProceeds.toNumber(), 200);
+
+    const proceedsBefore = await balance(payer.payer);
+    await program.methods
+      .withdrawSaleProceeds()
+      .accounts(saleAccounts)
+      .rpc();
+    assert.equal((await balance(payer.payer)) - proceedsBefore, 200);
+    await expectError(
+      program.methods
+        .withdrawSaleProceeds()
+        .accounts(saleAccounts)
+        .rpc(),
+      "NoTokens"
+    );
+  });
 });
@@ -10,15 +10,24 @@ import {
 import {
   createMint,
   getAccount,
+  getAssociatedTokenAddressSync,
+  getMint,
   getOrCreateAssociatedTokenAccount,
   mintTo,
   TOKEN_PROGRAM_ID,
+  transfer,
 } from "@solana/spl-token";
 import {
+  MAX_VOTER_WEIGHT_RECORD_SEED,
+  OPERATOR_SEED,
+  POSITION_MINT_SEED,
+  POSITION_RECEIPT_SEED,
   STAKE_CONFIG_SEED,
   STAKE_DETAIL_SEED,
   STAKER_INFO_SEED,
+  TOKEN_METADATA_PROGRAM_ID,
   VAULT_SEED,
+  VOTER_WEIGHT_RECORD_SEED,
 } from "./constants";
 import { assert } from "chai";
 import { setTimeout } from "timers/promises";
@@ -137,7 +146,7 @@ describe("vault", () => {
     assert.equal(vault.lockPeriod.toNumber(), lockPeriod);
     assert.equal(vault.totalStaked.toNumber(), 0);
     assert.equal(vault.vaultConfig.toBase58(), configPda.toBase58());
-    assert.equal(vault.version, 1);
+    assert.equal(vault.version, 2);
   });
 
   it("It stake", async () => {
@@ -201,6 +210,7 @@ describe("vault", () => {
         signer: payer.publicKey,
         stakeCurrencyMint: stakeCurrencyMint,
         stakeDetailPda: userStakeDetailPda,
+        operator: null,
       })
       .rpc();
     console.log("Your transaction signature stake", tx);
@@ -238,6 +248,7 @@ describe("vault", () => {
           signer: payer.publicKey,
           stakeCurrencyMint: stakeCurrencyMint,
           stakeDetailPda: userStakeDetailPda,
+          operator: null,
         })
         .rpc();
     } catch (error) {
@@ -259,6 +270,7 @@ describe("vault", () => {
         signer: payer.publicKey,
         stakeCurrencyMint: stakeCurrencyMint,
         stakeDetailPda: userStakeDetailPda,
+        operator: null,
       })
       .rpc();
 
@@ -285,7 +297,7 @@ describe("vault", () => {
     // case 1: unstake not finished unbonding yet
     try {
       await program.methods
-        .destake(stakeDetailId, new BN(lockPeriod), new BN(1))
+        .destake(stakeDetailId, new BN(lockPeriod), new BN(1), false)
         .accounts({
           signer: payer.publicKey,
           stakeCurrencyMint: stakeCurrencyMint,
@@ -299,7 +311,7 @@ describe("vault", () => {
 
     // case 2: successfully unstake
     await program.methods
-      .destake(stakeDetailId, new BN(lockPeriod), new BN(1000))
+      .destake(stakeDetailId, new BN(lockPeriod), new BN(1000), false)
       .accounts({
         signer: payer.publicKey,
         stakeCurrencyMint: stakeCurrencyMint,
@@ -351,7 +363,7 @@ describe("vault", () => {
     // case 2: cannot unstake more when amount = 0
     try {
       await program.methods
-        .destake(stakeDetailId, new BN(lockPeriod), new BN(1000))
+        .destake(stakeDetailId, new BN(lockPeriod), new BN(1000), false)
         .accounts({
           signer: payer.publicKey,
           stakeCurrencyMint: stakeCurrencyMint,
@@ -361,4 +373,1905 @@ describe("vault", () => {
       assert.include(JSON.stringify(error), "Tokens not staked");
     }
   });
+
+  it("It early unstake", async () => {
+    const earlyLockPeriod = 1000;
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(earlyLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [userStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        userStakePda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createVault(new BN(earlyLockPeriod))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .stake(new BN(earlyLockPeriod), new BN(1000))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        stakeDetailPda: userStakeDetailPda,
+        operator: null,
+      })
+      .rpc();
+
+    // case 1: early unstake is disabled by default
+    try {
+      await program.methods
+        .earlyDestake(new BN(1), new BN(earlyLockPeriod), new BN(1000))
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+          treasuryTokenAccount: null,
+        })
+        .rpc();
+      assert.fail("early unstake should be disabled");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "EarlyUnstakeNotAllowed");
+    }
+
+    // case 2: penalty is sent to treasury
+    const treasuryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer.payer,
+      stakeCurrencyMint,
+      user2.publicKey
+    );
+    await program.methods
+      .setEarlyUnstakeConfig(
+        new BN(earlyLockPeriod),
+        true,
+        5000,
+        false,
+        user2.publicKey
+      )
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .earlyDestake(new BN(1), new BN(earlyLockPeriod), new BN(1000))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        treasuryTokenAccount: treasuryTokenAccount.address,
+      })
+      .rpc();
+
+    // penalty decays from 50% right after staking
+    const treasuryBalance = Number(
+      (await getAccount(connection, treasuryTokenAccount.address)).amount
+    );
+    assert.isAbove(treasuryBalance, 0);
+    assert.isAtMost(treasuryBalance, 500);
+
+    let vaultInfo = await program.account.vault.fetch(vaultPda);
+    assert.equal(vaultInfo.totalStaked.toNumber(), 0);
+    const userStakeDetail = await program.account.stakeDetail.fetch(
+      userStakeDetailPda
+    );
+    assert.equal(userStakeDetail.stakeAmount.toNumber(), 0);
+
+    // case 3: a partial early unstaker gets no part of its own penalty, with
+    // nobody else staked it goes to treasury even when redistributing
+    const soloLockPeriod = earlyLockPeriod + 1;
+    let [soloVaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(soloLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [soloStakerPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        soloVaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [soloStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        soloStakerPda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createVault(new BN(soloLockPeriod))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .setEarlyUnstakeConfig(
+        new BN(soloLockPeriod),
+        true,
+        5000,
+        true,
+        user2.publicKey
+      )
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .stake(new BN(soloLockPeriod), new BN(1000))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        stakeDetailPda: soloStakeDetailPda,
+        operator: null,
+      })
+      .rpc();
+    await program.methods
+      .earlyDestake(new BN(1), new BN(soloLockPeriod), new BN(500))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        treasuryTokenAccount: treasuryTokenAccount.address,
+      })
+      .rpc();
+
+    const treasuryBalanceAfter = Number(
+      (await getAccount(connection, treasuryTokenAccount.address)).amount
+    );
+    assert.isAbove(treasuryBalanceAfter, treasuryBalance);
+    vaultInfo = await program.account.vault.fetch(soloVaultPda);
+    assert.equal(vaultInfo.totalStaked.toNumber(), 500);
+    assert.equal(vaultInfo.accPenaltyPerShare.toNumber(), 0);
+    assert.equal(vaultInfo.undistributedPenalty.toNumber(), 0);
+
+    // case 4: redistributed penalty is held for the other positions until
+    // they are paid out
+    let [soloStakeDetail2Pda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        soloStakerPda.toBytes(),
+        new BN(2).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .stake(new BN(soloLockPeriod), new BN(1000))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        stakeDetailPda: soloStakeDetail2Pda,
+        operator: null,
+      })
+      .rpc();
+    await program.methods
+      .earlyDestake(new BN(1), new BN(soloLockPeriod), new BN(100))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        treasuryTokenAccount: null,
+      })
+      .rpc();
+    const soloVaultTokenAccount = getAssociatedTokenAddressSync(
+      stakeCurrencyMint,
+      soloVaultPda,
+      true
+    );
+    const soloSurplus = async () => {
+      const info = await program.account.vault.fetch(soloVaultPda);
+      const balance = (await getAccount(connection, soloVaultTokenAccount))
+        .amount;
+      return (
+        Number(balance) -
+        info.totalStaked.toNumber() -
+        info.undistributedPenalty.toNumber()
+      );
+    };
+    vaultInfo = await program.account.vault.fetch(soloVaultPda);
+    assert.isAbove(vaultInfo.undistributedPenalty.toNumber(), 0);
+    assert.equal(await soloSurplus(), 0);
+
+    // the share of position 2 is paid with its own early unstake
+    await program.methods
+      .earlyDestake(new BN(2), new BN(soloLockPeriod), new BN(1))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        treasuryTokenAccount: null,
+      })
+      .rpc();
+    vaultInfo = await program.account.vault.fetch(soloVaultPda);
+    assert.isAtMost(vaultInfo.undistributedPenalty.toNumber(), 1);
+    assert.isAtLeast(await soloSurplus(), 0);
+    assert.isAtMost(await soloSurplus(), 1);
+  });
+
+  it("It split and merge positions", async () => {
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(lockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    const [stakeDetailPda, newStakeDetailPda] = [2, 3].map(
+      (id) =>
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from(STAKE_DETAIL_SEED),
+            userStakePda.toBytes(),
+            new BN(id).toBuffer("le", 8),
+          ],
+          program.programId
+        )[0]
+    );
+
+    // case 1: cannot split the whole position
+    try {
+      await program.methods
+        .splitPosition(new BN(2), new BN(lockPeriod), new BN(50))
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+          newStakeDetail: newStakeDetailPda,
+        })
+        .rpc();
+      assert.fail("split should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "InvalidSplitAmount");
+    }
+
+    // case 2: split 20 out of 50 into a new position with the same unlock time
+    await program.methods
+      .splitPosition(new BN(2), new BN(lockPeriod), new BN(20))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        newStakeDetail: newStakeDetailPda,
+      })
+      .rpc();
+
+    let stakeDetail = await program.account.stakeDetail.fetch(stakeDetailPda);
+    const newStakeDetail = await program.account.stakeDetail.fetch(
+      newStakeDetailPda
+    );
+    assert.equal(stakeDetail.stakeAmount.toNumber(), 30);
+    assert.equal(newStakeDetail.stakeAmount.toNumber(), 20);
+    assert.equal(newStakeDetail.id.toNumber(), 3);
+    assert.equal(
+      newStakeDetail.unstakedAtTime.toNumber(),
+      stakeDetail.unstakedAtTime.toNumber()
+    );
+    let userStakeInfo = await program.account.stakerInfo.fetch(userStakePda);
+    assert.equal(userStakeInfo.currentId.toNumber(), 3);
+    assert.equal(userStakeInfo.totalStake.toNumber(), 50);
+
+    // case 3: merge the new position back
+    await program.methods
+      .mergePositions(new BN(lockPeriod), new BN(2), [new BN(3)])
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .remainingAccounts([
+        { pubkey: newStakeDetailPda, isSigner: false, isWritable: true },
+      ])
+      .rpc();
+
+    stakeDetail = await program.account.stakeDetail.fetch(stakeDetailPda);
+    assert.equal(stakeDetail.stakeAmount.toNumber(), 50);
+    assert.isNull(
+      await program.account.stakeDetail.fetchNullable(newStakeDetailPda)
+    );
+  });
+
+  it("It transfer position", async () => {
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(lockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    const [userStakePda, recipientStakePda] = [
+      payer.publicKey,
+      user2.publicKey,
+    ].map(
+      (owner) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from(STAKER_INFO_SEED), vaultPda.toBytes(), owner.toBytes()],
+          program.programId
+        )[0]
+    );
+    let [userStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        userStakePda.toBytes(),
+        new BN(2).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [recipientStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        recipientStakePda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    const userStakeDetail = await program.account.stakeDetail.fetch(
+      userStakeDetailPda
+    );
+
+    await program.methods
+      .transferPosition(new BN(2), new BN(lockPeriod))
+      .accounts({
+        signer: payer.publicKey,
+        recipient: user2.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        recipientStakeDetail: recipientStakeDetailPda,
+      })
+      .rpc();
+
+    // validate. Lock is preserved and the old position is closed
+    const recipientStakeDetail = await program.account.stakeDetail.fetch(
+      recipientStakeDetailPda
+    );
+    assert.equal(recipientStakeDetail.stakeAmount.toNumber(), 50);
+    assert.equal(recipientStakeDetail.id.toNumber(), 1);
+    assert.equal(
+      recipientStakeDetail.staker.toBase58(),
+      user2.publicKey.toBase58()
+    );
+    assert.equal(
+      recipientStakeDetail.unstakedAtTime.toNumber(),
+      userStakeDetail.unstakedAtTime.toNumber()
+    );
+    assert.isNull(
+      await program.account.stakeDetail.fetchNullable(userStakeDetailPda)
+    );
+
+    const userStakeInfo = await program.account.stakerInfo.fetch(userStakePda);
+    assert.equal(userStakeInfo.totalStake.toNumber(), 0);
+    const recipientStakeInfo = await program.account.stakerInfo.fetch(
+      recipientStakePda
+    );
+    assert.equal(recipientStakeInfo.totalStake.toNumber(), 50);
+    assert.equal(recipientStakeInfo.currentId.toNumber(), 1);
+  });
+
+  it("It destake with position receipt", async () => {
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(lockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [user2StakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        user2.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [user2StakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        user2StakePda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [receiptMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from(POSITION_MINT_SEED), user2StakeDetailPda.toBytes()],
+      program.programId
+    );
+    let [receiptPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(POSITION_RECEIPT_SEED), receiptMint.toBytes()],
+      program.programId
+    );
+
+    const metadataProgram = new PublicKey(TOKEN_METADATA_PROGRAM_ID);
+    let [receiptMetadata] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        metadataProgram.toBytes(),
+        receiptMint.toBytes(),
+      ],
+      metadataProgram
+    );
+
+    // case 1: tokenize user2's position, with metaplex metadata
+    await program.methods
+      .setReceiptUri("https://positions.example.com/")
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .mintPositionReceipt(new BN(1), new BN(lockPeriod))
+      .accounts({
+        signer: user2.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .signers([user2])
+      .rpc();
+
+    const metadata = await connection.getAccountInfo(receiptMetadata);
+    assert.equal(metadata.owner.toBase58(), TOKEN_METADATA_PROGRAM_ID);
+    assert.include(
+      metadata.data.toString(),
+      "https://positions.example.com/" + receiptMint.toBase58()
+    );
+
+    const mint = await getMint(connection, receiptMint);
+    assert.equal(mint.decimals, 0);
+    assert.equal(Number(mint.supply), 1);
+    assert.isNull(mint.mintAuthority);
+    const receipt = await program.account.positionReceipt.fetch(receiptPda);
+    assert.equal(receipt.stakeAmount.toNumber(), 50);
+    assert.equal(receipt.lockPeriod.toNumber(), lockPeriod);
+    assert.equal(
+      receipt.stakeDetail.toBase58(),
+      user2StakeDetailPda.toBase58()
+    );
+
+    // case 2: tokenized position cannot be destaked directly
+    try {
+      await program.methods
+        .destake(new BN(1), new BN(lockPeriod), new BN(50), false)
+        .accounts({
+          signer: user2.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+          stakerTokenAccount: (
+            await getOrCreateAssociatedTokenAccount(
+              connection,
+              payer.payer,
+              stakeCurrencyMint,
+              user2.publicKey
+            )
+          ).address,
+        })
+        .signers([user2])
+        .rpc();
+      assert.fail("destake should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "PositionTokenized");
+    }
+
+    // case 3: receipt holder redeems the position
+    const payerReceiptTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer.payer,
+      receiptMint,
+      payer.publicKey
+    );
+    await transfer(
+      connection,
+      payer.payer,
+      getAssociatedTokenAddressSync(receiptMint, user2.publicKey),
+      payerReceiptTokenAccount.address,
+      user2,
+      1
+    );
+    const payerTokenAccount = getAssociatedTokenAddressSync(
+      stakeCurrencyMint,
+      payer.publicKey
+    );
+    const balanceBefore = (await getAccount(connection, payerTokenAccount))
+      .amount;
+    const stakerLamportsBefore = await connection.getBalance(user2.publicKey);
+
+    // receipts unbond like any position when the vault has an unbonding period
+    await program.methods
+      .setUnbondingPeriod(new BN(lockPeriod), new BN(2))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    const redeemAccounts = {
+      signer: payer.publicKey,
+      receiptMint: receiptMint,
+      staker: user2.publicKey,
+      stakerInfo: user2StakePda,
+      stakeDetail: user2StakeDetailPda,
+      stakeCurrencyMint: stakeCurrencyMint,
+    };
+    await program.methods
+      .destakeWithReceipt(new BN(lockPeriod))
+      .accounts(redeemAccounts)
+      .rpc();
+    const unbondingDetail = await program.account.stakeDetail.fetch(
+      user2StakeDetailPda
+    );
+    assert.isAbove(unbondingDetail.unbondingStartedAt.toNumber(), 0);
+    assert.equal(unbondingDetail.stakeAmount.toNumber(), 50);
+    assert.equal(Number((await getMint(connection, receiptMint)).supply), 1);
+
+    // the original staker cannot withdraw a tokenized position
+    try {
+      await program.methods
+        .withdraw(new BN(1), new BN(lockPeriod), false)
+        .accounts({
+          signer: user2.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+        })
+        .signers([user2])
+        .rpc();
+      assert.fail("withdraw should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "PositionTokenized");
+    }
+
+    await setTimeout(3000);
+    await program.methods
+      .destakeWithReceipt(new BN(lockPeriod))
+      .accounts(redeemAccounts)
+      .rpc();
+
+    const balanceAfter = (await getAccount(connection, payerTokenAccount))
+      .amount;
+    assert.equal(Number(balanceAfter) - Number(balanceBefore), 50);
+    assert.equal(Number((await getMint(connection, receiptMint)).supply), 0);
+    assert.isNull(
+      await program.account.positionReceipt.fetchNullable(receiptPda)
+    );
+    // receipt rent goes back to the original staker, not the holder
+    assert.isAbove(
+      await connection.getBalance(user2.publicKey),
+      stakerLamportsBefore
+    );
+    const user2StakeInfo = await program.account.stakerInfo.fetch(
+      user2StakePda
+    );
+    assert.equal(user2StakeInfo.totalStake.toNumber(), 0);
+
+    await program.methods
+      .setUnbondingPeriod(new BN(lockPeriod), new BN(0))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+  });
+
+  it("It destake many", async () => {
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(lockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    const [drainedStakeDetailPda, ...stakeDetailPdas] = [1, 4, 5].map(
+      (id) =>
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from(STAKE_DETAIL_SEED),
+            userStakePda.toBytes(),
+            new BN(id).toBuffer("le", 8),
+          ],
+          program.programId
+        )[0]
+    );
+
+    for (const stakeDetailPda of stakeDetailPdas) {
+      await program.methods
+        .stake(new BN(lockPeriod), new BN(10))
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+          stakeDetailPda: stakeDetailPda,
+          operator: null,
+        })
+        .rpc();
+    }
+
+    await setTimeout(lockPeriod * 1000);
+
+    const payerTokenAccount = getAssociatedTokenAddressSync(
+      stakeCurrencyMint,
+      payer.publicKey
+    );
+    const balanceBefore = (await getAccount(connection, payerTokenAccount))
+      .amount;
+
+    // drained position is skipped, matured positions are paid in one transfer
+    await program.methods
+      .destakeMany(new BN(lockPeriod))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .remainingAccounts(
+        [drainedStakeDetailPda, ...stakeDetailPdas].map((pubkey) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: true,
+        }))
+      )
+      .rpc();
+
+    const balanceAfter = (await getAccount(connection, payerTokenAccount))
+      .amount;
+    assert.equal(Number(balanceAfter) - Number(balanceBefore), 20);
+
+    for (const stakeDetailPda of stakeDetailPdas) {
+      const stakeDetail = await program.account.stakeDetail.fetch(
+        stakeDetailPda
+      );
+      assert.equal(stakeDetail.stakeAmount.toNumber(), 0);
+    }
+    const userStakeInfo = await program.account.stakerInfo.fetch(userStakePda);
+    assert.equal(userStakeInfo.totalStake.toNumber(), 0);
+  });
+
+  it("It close drained accounts", async () => {
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(lockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+
+    // case 1: drained stake details are still open, staker info cannot close
+    try {
+      await program.methods
+        .closeStakerInfo(new BN(lockPeriod))
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+        })
+        .rpc();
+      assert.fail("close staker info should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "OpenPositionsRemaining");
+    }
+
+    // case 2: close every stake detail, then the staker info
+    for (const id of [1, 4, 5]) {
+      await program.methods
+        .closePosition(new BN(id), new BN(lockPeriod))
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+        })
+        .rpc();
+      let [stakeDetailPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from(STAKE_DETAIL_SEED),
+          userStakePda.toBytes(),
+          new BN(id).toBuffer("le", 8),
+        ],
+        program.programId
+      );
+      assert.isNull(
+        await program.account.stakeDetail.fetchNullable(stakeDetailPda)
+      );
+    }
+
+    await program.methods
+      .closeStakerInfo(new BN(lockPeriod))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    assert.isNull(await program.account.stakerInfo.fetchNullable(userStakePda));
+  });
+
+  it("It enforce vault limits", async () => {
+    const earlyLockPeriod = 1000;
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(earlyLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [userStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        userStakePda.toBytes(),
+        new BN(2).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    const stake = (amount: number) =>
+      program.methods
+        .stake(new BN(earlyLockPeriod), new BN(amount))
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+          stakeDetailPda: userStakeDetailPda,
+          operator: null,
+        })
+        .rpc();
+
+    await program.methods
+      .setVaultLimits(
+        new BN(earlyLockPeriod),
+        new BN(100),
+        new BN(60),
+        new BN(10)
+      )
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+
+    // case 1: dust stake
+    try {
+      await stake(5);
+      assert.fail("stake should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "StakeAmountTooLow");
+    }
+
+    // case 2: above per staker cap
+    try {
+      await stake(70);
+      assert.fail("stake should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "StakerCapExceeded");
+    }
+
+    // case 3: within limits
+    await stake(60);
+    const vaultInfo = await program.account.vault.fetch(vaultPda);
+    assert.equal(vaultInfo.totalStaked.toNumber(), 60);
+    assert.equal(vaultInfo.maxTotalStaked.toNumber(), 100);
+  });
+
+  it("It manage lock periods and vault status", async () => {
+    const managedLockPeriod = 50;
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(managedLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [userStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        userStakePda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .setLockPeriodBounds(new BN(10), new BN(100))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+
+    // case 1: lock period out of bounds
+    try {
+      await program.methods
+        .createVault(new BN(5))
+        .accounts({
+          authority: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+        })
+        .rpc();
+      assert.fail("create vault should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "IncorrectLockPeriod");
+    }
+
+    // case 2: inactive vault does not accept new stakes
+    await program.methods
+      .createVault(new BN(managedLockPeriod))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .setVaultStatus(new BN(managedLockPeriod), false)
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    try {
+      await program.methods
+        .stake(new BN(managedLockPeriod), new BN(10))
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+          stakeDetailPda: userStakeDetailPda,
+          operator: null,
+        })
+        .rpc();
+      assert.fail("stake should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "VaultInactive");
+    }
+
+    // case 3: empty vault can be closed
+    await program.methods
+      .closeVault(new BN(managedLockPeriod))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    assert.isNull(await program.account.vault.fetchNullable(vaultPda));
+
+    // restore default bounds
+    await program.methods
+      .setLockPeriodBounds(new BN(1), new BN(5 * 365 * 24 * 60 * 60))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+  });
+
+  it("It unbond then withdraw", async () => {
+    const unbondingLockPeriod = 2;
+    const unbondingPeriod = 2;
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(unbondingLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [userStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        userStakePda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createVault(new BN(unbondingLockPeriod))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .setUnbondingPeriod(new BN(unbondingLockPeriod), new BN(unbondingPeriod))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .stake(new BN(unbondingLockPeriod), new BN(10))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        stakeDetailPda: userStakeDetailPda,
+        operator: null,
+      })
+      .rpc();
+
+    await setTimeout(unbondingLockPeriod * 1000);
+
+    // case 1: immediate destake is disabled
+    try {
+      await program.methods
+        .destake(new BN(1), new BN(unbondingLockPeriod), new BN(10), false)
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+        })
+        .rpc();
+      assert.fail("destake should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "UnbondingRequired");
+    }
+
+    // case 2: request unstake, tokens leave total staked
+    await program.methods
+      .requestUnstake(new BN(1), new BN(unbondingLockPeriod))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    let vaultInfo = await program.account.vault.fetch(vaultPda);
+    assert.equal(vaultInfo.totalStaked.toNumber(), 0);
+    assert.equal(vaultInfo.totalUnbonding.toNumber(), 10);
+
+    // case 3: cannot withdraw during unbonding
+    try {
+      await program.methods
+        .withdraw(new BN(1), new BN(unbondingLockPeriod), false)
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+        })
+        .rpc();
+      assert.fail("withdraw should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "UnbondingTimeNotOverYet");
+    }
+
+    await setTimeout(unbondingPeriod * 1000);
+
+    // case 4: withdraw after unbonding closes the drained position
+    await program.methods
+      .withdraw(new BN(1), new BN(unbondingLockPeriod), true)
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    vaultInfo = await program.account.vault.fetch(vaultPda);
+    assert.equal(vaultInfo.totalUnbonding.toNumber(), 0);
+    const userStakeInfo = await program.account.stakerInfo.fetch(userStakePda);
+    assert.equal(userStakeInfo.totalStake.toNumber(), 0);
+    assert.isNull(
+      await program.account.stakeDetail.fetchNullable(userStakeDetailPda)
+    );
+  });
+
+  it("It auto renew locks", async () => {
+    const renewLockPeriod = 4;
+    const renewWindow = 1;
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(renewLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [userStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        userStakePda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createVault(new BN(renewLockPeriod))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .setRenewWindow(new BN(renewLockPeriod), new BN(renewWindow))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .stake(new BN(renewLockPeriod), new BN(10))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        stakeDetailPda: userStakeDetailPda,
+        operator: null,
+      })
+      .rpc();
+    await program.methods
+      .enableAutoRenew(new BN(1), new BN(renewLockPeriod))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    // the window is kept on the position, closing it later does not apply
+    await program.methods
+      .setRenewWindow(new BN(renewLockPeriod), new BN(0))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    const stakeDetailBefore = await program.account.stakeDetail.fetch(
+      userStakeDetailPda
+    );
+    assert.equal(stakeDetailBefore.renewWindow.toNumber(), renewWindow);
+
+    // case 1: withdrawal window missed, the lock rolled over
+    await setTimeout((renewLockPeriod + renewWindow + 1) * 1000);
+    try {
+      await program.methods
+        .destake(new BN(1), new BN(renewLockPeriod), new BN(10), false)
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+        })
+        .rpc();
+      assert.fail("destake should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "UnbondingTimeNotOverYet");
+    }
+
+    // case 2: disabling auto renew matures the position at the end of the current cycle
+    await program.methods
+      .disableAutoRenew(new BN(1), new BN(renewLockPeriod))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    const stakeDetailAfter = await program.account.stakeDetail.fetch(
+      userStakeDetailPda
+    );
+    assert.equal(stakeDetailAfter.autoRenew, false);
+    assert.equal(
+      stakeDetailAfter.unstakedAtTime.toNumber(),
+      stakeDetailBefore.unstakedAtTime.toNumber() + renewLockPeriod
+    );
+  });
+
+  it("It grant and revoke stake", async () => {
+    const grantLockPeriod = 100;
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(grantLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [beneficiaryStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        user2.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [beneficiaryStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        beneficiaryStakePda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+
+    let [grantorStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [grantorStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        grantorStakePda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createVault(new BN(grantLockPeriod))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .grantStake(new BN(grantLockPeriod), new BN(100), true)
+      .accounts({
+        grantor: payer.publicKey,
+        beneficiary: user2.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        stakeDetail: beneficiaryStakeDetailPda,
+      })
+      .rpc();
+
+    let stakeDetail = await program.account.stakeDetail.fetch(
+      beneficiaryStakeDetailPda
+    );
+    assert.equal(stakeDetail.stakeAmount.toNumber(), 100);
+    assert.equal(stakeDetail.staker.toBase58(), user2.publicKey.toBase58());
+    assert.equal(stakeDetail.grantor.toBase58(), payer.publicKey.toBase58());
+    assert.equal(stakeDetail.revocable, true);
+
+    // case 1: beneficiary cannot move a revocable grant
+    try {
+      await program.methods
+        .transferPosition(new BN(1), new BN(grantLockPeriod))
+        .accounts({
+          signer: user2.publicKey,
+          recipient: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+          recipientStakeDetail: grantorStakeDetailPda,
+        })
+        .signers([user2])
+        .rpc();
+      assert.fail("transfer should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "PositionRevocable");
+    }
+
+    // case 2: grantor takes back the unvested part, the vested part stays locked
+    await setTimeout(2000);
+    await program.methods
+      .revokeGrant(new BN(1), new BN(grantLockPeriod))
+      .accounts({
+        grantor: payer.publicKey,
+        beneficiary: user2.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    stakeDetail = await program.account.stakeDetail.fetch(
+      beneficiaryStakeDetailPda
+    );
+    assert.equal(stakeDetail.revocable, false);
+    assert.isAbove(stakeDetail.stakeAmount.toNumber(), 0);
+    assert.isBelow(stakeDetail.stakeAmount.toNumber(), 100);
+    const vault = await program.account.vault.fetch(vaultPda);
+    assert.equal(
+      vault.totalStaked.toNumber(),
+      stakeDetail.stakeAmount.toNumber()
+    );
+
+    // case 3: revocation is one-off
+    try {
+      await program.methods
+        .revokeGrant(new BN(1), new BN(grantLockPeriod))
+        .accounts({
+          grantor: payer.publicKey,
+          beneficiary: user2.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+        })
+        .rpc();
+      assert.fail("revoke should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "GrantNotRevocable");
+    }
+  });
+
+  it("It compute voting power", async () => {
+    // lock ends are rounded up to 28 day epochs
+    const veEpoch = 28 * 24 * 60 * 60;
+    const veLockPeriod = 8 * veEpoch;
+    const maxLockPeriod = 16 * veEpoch;
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(veLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [userStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        userStakePda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .setLockPeriodBounds(new BN(1), new BN(maxLockPeriod))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .createVault(new BN(veLockPeriod))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .stake(new BN(veLockPeriod), new BN(10000))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        stakeDetailPda: userStakeDetailPda,
+        operator: null,
+      })
+      .rpc();
+
+    // power = amount * remaining_lock / max_lock, decaying linearly
+    const stakeDetail = await program.account.stakeDetail.fetch(
+      userStakeDetailPda
+    );
+    const unlockTime = stakeDetail.unstakedAtTime.toNumber();
+    const veEnd = stakeDetail.veEnd.toNumber();
+    assert.equal(veEnd % veEpoch, 0);
+    assert.isAtLeast(veEnd, unlockTime);
+    assert.isBelow(veEnd, unlockTime + veEpoch);
+    const votingPowerAt = (timestamp: number) =>
+      program.methods
+        .getVotingPower(payer.publicKey, new BN(timestamp))
+        .accounts({ stakeCurrencyMint: stakeCurrencyMint })
+        .remainingAccounts(
+          [vaultPda, userStakeDetailPda].map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: false,
+          }))
+        )
+        .view();
+
+    const fullPower = await votingPowerAt(veEnd - veLockPeriod);
+    assert.closeTo(fullPower.toNumber(), 5000, 1);
+    const halfPower = await votingPowerAt(veEnd - veLockPeriod / 2);
+    assert.closeTo(halfPower.toNumber(), 2500, 1);
+    const expiredPower = await votingPowerAt(veEnd);
+    assert.equal(expiredPower.toNumber(), 0);
+
+    const totalPowerAt = (timestamp: number) =>
+      program.methods
+        .getTotalVotingPower(new BN(timestamp))
+        .accounts({ stakeCurrencyMint: stakeCurrencyMint })
+        .view();
+    const totalPower = await totalPowerAt(veEnd - veLockPeriod / 2);
+    assert.isAtLeast(totalPower.toNumber(), 2499);
+
+    // expired positions stop decaying, only auto-renewed power is left
+    const config = await program.account.stakeConfig.fetch(configPda);
+    const fixedPower = config.veFixedPower.div(new BN(10).pow(new BN(12)));
+    const futurePower = await totalPowerAt(veEnd + 70 * veEpoch);
+    assert.equal(futurePower.toNumber(), fixedPower.toNumber());
+
+    await program.methods
+      .setLockPeriodBounds(new BN(1), new BN(5 * 365 * 24 * 60 * 60))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+  });
+
+  it("It update governance voter weight records", async () => {
+    const veLockPeriod = 8 * 28 * 24 * 60 * 60;
+    const realm = Keypair.generate().publicKey;
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(veLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [userStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        userStakePda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [voterWeightRecordPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VOTER_WEIGHT_RECORD_SEED),
+        realm.toBytes(),
+        stakeCurrencyMint.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [maxVoterWeightRecordPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(MAX_VOTER_WEIGHT_RECORD_SEED),
+        realm.toBytes(),
+        stakeCurrencyMint.toBytes(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createVoterWeightRecord(realm)
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    // votes need the realm, governance and proposal accounts of spl-governance,
+    // the Rust tests in fuzz/ cover the vote lock
+    const proposal = Keypair.generate().publicKey;
+    try {
+      await program.methods
+        .updateVoterWeightRecord(realm, { castVote: {} }, proposal)
+        .accounts({
+          signer: payer.publicKey,
+          governanceRealm: null,
+          governance: null,
+          proposal: null,
+          stakeCurrencyMint: stakeCurrencyMint,
+        })
+        .remainingAccounts([
+          { pubkey: vaultPda, isSigner: false, isWritable: false },
+          { pubkey: userStakeDetailPda, isSigner: false, isWritable: true },
+        ])
+        .rpc();
+      assert.fail("cast vote without a proposal should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "ProposalRequired");
+    }
+    await program.methods
+      .updateVoterWeightRecord(realm, { createProposal: {} }, proposal)
+      .accounts({
+        signer: payer.publicKey,
+        governanceRealm: null,
+        governance: null,
+        proposal: null,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .remainingAccounts([
+        { pubkey: vaultPda, isSigner: false, isWritable: false },
+        { pubkey: userStakeDetailPda, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+    await program.methods
+      .createMaxVoterWeightRecord(realm)
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .updateMaxVoterWeightRecord(realm)
+      .accounts({
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+
+    // validate. Weight comes from the locked position of the vote-escrow test
+    const voterWeightRecord = await program.account.voterWeightRecord.fetch(
+      voterWeightRecordPda
+    );
+    assert.equal(voterWeightRecord.realm.toBase58(), realm.toBase58());
+    assert.equal(
+      voterWeightRecord.governingTokenMint.toBase58(),
+      stakeCurrencyMint.toBase58()
+    );
+    assert.equal(
+      voterWeightRecord.governingTokenOwner.toBase58(),
+      payer.publicKey.toBase58()
+    );
+    assert.isAbove(voterWeightRecord.voterWeight.toNumber(), 0);
+    assert.isAtMost(voterWeightRecord.voterWeight.toNumber(), 5000);
+    assert.isAbove(voterWeightRecord.voterWeightExpiry.toNumber(), 0);
+    assert.deepEqual(voterWeightRecord.weightAction, { createProposal: {} });
+    assert.equal(
+      voterWeightRecord.weightActionTarget.toBase58(),
+      proposal.toBase58()
+    );
+
+    // only votes lock positions
+    const stakeDetail = await program.account.stakeDetail.fetch(
+      userStakeDetailPda
+    );
+    assert.equal(stakeDetail.voteLockedUntil.toNumber(), 0);
+
+    const maxVoterWeightRecord =
+      await program.account.maxVoterWeightRecord.fetch(
+        maxVoterWeightRecordPda
+      );
+    assert.isAtLeast(
+      maxVoterWeightRecord.maxVoterWeight.toNumber(),
+      voterWeightRecord.voterWeight.toNumber()
+    );
+  });
+
+  it("It compute time-weighted average balance", async () => {
+    const twabLockPeriod = 600;
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(twabLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    const [firstStakeDetailPda, secondStakeDetailPda] = [1, 2].map(
+      (id) =>
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from(STAKE_DETAIL_SEED),
+            userStakePda.toBytes(),
+            new BN(id).toBuffer("le", 8),
+          ],
+          program.programId
+        )[0]
+    );
+
+    await program.methods
+      .createVault(new BN(twabLockPeriod))
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    for (const stakeDetailPda of [firstStakeDetailPda, secondStakeDetailPda]) {
+      await program.methods
+        .stake(new BN(twabLockPeriod), new BN(100))
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+          stakeDetailPda: stakeDetailPda,
+          operator: null,
+        })
+        .rpc();
+      await setTimeout(2000);
+    }
+
+    const stakerInfo = await program.account.stakerInfo.fetch(userStakePda);
+    assert.equal(stakerInfo.checkpointCount.toNumber(), 2);
+    const [first, second] = stakerInfo.checkpoints;
+    assert.equal(first.totalStake.toNumber(), 100);
+    assert.equal(second.totalStake.toNumber(), 200);
+
+    const twab = (start: number, end: number) =>
+      program.methods
+        .getTwab(
+          payer.publicKey,
+          new BN(twabLockPeriod),
+          new BN(start),
+          new BN(end)
+        )
+        .accounts({ stakeCurrencyMint: stakeCurrencyMint })
+        .view();
+
+    const t0 = first.timestamp.toNumber();
+    const t1 = second.timestamp.toNumber();
+    assert.equal((await twab(t0 - 10, t0)).toNumber(), 0);
+    assert.equal((await twab(t0, t1)).toNumber(), 100);
+    assert.equal((await twab(t0, 2 * t1 - t0)).toNumber(), 150);
+
+    try {
+      await twab(t1, t0);
+      assert.fail("twab should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "InvalidTwabRange");
+    }
+  });
+
+  it("It delegate to operators", async () => {
+    const twabLockPeriod = 600;
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(twabLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    let [userStakePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKER_INFO_SEED),
+        vaultPda.toBytes(),
+        payer.publicKey.toBytes(),
+      ],
+      program.programId
+    );
+    let [userStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        userStakePda.toBytes(),
+        new BN(1).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    const [payerOperatorPda, user2OperatorPda] = [
+      payer.publicKey,
+      user2.publicKey,
+    ].map(
+      (owner) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from(OPERATOR_SEED), configPda.toBytes(), owner.toBytes()],
+          program.programId
+        )[0]
+    );
+    const user2TokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer.payer,
+      stakeCurrencyMint,
+      user2.publicKey
+    );
+
+    await program.methods
+      .registerOperator(1000)
+      .accounts({
+        signer: user2.publicKey,
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .signers([user2])
+      .rpc();
+    await program.methods
+      .registerOperator(500)
+      .accounts({
+        signer: payer.publicKey,
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+
+    const votingPowerOf = (owner: PublicKey, timestamp: number) =>
+      program.methods
+        .getVotingPower(owner, new BN(timestamp))
+        .accounts({ stakeCurrencyMint: stakeCurrencyMint })
+        .remainingAccounts(
+          [vaultPda, userStakeDetailPda].map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: false,
+          }))
+        )
+        .view();
+    const now = Math.floor(Date.now() / 1000);
+    const ownPower = await votingPowerOf(payer.publicKey, now);
+
+    // case 1: delegate the position of the TWAB test to user2
+    await program.methods
+      .delegate(new BN(1), new BN(twabLockPeriod))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        operator: user2OperatorPda,
+        previousOperator: null,
+        previousOperatorTokenAccount: null,
+      })
+      .rpc();
+    let stakeDetail = await program.account.stakeDetail.fetch(
+      userStakeDetailPda
+    );
+    assert.equal(stakeDetail.delegateTo.toBase58(), user2.publicKey.toBase58());
+    assert.equal(stakeDetail.delegationCommissionBps, 1000);
+    let user2Operator = await program.account.operator.fetch(user2OperatorPda);
+    assert.equal(user2Operator.delegatedStake.toNumber(), 100);
+
+    // delegated positions are voted by the operator, not by the staker
+    const operatorPower = await votingPowerOf(user2.publicKey, now);
+    assert.equal(operatorPower.toString(), ownPower.toString());
+    try {
+      await votingPowerOf(payer.publicKey, now);
+      assert.fail("staker should not vote a delegated position");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "InvalidStakeDetailAccount");
+    }
+
+    // case 2: delegated positions cannot be restructured
+    try {
+      await program.methods
+        .splitPosition(new BN(1), new BN(twabLockPeriod), new BN(50))
+        .accounts({
+          signer: payer.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+        })
+        .rpc();
+      assert.fail("split should fail");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "PositionDelegated");
+    }
+
+    // case 3: redelegate to payer, stake stays in the vault
+    await program.methods
+      .delegate(new BN(1), new BN(twabLockPeriod))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        operator: payerOperatorPda,
+        previousOperator: user2OperatorPda,
+        previousOperatorTokenAccount: user2TokenAccount.address,
+      })
+      .rpc();
+    user2Operator = await program.account.operator.fetch(user2OperatorPda);
+    assert.equal(user2Operator.delegatedStake.toNumber(), 0);
+    let payerOperator = await program.account.operator.fetch(payerOperatorPda);
+    assert.equal(payerOperator.delegatedStake.toNumber(), 100);
+
+    // case 4: inactive operators cannot receive delegations but can be left,
+    // only the authority sets the status, the operator its commission
+    try {
+      await program.methods
+        .setOperatorStatus(user2.publicKey, false)
+        .accounts({
+          authority: user2.publicKey,
+          stakeCurrencyMint: stakeCurrencyMint,
+        })
+        .signers([user2])
+        .rpc();
+      assert.fail("operator should not set its status");
+    } catch (error) {
+      assert.include(JSON.stringify(error), "IncorrectAuthority");
+    }
+    await program.methods
+      .setOperatorConfig(500)
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .setOperatorStatus(payer.publicKey, false)
+      .accounts({
+        authority: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+      })
+      .rpc();
+    await program.methods
+      .undelegate(new BN(1), new BN(twabLockPeriod))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        operatorTokenAccount: getAssociatedTokenAddressSync(
+          stakeCurrencyMint,
+          payer.publicKey
+        ),
+      })
+      .rpc();
+    payerOperator = await program.account.operator.fetch(payerOperatorPda);
+    assert.equal(payerOperator.delegatedStake.toNumber(), 0);
+    stakeDetail = await program.account.stakeDetail.fetch(userStakeDetailPda);
+    assert.equal(
+      stakeDetail.delegateTo.toBase58(),
+      PublicKey.default.toBase58()
+    );
+
+    // case 5: a new position can be delegated when it is staked
+    const stakeInfo = await program.account.stakerInfo.fetch(userStakePda);
+    const [newStakeDetailPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(STAKE_DETAIL_SEED),
+        userStakePda.toBytes(),
+        stakeInfo.currentId.toBuffer("le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .stake(new BN(twabLockPeriod), new BN(10))
+      .accounts({
+        signer: payer.publicKey,
+        stakeCurrencyMint: stakeCurrencyMint,
+        stakeDetailPda: newStakeDetailPda,
+        operator: user2OperatorPda,
+      })
+      .rpc();
+    stakeDetail = await program.account.stakeDetail.fetch(newStakeDetailPda);
+    assert.equal(stakeDetail.delegateTo.toBase58(), user2.publicKey.toBase58());
+    user2Operator = await program.account.operator.fetch(user2OperatorPda);
+    assert.equal(user2Operator.delegatedStake.toNumber(), 10);
+  });
+
+  it("It audit vault solvency", async () => {
+    const auditLockPeriod = 600;
+    let [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(STAKE_CONFIG_SEED), stakeCurrencyMint.toBytes()],
+      program.programId
+    );
+    let [vaultPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(VAULT_SEED),
+        configPda.toBytes(),
+        new BN(auditLockPeriod).toBuffer("le", 8),
+      ],
+      program.programId
+    );
+
+    const vault = await program.account.vault.fetch(vaultPda);
+    const vaultTokenAccount = await getAccount(
+      connection,
+      getAssociatedTokenAddressSync(stakeCurrencyMint, vaultPda, true)
+    );
+
+    const report = await program.methods
+      .auditVault(new BN(auditLockPeriod))
+      .accounts({ stakeCurrencyMint: stakeCurrencyMint })
+      .view();
+
+    assert.equal(
+      report.tokenBalance.toString(),
+      vaultTokenAccount.amount.toString()
+    );
+    assert.equal(report.totalStaked.toNumber(), vault.totalStaked.toNumber());
+    assert.equal(
+      report.totalUnbonding.toNumber(),
+      vault.totalUnbonding.toNumber()
+    );
+    assert.equal(
+      report.undistributedPenalty.toNumber(),
+      vault.undistributedPenalty.toNumber()
+    );
+    assert.equal(
+      report.surplus.toNumber(),
+      Number(vaultTokenAccount.amount) -
+        vault.totalStaked.toNumber() -
+        vault.totalUnbonding.toNumber() -
+        vault.undistributedPenalty.toNumber()
+    );
+  });
 });